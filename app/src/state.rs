@@ -1,4 +1,5 @@
 use crate::sprite_atlas::{Atlas, Sprite, Sprites};
+use rules::fact::TextFormatter;
 use std::{collections::HashSet, time::Instant};
 use winit::event::{self, WindowEvent};
 
@@ -127,7 +128,10 @@ impl State {
         if should_move_sim_forward {
             let facts = self.game_state.tick();
             // act on them
-            dbg!(&facts);
+            let formatter = TextFormatter::new(self.game_state.get_world());
+            for line in formatter.format_all(&facts) {
+                println!("{}", line);
+            }
         }
         self.last_update = Instant::now();
     }
@@ -1,5 +1,6 @@
 use regex::Regex;
-use std::ops;
+use serde::{Deserialize, Serialize};
+use std::{cell::Cell, ops};
 #[macro_use]
 extern crate lazy_static;
 
@@ -17,27 +18,71 @@ pub fn dx(x: i8) -> i8 {
     }
 }
 
-// #[cfg(not(test))]
-// use rand::prelude::*;
-// #[cfg(not(test))]
-// pub fn dx(x: i8) -> i8 {
-//     thread_rng().gen_range(1..=x)
-// }
+thread_local! {
+    // splitmix64 state of the dice of the thread, none until they are seeded.
+    static SEED: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// Seeds the dice of the current thread: the same seed yields the same rolls.
+///
+/// Until they are seeded, dice always yield their face minus 2, which keeps benchmarks and
+/// the tests of the crates using them free of randomness.
+///```
+/// dice::seed(42);
+/// let first = dice::Roll::from("10d6").roll();
+/// dice::seed(42);
+/// assert_eq!(dice::Roll::from("10d6").roll(), first);
+///```
+pub fn seed(seed: u64) {
+    SEED.with(|s| s.set(Some(seed)));
+}
+
+/// The state of the dice of the current thread, none until they are seeded.
+///
+/// Saved with a game, it is given back to [set_state](fn.set_state.html) to go on with the same rolls.
+///```
+/// dice::seed(42);
+/// dice::Roll::from("1d20").roll();
+/// let state = dice::get_state();
+/// let next = dice::Roll::from("10d6").roll();
+/// dice::set_state(state);
+/// assert_eq!(dice::Roll::from("10d6").roll(), next);
+///```
+pub fn get_state() -> Option<u64> {
+    SEED.with(|s| s.get())
+}
+
+pub fn set_state(state: Option<u64>) {
+    SEED.with(|s| s.set(state));
+}
+
+// splitmix64, small and good enough for dice.
+#[cfg(not(test))]
+fn next_random() -> Option<u64> {
+    SEED.with(|s| {
+        let state = s.get()?.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        s.set(Some(state));
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        Some(z ^ (z >> 31))
+    })
+}
 
-//uncomment this to benchmark
 #[cfg(not(test))]
 pub fn dx(x: i8) -> i8 {
-    match x {
-        0 => 0,
-        1 => 1,
-        x => x - 2,
+    match (x, next_random()) {
+        (0, _) => 0,
+        (1, _) => 1,
+        (x, Some(random)) => 1 + (random % x as u64) as i8,
+        (x, None) => x - 2,
     }
 }
 
 /// Used internally by [Roll](struct.Roll.html)
 ///
 /// Represents "1d6+1".
-#[derive(Clone, Default, Debug, PartialEq, Eq)]
+#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Bonus {
     pub nb_dice: i8,
     pub face: i8,
@@ -89,7 +134,7 @@ impl Bonus {
 /// A roll can also be rolled. The value can be memoized, to allow carrying the result of one roll around the system.
 /// Rolls always yield half the dice face rounded down in tests (a d3 will yield 1 for instance). It allows for cleaner unit tests with no need for a seed.
 ///
-#[derive(Clone, Default, Debug, PartialEq, Eq)]
+#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Roll {
     bonuses: Vec<(String, Bonus)>,
     pub value: i64,
@@ -202,6 +247,55 @@ impl Roll {
         self.bonuses.iter().map(|(_, bonus)| bonus.roll()).sum()
     }
 
+    /// The total rolled on average, without rolling the dice.
+    ///```
+    ///use dice::Roll;
+    ///assert_eq!(Roll::from("2d6+1").average(), 8.0);
+    ///assert_eq!((Roll::d("", 1, 4) + Roll::flat("str", 3)).average(), 5.5);
+    ///```
+    pub fn average(&self) -> f64 {
+        self.distribution()
+            .iter()
+            .map(|(total, chance)| *total as f64 * chance)
+            .sum()
+    }
+
+    /// Every total the roll can yield with its chance, sorted by total.
+    ///```
+    ///use dice::Roll;
+    ///let distribution = Roll::from("2d4").distribution();
+    ///assert_eq!(distribution.len(), 7);
+    ///assert_eq!(distribution[0], (2, 1.0 / 16.0));
+    ///assert_eq!(distribution[3], (5, 4.0 / 16.0));
+    ///```
+    pub fn distribution(&self) -> Vec<(i64, f64)> {
+        let mut totals: Vec<(i64, f64)> = vec![(0, 1.0)];
+        for (_, bonus) in self.bonuses.iter() {
+            for _ in 0..bonus.nb_dice {
+                let faces: Vec<i64> = match bonus.face {
+                    0 => vec![0],
+                    face => (1..=face as i64).collect(),
+                };
+                let chance = 1.0 / faces.len() as f64;
+                let mut next: Vec<(i64, f64)> = vec![];
+                for (total, p) in totals.iter() {
+                    for face in faces.iter() {
+                        match next.iter_mut().find(|(t, _)| *t == total + face) {
+                            Some((_, q)) => *q += p * chance,
+                            None => next.push((total + face, p * chance)),
+                        }
+                    }
+                }
+                totals = next;
+            }
+            for (total, _) in totals.iter_mut() {
+                *total += bonus.flat_bonus;
+            }
+        }
+        totals.sort_by_key(|(total, _)| *total);
+        totals
+    }
+
     /// Get the detail about a bonus.
     ///
     /// Useful when some rules interact with another.
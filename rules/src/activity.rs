@@ -1,59 +1,405 @@
-use crate::{character::Character, fact, world::World};
+use crate::{
+    ai::{plan_turn, Utility},
+    character::{feat::FeatActivity, skill::Skill, spellcasting::SpellSlot, Character},
+    fact::{self, Fact},
+    inventory::get_held_consumables,
+    item::{consumable::ConsumableEffect, AnyItem},
+    reaction::{offer_reactions, Trigger},
+    status::{end_status, StatusType},
+    utils::get_wielded_weapons,
+    world::World,
+};
 
-mod attack;
-mod bless;
-mod find_target;
-mod magicmissile;
+mod activate_talisman;
+pub(crate) mod attack;
+mod battle_medicine;
+mod cast_spell;
+mod demoralize;
+mod double_slice;
+mod drink_potion;
+pub(crate) mod find_target;
+mod grip;
+mod interact;
 mod pass;
+mod power_attack;
+mod raise_shield;
+mod release;
+mod stand;
+mod stride;
+mod sudden_charge;
+mod sustain_spell;
+mod take_cover;
+mod throw_bomb;
+mod trip;
 
 use std::fmt;
-// Value AI:
-// 0 cannot be cast or pointless
-// 10 gives an advantage
-// 20 gives a major advantage
+
+// p15 traits of an action, the ones other rules care about.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ActionTrait {
+    Attack,
+    Concentrate,
+    Manipulate,
+    Move,
+    Flourish,
+    Open,
+}
+
+// p17 only one flourish per turn, an open action must be the first one of the turn.
+pub(crate) fn check_traits(traits: &[ActionTrait], character: &Character) -> bool {
+    let flourished = traits.contains(&ActionTrait::Flourish) && character.flourish_used;
+    let opened = traits.contains(&ActionTrait::Open) && character.actions_used > 0;
+    !flourished && !opened
+}
+
+/**
+The AI weighs the activities by their utility, the expected outcome of their dice.
+Activities whose worth only shows in the ones that follow, like a Stride, are worth nothing by themselves:
+the AI plans the whole turn.
+*/
 pub trait Activity: fmt::Debug {
     fn can_be_used(&self, character: &Character, _context: &World) -> bool {
         character.hp > 0
     }
-    fn ai_playing_value(&self, character: &Character, context: &World) -> i64;
+    // None for the activities left to players, like dropping a weapon.
+    fn ai_utility(&self, character: &Character, context: &World) -> Option<Utility>;
     fn resolve(&mut self, character: &Character, context: &mut World, facts: &mut fact::Facts);
     fn get_name(&self) -> &str;
     fn get_cost(&self) -> i64 {
         1
     }
+    fn get_traits(&self) -> Vec<ActionTrait> {
+        vec![]
+    }
 }
 
 /**
-Right now doesnt care about the specificity of a character.
-Would be based on a list of feats and the equipment.
+The activities come from the character: a Strike per wielded weapon, its spells and the
+ones it can sustain, the skill actions of its trained skills and the activities granted by its feats.
+Creatures from a stat block get one activity per Strike instead of their weapons.
  */
 impl Character {
-    fn get_activities(&self) -> Vec<Box<dyn Activity>> {
-        vec![
-            Box::new(magicmissile::Action::new()),
-            Box::new(attack::Action::new()),
-            Box::new(bless::Action::new()),
-        ]
+    pub(crate) fn get_activities(&self, world: &World) -> Vec<Box<dyn Activity>> {
+        let mut activities: Vec<Box<dyn Activity>> = match &self.creature {
+            Some(creature) => creature
+                .strikes
+                .iter()
+                .map(|s| Box::new(attack::Action::from_strike(s.clone())) as Box<dyn Activity>)
+                .collect(),
+            None => get_wielded_weapons(self, world)
+                .into_iter()
+                .map(|(id, w)| Box::new(attack::Action::from_weapon(&id, w)) as Box<dyn Activity>)
+                .collect(),
+        };
+        for (index, entry) in self.spellcasting.iter().enumerate() {
+            for castable in entry.get_castable(&world.spellbook, self.level) {
+                let data = match world.spellbook.get(&castable.spell) {
+                    Ok(data) => data,
+                    Err(_) => continue,
+                };
+                for actions in data.actions.get_choices() {
+                    activities.push(Box::new(cast_spell::Action::new(
+                        index,
+                        &castable.spell,
+                        castable.rank,
+                        castable.slot,
+                        data,
+                        actions,
+                    )));
+                }
+            }
+        }
+        for (index, emanation) in self.emanations.iter().enumerate() {
+            if emanation.sustain_growth > 0 {
+                activities.push(Box::new(sustain_spell::Action::new(index, &emanation.name)));
+            }
+        }
+        activities.extend(self.get_item_activities(world));
+        if self.is_trained(Skill::Intimidation) {
+            activities.push(Box::new(demoralize::Action::new()));
+        }
+        if self.is_trained(Skill::Athletics) {
+            activities.push(Box::new(trip::Action::new()));
+        }
+        for feat in self.feats.iter() {
+            activities.extend(feat.activities.iter().map(|a| feat_activity(*a)));
+        }
+        activities.push(Box::new(stride::Action::new()));
+        activities.push(Box::new(stand::Action::new()));
+        activities.push(Box::new(raise_shield::Action::new()));
+        activities.push(Box::new(take_cover::Action::new()));
+        activities
     }
 }
 
-/**
-Return a new
-*/
-pub fn select_best_action<'a>(
-    character: &'a Character,
+impl Character {
+    /**
+    p272 the held consumables are activated, the ones in the inventory have to be drawn first,
+    except for talismans which are affixed to the gear.
+    Only one activity per kind of item, the first one carried is used.
+    */
+    fn get_item_activities(&self, world: &World) -> Vec<Box<dyn Activity>> {
+        let mut activities: Vec<Box<dyn Activity>> = vec![];
+        let mut names: Vec<String> = vec![];
+        let mut push = |activity: Box<dyn Activity>, activities: &mut Vec<Box<dyn Activity>>| {
+            if !names.iter().any(|n| n == activity.get_name()) {
+                names.push(String::from(activity.get_name()));
+                activities.push(activity);
+            }
+        };
+        for (id, item) in get_held_consumables(self, world) {
+            match &item.effect {
+                ConsumableEffect::Heal { .. } => push(
+                    Box::new(drink_potion::Action::new(&id, item)),
+                    &mut activities,
+                ),
+                ConsumableEffect::Bomb { .. } => push(
+                    Box::new(throw_bomb::Action::new(&id, item)),
+                    &mut activities,
+                ),
+                ConsumableEffect::Scroll { spell, rank } => {
+                    let data = match world.spellbook.get(spell) {
+                        Ok(data) => data,
+                        Err(_) => continue,
+                    };
+                    let entry = self
+                        .spellcasting
+                        .iter()
+                        .position(|e| data.traditions.contains(&e.tradition));
+                    if let Some(entry) = entry {
+                        for actions in data.actions.get_choices() {
+                            let cast = cast_spell::Action::new(
+                                entry,
+                                spell,
+                                *rank,
+                                SpellSlot::Scroll,
+                                data,
+                                actions,
+                            )
+                            .with_scroll(&id);
+                            push(Box::new(cast), &mut activities);
+                        }
+                    }
+                }
+                ConsumableEffect::Talisman(_) => {}
+            }
+        }
+        for id in self.inventory.iter() {
+            match world.items.get(id) {
+                Some(AnyItem::ConsumableItem(talisman))
+                    if matches!(talisman.effect, ConsumableEffect::Talisman(_)) =>
+                {
+                    push(
+                        Box::new(activate_talisman::Action::new(id, talisman)),
+                        &mut activities,
+                    )
+                }
+                Some(item) => push(
+                    Box::new(interact::Action::new(interact::Interaction::Draw, id, item)),
+                    &mut activities,
+                ),
+                None => {}
+            }
+        }
+        for id in vec![&self.loadout.right_hand, &self.loadout.left_hand]
+            .into_iter()
+            .flatten()
+        {
+            let item = match world.items.get(id) {
+                Some(item) => item,
+                None => continue,
+            };
+            push(
+                Box::new(interact::Action::new(interact::Interaction::Stow, id, item)),
+                &mut activities,
+            );
+            push(
+                Box::new(release::Action::new(id, item, self)),
+                &mut activities,
+            );
+            if let AnyItem::WeaponItem(weapon) = item {
+                if weapon.get_two_hand_die().is_some() && !self.loadout.is_held_in_both_hands(id) {
+                    push(Box::new(grip::Action::new(id, weapon)), &mut activities);
+                }
+            }
+        }
+        activities
+    }
+}
+
+fn feat_activity(activity: FeatActivity) -> Box<dyn Activity> {
+    match activity {
+        FeatActivity::PowerAttack => Box::new(power_attack::Action::new()),
+        FeatActivity::SuddenCharge => Box::new(sudden_charge::Action::new()),
+        FeatActivity::DoubleSlice => Box::new(double_slice::Action::new()),
+        FeatActivity::BattleMedicine => Box::new(battle_medicine::Action::new()),
+    }
+}
+
+// the first activity of the turn planned by the AI, a Pass when nothing is worth doing.
+pub fn select_best_action(
+    character: &Character,
+    action_left: i64,
+    world: &mut World,
+) -> Box<dyn Activity> {
+    let best = plan_turn(character, action_left, world).first;
+    take_activity(character.get_activities(world), best)
+}
+
+pub(crate) fn can_choose(
+    activity: &dyn Activity,
+    character: &Character,
     action_left: i64,
-    world: &'a World,
+    world: &World,
+) -> bool {
+    activity.can_be_used(character, world) && activity.get_cost() <= action_left
+}
+
+pub(crate) fn take_activity(
+    mut activities: Vec<Box<dyn Activity>>,
+    index: Option<usize>,
 ) -> Box<dyn Activity> {
-    let activities = character.get_activities();
-    let best = activities
-        .into_iter()
-        .filter(|act| act.can_be_used(character, world))
-        .filter(|act| act.get_cost() <= action_left)
-        .map(|act| (act.ai_playing_value(character, world), act))
-        .max_by(|a, b| a.0.cmp(&b.0));
-    match best {
-        Some((_, c)) => c,
+    match index {
+        Some(index) => activities.swap_remove(index),
         None => Box::new(pass::Action::new()),
     }
 }
+
+/**
+Resolves an activity, after giving other creatures a chance to react to it.
+If a reaction takes the character out, the activity is lost.
+*/
+pub fn perform(
+    activity: &mut Box<dyn Activity>,
+    character: &Character,
+    world: &mut World,
+    facts: &mut fact::Facts,
+) {
+    let traits = activity.get_traits();
+    facts.push(Fact::Activity {
+        character_id: character.id.clone(),
+        name: String::from(activity.get_name()),
+        cost: activity.get_cost(),
+    });
+    let actor = world.get_mut_character(&character.id);
+    actor.actions_used += activity.get_cost();
+    if traits.contains(&ActionTrait::Flourish) {
+        actor.flourish_used = true;
+    }
+    // p471 taking cover ends when the creature attacks or moves.
+    if traits.contains(&ActionTrait::Attack) || traits.contains(&ActionTrait::Move) {
+        end_status(&character.id, StatusType::TakingCover, world, facts);
+    }
+    if traits.contains(&ActionTrait::Manipulate) || traits.contains(&ActionTrait::Move) {
+        let mut trigger = Trigger::ManipulateOrMove {
+            actor: character.id.clone(),
+        };
+        offer_reactions(&mut trigger, world, facts);
+    }
+    let character = world.get_character(&character.id).clone();
+    if character.hp <= 0 {
+        return;
+    }
+    activity.resolve(&character, world, facts);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        character::{
+            abilities::Ability,
+            builder::CharacterOptions,
+            proficiency::Proficiency,
+            spellcasting::{CastingType, SpellcastingEntry},
+        },
+        position::Position,
+        spell::Tradition,
+        status::StatusType,
+    };
+
+    fn get_names(character: &Character, world: &World) -> Vec<String> {
+        character
+            .get_activities(world)
+            .iter()
+            .map(|a| String::from(a.get_name()))
+            .collect()
+    }
+
+    #[test]
+    fn derived_activities() {
+        let mut world = World::new();
+        let mut c = Character::new(String::from("Valeros"), String::from("heroes"), 20);
+        // the fists, when the hands are empty
+        assert!(get_names(&c, &world)[0].starts_with("Strike ("));
+        c.loadout.right_hand = world.spawn_item("longsword").ok();
+        c.loadout.left_hand = world.spawn_item("shortsword").ok();
+        c.proficiencies
+            .skills
+            .insert(Skill::Athletics, Proficiency::Trained);
+        let mut entry = SpellcastingEntry::new(
+            "Divine Spontaneous Spells",
+            Tradition::Divine,
+            CastingType::Spontaneous,
+            Ability::Charisma,
+        )
+        .slots(1, 1);
+        entry.learn(&world.spellbook, "bless").unwrap();
+        c.spellcasting.push(entry);
+        let options = CharacterOptions::core();
+        c.feats
+            .push(options.get_feat("double-slice").unwrap().clone());
+        assert_eq!(
+            get_names(&c, &world),
+            vec![
+                "Strike (Longsword)",
+                "Strike (Shortsword)",
+                "Bless",
+                "Stow Longsword",
+                "Release Longsword",
+                "Stow Shortsword",
+                "Release Shortsword",
+                "Trip",
+                "Double Slice",
+                "Stride",
+                "Stand",
+                "Raise Shield",
+                "Take Cover"
+            ]
+        );
+    }
+
+    #[test]
+    fn traits() {
+        let mut world = World::new();
+        let id = world.add_character_at("Valeros", "heroes", 20, Position::new(0, 0));
+        let enemy = world.add_character_at("Goblin", "goblins", 20, Position::new(0, 10));
+        let mut charge: Box<dyn Activity> = Box::new(sudden_charge::Action::new());
+        let c = world.get_character(&id).clone();
+        assert!(charge.can_be_used(&c, &world));
+        let mut facts = fact::Facts::new();
+        perform(&mut charge, &c, &mut world, &mut facts);
+        let c = world.get_character(&id).clone();
+        assert_eq!((c.actions_used, c.flourish_used), (2, true));
+        assert!(c
+            .position
+            .is_within_reach(&world.get_character(&enemy).position, 5));
+        assert!(world.get_character(&enemy).hp < 20);
+        // open and flourish
+        assert!(!charge.can_be_used(&c, &world));
+        // a second flourish in the same turn
+        let power_attack = power_attack::Action::new();
+        assert!(!power_attack.can_be_used(&c, &world));
+        world.get_mut_character(&id).start_turn();
+        assert!(charge.can_be_used(world.get_character(&id), &world));
+        assert!(power_attack.can_be_used(world.get_character(&id), &world));
+
+        let mut trip: Box<dyn Activity> = Box::new(trip::Action::new());
+        let c = world.get_character(&id).clone();
+        perform(&mut trip, &c, &mut world, &mut facts);
+        assert!(world.get_character(&enemy).has_status(StatusType::Prone));
+        let goblin = world.get_character(&enemy).clone();
+        let stand = stand::Action::new();
+        assert!(stand.can_be_used(&goblin, &world));
+    }
+}
@@ -0,0 +1,79 @@
+use crate::{
+    ai::Utility,
+    character::Character,
+    fact,
+    inventory::use_charge,
+    item::{
+        consumable::{ConsumableEffect, ConsumableItem},
+        AnyItem, ItemId,
+    },
+    status::{apply_status, Duration, StatusEffect, StatusType},
+    world::World,
+};
+
+use super::Activity;
+
+/**
+p565 activating a talisman affixed to the gear is a free action, it grants its effect until
+the next turn and is used up.
+*/
+#[derive(Clone, Debug)]
+pub struct Action {
+    name: String,
+    item_id: ItemId,
+}
+
+impl Action {
+    pub fn new(item_id: &str, talisman: &ConsumableItem) -> Self {
+        Self {
+            name: format!("Activate {}", talisman.info.name),
+            item_id: String::from(item_id),
+        }
+    }
+
+    fn get_status(&self, world: &World) -> Option<StatusType> {
+        match world.items.get(&self.item_id) {
+            Some(AnyItem::ConsumableItem(ConsumableItem {
+                effect: ConsumableEffect::Talisman(status),
+                ..
+            })) => Some(*status),
+            _ => None,
+        }
+    }
+}
+
+impl Activity for Action {
+    fn can_be_used(&self, character: &Character, world: &World) -> bool {
+        character.hp > 0
+            && character.inventory.contains(&self.item_id)
+            && self
+                .get_status(world)
+                .is_some_and(|status| !character.has_status(status))
+    }
+
+    // worth what it adds to the Strikes that follow it in the turn.
+    fn ai_utility(&self, _character: &Character, _world: &World) -> Option<Utility> {
+        Some(Utility::default())
+    }
+
+    fn resolve(&mut self, character: &Character, world: &mut World, facts: &mut fact::Facts) {
+        let status_type = match self.get_status(world) {
+            Some(status) => status,
+            None => return,
+        };
+        use_charge(&character.id, &self.item_id, world);
+        let effect = StatusEffect {
+            status_type,
+            duration: Duration::StartOfNextTurn,
+        };
+        apply_status(&character.id, effect, world, facts);
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_cost(&self) -> i64 {
+        0
+    }
+}
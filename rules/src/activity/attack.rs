@@ -1,102 +1,288 @@
 use dice::Roll;
 
 use crate::{
-    character::Character,
-    fact,
+    ai::{get_threat, Utility},
+    character::{abilities::Ability, creature::CreatureStrike, Character},
+    check::{flat_check, get_chances, DegreeOfSuccess},
+    cover::{get_cover, get_flat_check_dc},
+    fact::{self, Fact},
     item::{
         weapon::{CombatProperties, DamageType, WeaponItem},
-        AnyItem,
+        ItemId,
     },
-    rules::Rule,
+    rules::{CombatContext, Rule},
+    spell::get_emanation_statuses,
     status::StatusType,
     utils::get_armor,
     world::World,
 };
-use crate::{timeline::get_modifier, utils::get_active_weapon};
+use crate::{
+    damage::{adjust_strike_damage, apply_damage, Damage},
+    defense::{compute_ac, is_flat_footed},
+    flanking::is_flanking,
+    timeline::{get_modifier, CharacterId},
+    utils::{get_melee_weapon, get_weapon, get_wielded_weapons},
+};
 
-use super::{find_target::find_first_conscious_enemy, Activity};
+use super::{
+    find_target::{find_conscious_enemies, find_conscious_enemies_in_reach},
+    ActionTrait, Activity,
+};
 
+/**
+Strike with one of the wielded weapons, or with one of the Strikes of a creature stat block.
+*/
 #[derive(Clone, Debug)]
-pub struct Action;
+pub struct Action {
+    name: String,
+    weapon_id: ItemId,
+    creature_strike: Option<CreatureStrike>,
+}
 
 impl Action {
-    pub fn new() -> Self {
-        Self {}
+    pub fn from_weapon(id: &str, weapon: &WeaponItem) -> Self {
+        Self {
+            name: format!("Strike ({})", weapon.info.name),
+            weapon_id: String::from(id),
+            creature_strike: None,
+        }
+    }
+
+    pub fn from_strike(strike: CreatureStrike) -> Self {
+        Self {
+            name: format!("Strike ({})", strike.weapon.info.name),
+            weapon_id: strike.weapon.info.id.clone(),
+            creature_strike: Some(strike),
+        }
+    }
+
+    fn get_weapon<'a>(&'a self, world: &'a World) -> &'a WeaponItem {
+        match &self.creature_strike {
+            Some(s) => &s.weapon,
+            None => get_weapon(&self.weapon_id, world),
+        }
     }
 }
 
 impl Activity for Action {
-    fn ai_playing_value(&self, _character: &Character, _context: &World) -> i64 {
-        Roll::d("", 1, 20).roll()
+    fn can_be_used(&self, character: &Character, world: &World) -> bool {
+        character.hp > 0 && find_target(character, self.get_weapon(world), world).is_some()
+    }
+
+    fn ai_utility(&self, character: &Character, world: &World) -> Option<Utility> {
+        let weapon = self.get_weapon(world);
+        let best = find_best_target(character, weapon, self.creature_strike.as_ref(), 0, world);
+        Some(best.map_or(Utility::default(), |(_, utility)| utility))
     }
 
     fn resolve<'lworld>(&mut self, source: &Character, world: &mut World, facts: &mut fact::Facts) {
-        let target_id = find_first_conscious_enemy(&source.party, world);
-        match target_id {
-            None => {
-                return;
-            }
-            Some(id) => {
-                let target: &Character = world.get_character(&id);
-                let weapon = get_active_weapon(&source, world);
-
-                let attack_roll = compute_attack_roll(&weapon, source, target);
-                let ac_bonus = compute_ac(target, world);
-
-                if ac_bonus > attack_roll.value {
-                    facts.info(&format!(
-                        "\t{} missed {} with {} ({} = {} vs {} AC)",
-                        source.name,
-                        target.name,
-                        weapon.info.name,
-                        attack_roll.details,
-                        attack_roll.value,
-                        ac_bonus
-                    ));
-                    return;
-                }
-                // p278 critical hits
-                let is_critical = attack_roll.natural_20 || (attack_roll.value - ac_bonus) >= 10;
-                facts.info(&format!(
-                    "\t{} {}hits {} with {} ({} = {} vs {} AC)",
-                    source.name,
-                    if is_critical { "critically " } else { "" },
-                    target.name,
-                    weapon.info.name,
-                    attack_roll.details,
-                    attack_roll.value,
-                    ac_bonus
-                ));
-                let dmg = compute_damage_roll(&weapon, source, target, world, is_critical);
-                let verb = match dmg.damage_type {
-                    DamageType::Bludgeoning => "was bludgeoned for",
-                    DamageType::Piercing => "was pierced for",
-                    DamageType::Slashing => "was slashed for",
-                };
-                facts.info(&format!(
-                    "\t{} {} {} damage ({})",
-                    target.name, verb, dmg.value, dmg.details,
-                ));
-
-                // apply damage and statuses and loosing objects and...
-                let target: &mut Character = world.get_mut_character(&id); // could this be avoided? maybe
-                (*target).sub_hp(dmg.value);
-            }
+        let weapon = self.get_weapon(world).clone();
+        let best = find_best_target(source, &weapon, self.creature_strike.as_ref(), 0, world);
+        if let Some((id, _)) = best {
+            strike_with(
+                source,
+                &id,
+                &weapon,
+                self.creature_strike.as_ref(),
+                StrikeModifiers::default(),
+                world,
+                facts,
+            );
         }
     }
 
+    fn get_traits(&self) -> Vec<ActionTrait> {
+        vec![ActionTrait::Attack]
+    }
+
     fn get_name(&self) -> &str {
-        "Attack"
+        &self.name
+    }
+}
+
+// melee weapons need the target within reach, ranged weapons can shoot anyone in line of effect.
+fn find_targets(source: &Character, weapon: &WeaponItem, world: &World) -> Vec<CharacterId> {
+    if weapon.is_ranged {
+        find_conscious_enemies(source, world)
+    } else {
+        find_conscious_enemies_in_reach(source, weapon.get_reach(), world)
     }
 }
 
+pub(super) fn find_target(
+    source: &Character,
+    weapon: &WeaponItem,
+    world: &World,
+) -> Option<CharacterId> {
+    find_targets(source, weapon, world).into_iter().next()
+}
+
+// the target the Strike is worth the most against, to the personality of the character.
+pub(super) fn find_best_target(
+    source: &Character,
+    weapon: &WeaponItem,
+    creature_strike: Option<&CreatureStrike>,
+    extra_dice: i64,
+    world: &World,
+) -> Option<(CharacterId, Utility)> {
+    let mut best: Option<(CharacterId, Utility)> = None;
+    for id in find_targets(source, weapon, world) {
+        let target = world.get_character(&id);
+        let modifiers = StrikeModifiers {
+            extra_dice,
+            ..StrikeModifiers::default()
+        };
+        let odds = expect_strike(source, target, weapon, creature_strike, modifiers, world);
+        let utility = get_strike_utility(source, target, odds, world);
+        let score = utility.score(&source.personality);
+        if best
+            .as_ref()
+            .is_none_or(|(_, u)| score > u.score(&source.personality))
+        {
+            best = Some((id, utility));
+        }
+    }
+    best
+}
+
+// a target taken out deals no damage during its next turn.
+pub(super) fn get_strike_utility(
+    source: &Character,
+    target: &Character,
+    odds: StrikeOdds,
+    world: &World,
+) -> Utility {
+    Utility {
+        damage: odds.damage,
+        kill: odds.kill,
+        threat: if odds.kill > 0.0 {
+            odds.kill * get_threat(target, source, world)
+        } else {
+            0.0
+        },
+        ..Utility::default()
+    }
+}
+
+/**
+A single Strike with the melee weapon. p471
+Shared by Sudden Charge and reactions like Attack of Opportunity.
+*/
+pub fn strike(source: &Character, target_id: &str, world: &mut World, facts: &mut fact::Facts) {
+    let (weapon, creature_strike) = match get_melee_weapon(source, world) {
+        Some((weapon, creature_strike)) => (weapon.clone(), creature_strike.cloned()),
+        None => return,
+    };
+    strike_with(
+        source,
+        target_id,
+        &weapon,
+        creature_strike.as_ref(),
+        StrikeModifiers::default(),
+        world,
+        facts,
+    );
+}
+
+// what an activity changes to its Strikes.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct StrikeModifiers {
+    // weapon damage dice, like the ones of Power Attack.
+    pub extra_dice: i64,
+    // taken from the attack roll, like the one of the second Strike of Double Slice.
+    pub penalty: i64,
+}
+
+/**
+Returns the degree of success of the attack roll, missing by 10 or more is a critical failure.
+*/
+pub(super) fn strike_with(
+    source: &Character,
+    target_id: &str,
+    weapon: &WeaponItem,
+    creature_strike: Option<&CreatureStrike>,
+    modifiers: StrikeModifiers,
+    world: &mut World,
+    facts: &mut fact::Facts,
+) -> DegreeOfSuccess {
+    let target: &Character = world.get_character(target_id);
+
+    // p618 concealed and hidden targets may be missed before rolling.
+    if let Some(dc) = get_flat_check_dc(source, target, world) {
+        if !flat_check(&source.id, dc, facts) {
+            return DegreeOfSuccess::Failure;
+        }
+    }
+    let attack_roll = compute_attack_roll(
+        weapon,
+        creature_strike,
+        modifiers.penalty,
+        source,
+        target,
+        world,
+    );
+    let ac_bonus = get_strike_ac(source, target, weapon, world);
+    let degree = get_strike_degree(&attack_roll, ac_bonus);
+    facts.push(Fact::Attack {
+        character_id: source.id.clone(),
+        target_id: String::from(target_id),
+        weapon: weapon.info.name.clone(),
+        total: attack_roll.value,
+        details: attack_roll.details.clone(),
+        ac: ac_bonus,
+        degree,
+    });
+    if !degree.is_success() {
+        return degree;
+    }
+    let is_critical = degree == DegreeOfSuccess::CriticalSuccess;
+    let dmg = compute_damage_roll(
+        weapon,
+        creature_strike,
+        modifiers.extra_dice,
+        source,
+        target,
+        world,
+        is_critical,
+    );
+
+    // apply damage and statuses and loosing objects and...
+    let damage = Damage::new(dmg.value)
+        .of_type(dmg.damage_type)
+        .details(&dmg.details);
+    apply_damage(&source.id, target_id, damage, world, facts);
+    degree
+}
+
+// the AC of the target against a Strike of the source, with flanking and cover.
+fn get_strike_ac(
+    source: &Character,
+    target: &Character,
+    weapon: &WeaponItem,
+    world: &World,
+) -> i64 {
+    let mut ac = compute_ac(target, world);
+    // p476 flanked creatures are flat-footed to the melee attacks of the flanking creatures.
+    if !weapon.is_ranged && !is_flat_footed(target) && is_flanking(source, target, world) {
+        ac = ac + Roll::flat("flanked", -2);
+    }
+    ac.roll() + get_cover(source, target, weapon.is_ranged, world).get_ac_bonus()
+}
+
+// p278 critical hits by 10 or more, a natural 20 or a natural 1 changes the degree by one step.
+fn get_strike_degree(attack_roll: &AttackRollResults, ac: i64) -> DegreeOfSuccess {
+    DegreeOfSuccess::from_check(attack_roll.value, attack_roll.natural, ac)
+}
+
 struct AttackRollResults {
     value: i64,
     details: String,
-    natural_20: bool,
+    // the d20 alone
+    natural: i64,
 }
 
 pub struct DamageRollResults {
+    // after the weaknesses and resistances of every damage type.
     pub value: i64,
     pub damage_type: DamageType,
     pub is_critical: bool,
@@ -105,63 +291,133 @@ pub struct DamageRollResults {
 
 fn compute_attack_roll(
     weapon: &WeaponItem,
+    creature_strike: Option<&CreatureStrike>,
+    penalty: i64,
     source: &Character,
     _target: &Character,
+    world: &World,
 ) -> AttackRollResults {
-    let roll = Roll::d("", 1, 20);
-    // strength or dexterity modifier
-    let ability_score = if weapon.is_ranged {
+    let natural = Roll::d("", 1, 20).roll();
+    let mut total = Roll::flat("natural", natural)
+        + get_attack_modifier(weapon, creature_strike, penalty, source, world);
+    AttackRollResults {
+        value: total.resolve(),
+        details: total.to_string(),
+        natural,
+    }
+}
+
+// everything added to the d20 of the attack roll, as flat bonuses.
+fn get_attack_modifier(
+    weapon: &WeaponItem,
+    creature_strike: Option<&CreatureStrike>,
+    penalty: i64,
+    source: &Character,
+    world: &World,
+) -> Roll {
+    // strength or dexterity modifier, already part of a stat block attack bonus
+    let ability_score = if let Some(s) = creature_strike {
+        Roll::flat("attack", s.attack_bonus)
+    } else if weapon.is_ranged {
         Roll::flat("dex", get_modifier(source.ability_score.dexterity))
     } else {
         Roll::flat("str", get_modifier(source.ability_score.strength))
     };
 
     // @todo move this to a rule.
-    let status_bonus = if source.has_status(StatusType::Bless) {
+    let blessed = source.has_status(StatusType::Bless)
+        || get_emanation_statuses(source, world).contains(&StatusType::Bless);
+    let status_bonus = if blessed {
         Roll::flat("bless", 1)
     } else {
         Roll::default()
     };
-    let item_bonus = Roll::default();
+    // p446 proficiency with the weapon category
+    let proficiency = if creature_strike.is_some() {
+        Roll::default()
+    } else {
+        let rank = source.proficiencies.get_weapon(weapon.category);
+        Roll::flat("proficiency", rank.bonus(source.level))
+    };
+    // p580 weapon potency rune, p565 or a potency crystal
+    let item_bonus = if source.has_status(StatusType::PotencyCrystal) {
+        Roll::flat("potency", (weapon.runes.potency as i64).max(1))
+    } else {
+        Roll::flat("potency", weapon.runes.potency as i64)
+    };
+    // p622 prone
+    let circumstance = if source.has_status(StatusType::Prone) {
+        Roll::flat("prone", -2)
+    } else {
+        Roll::default()
+    };
+    let penalty = if penalty > 0 {
+        Roll::flat("penalty", -penalty)
+    } else {
+        Roll::default()
+    };
 
-    // total
-    let mut total = roll + ability_score + item_bonus + status_bonus;
-    AttackRollResults {
-        value: total.resolve(),
-        details: total.to_string(),
-        natural_20: total.resolve() == 20,
+    ability_score
+        + proficiency
+        + item_bonus
+        + status_bonus
+        + circumstance
+        + penalty
+        + source.get_ability_status_penalty(if weapon.is_ranged {
+            Ability::Dexterity
+        } else {
+            Ability::Strength
+        })
+}
+
+// p283 the two-hand die replaces the weapon die when the weapon is held in both hands.
+fn get_damage_die(weapon: &WeaponItem, source: &Character) -> i64 {
+    match weapon.get_two_hand_die() {
+        Some(faces) if source.loadout.is_held_in_both_hands(&weapon.info.id) => faces,
+        _ => weapon.damage.dice_faces,
     }
 }
 
 fn compute_damage_roll(
     weapon: &WeaponItem,
+    creature_strike: Option<&CreatureStrike>,
+    extra_dice: i64,
     source: &Character,
     target: &Character,
     world: &World,
     is_critical: bool,
 ) -> DamageRollResults {
-    let CombatProperties {
-        dice_faces,
-        nb_dice,
-        ..
-    } = weapon.damage;
-
-    let mut rules = vec![Rule::StrengthModDamage];
-    rules.extend(weapon.info.rules.clone());
-    rules.extend(get_armor(target, world).info.rules.clone());
-
-    let mut pre_crit_roll = Roll::d("weapon", nb_dice, dice_faces);
-    pre_crit_roll = world
-        .rules
-        .dmg_pre_crit(&rules, pre_crit_roll, source, world);
-
-    let mut post_crit_roll =
-        world
-            .rules
-            .dmg_post_crit(&weapon.info.rules, Roll::default(), source, world);
-
-    let total =
-        pre_crit_roll.resolve() * if is_critical { 2 } else { 1 } + post_crit_roll.resolve();
+    let DamageRolls {
+        mut pre_crit_roll,
+        mut post_crit_roll,
+        mut extra,
+        ghost_touch,
+    } = get_damage_rolls(
+        weapon,
+        creature_strike,
+        extra_dice,
+        source,
+        target,
+        world,
+        is_critical,
+    );
+    let multiplier = if is_critical { 2 } else { 1 };
+    let mut total = adjust_strike_damage(
+        target,
+        weapon.damage.damage_type,
+        pre_crit_roll.resolve() * multiplier + post_crit_roll.resolve(),
+        ghost_touch,
+    );
+    let mut precrit = pre_crit_roll.to_string();
+    for (damage_type, roll) in extra.iter_mut() {
+        total += adjust_strike_damage(
+            target,
+            *damage_type,
+            roll.resolve() * multiplier,
+            ghost_touch,
+        );
+        precrit += &format!(" + {} {:?}", roll.to_string(), damage_type).to_lowercase();
+    }
 
     let pc_str = post_crit_roll.to_string();
 
@@ -171,34 +427,437 @@ fn compute_damage_roll(
         is_critical,
         details: if is_critical {
             format!(
-                "critical 2x({precrit}) + {postcrit} = {total} dmg",
-                precrit = pre_crit_roll.to_string(),
-                postcrit = post_crit_roll.to_string(),
-                total = total
+                "critical 2x({precrit}){sep}{postcrit}",
+                precrit = precrit,
+                sep = if pc_str.is_empty() { "" } else { " + " },
+                postcrit = pc_str,
             )
         } else {
             format!(
-                "{precrit}{sep}{postcrit} = {total} dmg",
-                precrit = pre_crit_roll.to_string(),
-                sep = if pc_str != "" { " + " } else { "" },
+                "{precrit}{sep}{postcrit}",
+                precrit = precrit,
+                sep = if pc_str.is_empty() { "" } else { " + " },
                 postcrit = pc_str,
-                total = total
             )
         },
     }
 }
 
-fn compute_ac(target: &Character, world: &World) -> i64 {
-    let ac_bonus_armor = {
-        if let Some(armor_id) = &target.loadout.armor {
-            let item = world.items.get(armor_id).expect("lost armor");
-            match item {
-                AnyItem::ArmorItem(a) => a.ac_bonus,
-                _ => panic!("do not know"),
+// the damage dice and bonuses of a Strike.
+struct DamageRolls {
+    // doubled on a critical hit.
+    pre_crit_roll: Roll,
+    // added after doubling.
+    post_crit_roll: Roll,
+    // p581 the damage of property runes, doubled too, resisted by its own type.
+    extra: Vec<(DamageType, Roll)>,
+    ghost_touch: bool,
+}
+
+// the chances of the same total are added up.
+fn add_chance(totals: &mut Vec<(i64, f64)>, amount: i64, chance: f64) {
+    match totals.iter_mut().find(|(total, _)| *total == amount) {
+        Some((_, p)) => *p += chance,
+        None => totals.push((amount, chance)),
+    }
+}
+
+// every total of damage the rolls can deal with its chance, each damage type resisted on its own.
+fn get_damage_distribution(
+    rolls: &DamageRolls,
+    damage_type: DamageType,
+    target: &Character,
+    multiplier: i64,
+) -> Vec<(i64, f64)> {
+    let post_crit = rolls.post_crit_roll.distribution();
+    let mut totals = vec![];
+    for (pre, p) in rolls.pre_crit_roll.distribution() {
+        for (post, q) in post_crit.iter() {
+            let amount = adjust_strike_damage(
+                target,
+                damage_type,
+                pre * multiplier + post,
+                rolls.ghost_touch,
+            );
+            add_chance(&mut totals, amount, p * q);
+        }
+    }
+    for (extra_type, roll) in rolls.extra.iter() {
+        let mut next = vec![];
+        for (extra, p) in roll.distribution() {
+            let amount =
+                adjust_strike_damage(target, *extra_type, extra * multiplier, rolls.ghost_touch);
+            for (total, q) in totals.iter() {
+                add_chance(&mut next, total + amount, p * q);
             }
+        }
+        totals = next;
+    }
+    totals
+}
+
+fn get_damage_rolls(
+    weapon: &WeaponItem,
+    creature_strike: Option<&CreatureStrike>,
+    extra_dice: i64,
+    source: &Character,
+    target: &Character,
+    world: &World,
+    is_critical: bool,
+) -> DamageRolls {
+    let CombatProperties { nb_dice, .. } = weapon.damage;
+    let dice_faces = get_damage_die(weapon, source);
+
+    let mut rules = vec![];
+    if creature_strike.is_none() {
+        rules.push(Rule::StrengthModDamage);
+    }
+    rules.extend(weapon.info.rules.clone());
+    rules.extend(weapon.runes.get_rules());
+    rules.extend(source.feats.iter().flat_map(|f| f.rules.clone()));
+    if let Some(armor) = get_armor(target, world) {
+        rules.extend(armor.info.rules.clone());
+    }
+    let ctx = CombatContext {
+        source,
+        target,
+        weapon,
+        world,
+        is_critical,
+    };
+
+    let mut pre_crit_roll = Roll::d("weapon", nb_dice, dice_faces);
+    if extra_dice > 0 {
+        pre_crit_roll = pre_crit_roll + Roll::d("extra", extra_dice, dice_faces);
+    }
+    if let Some(s) = creature_strike {
+        pre_crit_roll = pre_crit_roll + Roll::flat("bonus", s.damage_bonus);
+    }
+    pre_crit_roll = world.rules.dmg_pre_crit(&rules, pre_crit_roll, &ctx);
+    let post_crit_roll = world
+        .rules
+        .dmg_post_crit(&weapon.info.rules, Roll::default(), &ctx);
+    DamageRolls {
+        pre_crit_roll,
+        post_crit_roll,
+        extra: world.rules.extra_damage(&rules, &ctx),
+        ghost_touch: rules.contains(&Rule::GhostTouch),
+    }
+}
+
+// the chance of every degree of success of a Strike, indexed by degree.
+fn get_strike_chances(
+    source: &Character,
+    target: &Character,
+    weapon: &WeaponItem,
+    creature_strike: Option<&CreatureStrike>,
+    penalty: i64,
+    world: &World,
+) -> [f64; 4] {
+    // p618 the flat check against concealed and hidden targets comes first, failing it misses.
+    let flat_check = get_flat_check_dc(source, target, world)
+        .map_or(1.0, |dc| ((21 - dc) as f64 / 20.0).clamp(0.0, 1.0));
+    let modifier = get_attack_modifier(weapon, creature_strike, penalty, source, world).roll();
+    let ac = get_strike_ac(source, target, weapon, world);
+    let mut chances = get_chances(modifier, ac).map(|chance| chance * flat_check);
+    chances[DegreeOfSuccess::Failure as usize] += 1.0 - flat_check;
+    chances
+}
+
+/**
+What a Strike is expected to do, from the chances of the d20 and of the damage dice instead of
+rolling them. Shield blocks and other reactions are left out.
+*/
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct StrikeOdds {
+    // critical hits included
+    pub hit: f64,
+    pub damage: f64,
+    // the chance to bring the target to 0 HP
+    pub kill: f64,
+    pub critical_failure: f64,
+}
+
+pub(crate) fn expect_strike(
+    source: &Character,
+    target: &Character,
+    weapon: &WeaponItem,
+    creature_strike: Option<&CreatureStrike>,
+    modifiers: StrikeModifiers,
+    world: &World,
+) -> StrikeOdds {
+    if target.hp <= 0 {
+        return StrikeOdds::default();
+    }
+    let penalty = modifiers.penalty;
+    let chances = get_strike_chances(source, target, weapon, creature_strike, penalty, world);
+    let mut odds = StrikeOdds {
+        critical_failure: chances[DegreeOfSuccess::CriticalFailure as usize],
+        ..StrikeOdds::default()
+    };
+    for is_critical in [false, true] {
+        let degree = if is_critical {
+            DegreeOfSuccess::CriticalSuccess
         } else {
-            0
+            DegreeOfSuccess::Success
+        };
+        let chance = chances[degree as usize];
+        if chance == 0.0 {
+            continue;
         }
+        let rolls = get_damage_rolls(
+            weapon,
+            creature_strike,
+            modifiers.extra_dice,
+            source,
+            target,
+            world,
+            is_critical,
+        );
+        let multiplier = if is_critical { 2 } else { 1 };
+        let hp = target.hp + target.temp_hp;
+        let damage_type = weapon.damage.damage_type;
+        for (amount, p) in get_damage_distribution(&rolls, damage_type, target, multiplier) {
+            // damage past 0 HP is lost.
+            odds.damage += chance * p * amount.min(hp) as f64;
+            if amount >= hp {
+                odds.kill += chance * p;
+            }
+        }
+        odds.hit += chance;
+    }
+    odds
+}
+
+/**
+The average damage of the best Strike of the source against the target, wherever it stands.
+Quicker than `expect_strike`, without the chance to kill nor the HP left to the target.
+*/
+pub(crate) fn expect_best_strike_damage(
+    source: &Character,
+    target: &Character,
+    world: &World,
+) -> f64 {
+    let expect = |weapon: &WeaponItem, creature_strike: Option<&CreatureStrike>| {
+        let chances = get_strike_chances(source, target, weapon, creature_strike, 0, world);
+        [false, true]
+            .iter()
+            .map(|&is_critical| {
+                let (degree, multiplier) = if is_critical {
+                    (DegreeOfSuccess::CriticalSuccess, 2.0)
+                } else {
+                    (DegreeOfSuccess::Success, 1.0)
+                };
+                let rolls = get_damage_rolls(
+                    weapon,
+                    creature_strike,
+                    0,
+                    source,
+                    target,
+                    world,
+                    is_critical,
+                );
+                let average =
+                    rolls.pre_crit_roll.average() * multiplier + rolls.post_crit_roll.average();
+                let mut amount = adjust_strike_damage(
+                    target,
+                    weapon.damage.damage_type,
+                    average.round() as i64,
+                    rolls.ghost_touch,
+                );
+                for (damage_type, roll) in rolls.extra.iter() {
+                    let average = roll.average() * multiplier;
+                    amount += adjust_strike_damage(
+                        target,
+                        *damage_type,
+                        average.round() as i64,
+                        rolls.ghost_touch,
+                    );
+                }
+                chances[degree as usize] * amount as f64
+            })
+            .sum::<f64>()
+    };
+    let damages: Vec<f64> = match &source.creature {
+        Some(creature) => creature
+            .strikes
+            .iter()
+            .map(|s| expect(&s.weapon, Some(s)))
+            .collect(),
+        None => get_wielded_weapons(source, world)
+            .into_iter()
+            .map(|(_, weapon)| expect(weapon, None))
+            .collect(),
     };
-    10 + ac_bonus_armor
+    damages.into_iter().fold(0.0, f64::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        damage::adjust_damage,
+        inventory::wield,
+        item::rune::{PropertyRune, Runes},
+        position::Position,
+        utils::get_active_weapon,
+    };
+
+    #[test]
+    fn rune_damage() {
+        let mut world = World::new();
+        let skeleton = world
+            .spawn_creature("skeleton-guard", "undead", Position::new(0, 1))
+            .unwrap();
+        let mut knight = Character::new(String::from("Knight"), String::from("knights"), 20);
+        knight.ability_score.strength = 16;
+        let knight = world.add_character(knight);
+        let longsword = world
+            .catalog
+            .get_weapon("longsword")
+            .unwrap()
+            .with_runes(Runes {
+                potency: 2,
+                property: vec![PropertyRune::Flaming, PropertyRune::Holy],
+                ..Default::default()
+            })
+            .unwrap();
+        let weapon_id = world.spawn_weapon(longsword).unwrap();
+        wield(&knight, &weapon_id, &mut world).unwrap();
+
+        let knight = world.get_character(&knight);
+        let weapon = get_active_weapon(knight, &world);
+        let skeleton = world.get_character(&skeleton);
+        let dmg = compute_damage_roll(weapon, None, 0, knight, skeleton, &world, false);
+        // the skeleton resists 5 slashing and 5 fire, the fire of the rune is resisted on its own:
+        // 1d8 6 + str 3 - 5 slashing, 1d6 4 - 5 fire, 1d6 4 good.
+        assert_eq!(dmg.value, 8);
+        assert!(dmg.details.contains("flaming 1d6 fire"));
+        assert!(dmg.details.contains("holy 1d6 good"));
+
+        let odds = expect_strike(
+            knight,
+            skeleton,
+            weapon,
+            None,
+            StrikeModifiers::default(),
+            &world,
+        );
+        assert!(odds.kill > 0.0 && odds.damage <= 4.0);
+    }
+
+    #[test]
+    fn ghost_touch() {
+        let mut world = World::new();
+        let ghost = world
+            .spawn_creature("orc-brute", "ghosts", Position::new(0, 1))
+            .unwrap();
+        let ghost_mut = world.get_mut_character(&ghost);
+        ghost_mut.traits.push(Rule::Incorporeal);
+        ghost_mut.creature.as_mut().unwrap().resist_all = 5;
+        let mut knight = Character::new(String::from("Knight"), String::from("knights"), 20);
+        knight.ability_score.strength = 16;
+        let knight = world.add_character(knight);
+        let longsword = world.catalog.get_weapon("longsword").unwrap();
+        let ghost_touch = longsword
+            .clone()
+            .with_runes(Runes {
+                potency: 1,
+                property: vec![PropertyRune::GhostTouch],
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(ghost_touch.info.name, "+1 Ghost Touch Longsword");
+
+        let knight = world.get_character(&knight);
+        let ghost = world.get_character(&ghost);
+        let damage = |weapon| compute_damage_roll(weapon, None, 0, knight, ghost, &world, false);
+        // 1d8 6 + str 3, less the resistance to all damage unless the weapon is ghost touch.
+        assert_eq!(damage(&longsword).value, 4);
+        assert_eq!(damage(&ghost_touch).value, 9);
+        assert_eq!(adjust_damage(ghost, DamageType::Force, 9), 9);
+    }
+
+    #[test]
+    fn strength_of_the_weapon_used() {
+        let mut world = World::new();
+        let knight = world.add_character_at("Knight", "knights", 20, Position::new(0, 0));
+        world.get_mut_character(&knight).ability_score.strength = 16;
+        let goblin = world.add_character_at("Goblin", "goblins", 20, Position::new(0, 1));
+        // the sling in the right hand, the shortsword in the left one.
+        for key in ["sling", "shortsword"] {
+            let id = world.spawn_item(key).unwrap();
+            wield(&knight, &id, &mut world).unwrap();
+        }
+        let knight = world.get_character(&knight);
+        let goblin = world.get_character(&goblin);
+        let wielded = get_wielded_weapons(knight, &world);
+        let (sling, shortsword) = (wielded[0].1, wielded[1].1);
+        assert_eq!(get_active_weapon(knight, &world).info.name, "Sling");
+        let damage = |weapon| compute_damage_roll(weapon, None, 0, knight, goblin, &world, false);
+        assert!(damage(shortsword).details.contains("str"));
+        assert!(!damage(sling).details.contains("str"));
+        assert_eq!(
+            get_melee_weapon(knight, &world).unwrap().0.info.name,
+            "Shortsword"
+        );
+    }
+
+    #[test]
+    fn creature_damage() {
+        let mut world = World::new();
+        let scout = world
+            .spawn_creature("kobold-scout", "kobolds", Position::new(0, 0))
+            .unwrap();
+        let target = world.add_character_at("Valeros", "heroes", 20, Position::new(0, 1));
+        let scout = world.get_character(&scout);
+        let shortsword = &scout.creature.as_ref().unwrap().strikes[0];
+        let target = world.get_character(&target);
+        let dmg = compute_damage_roll(
+            &shortsword.weapon,
+            Some(shortsword),
+            0,
+            scout,
+            target,
+            &world,
+            false,
+        );
+        // 1d6 4 + 1 of the stat block, its dexterity is already counted.
+        assert_eq!(dmg.value, 5);
+        assert!(!dmg.details.contains("finesse"));
+    }
+
+    #[test]
+    fn natural_rolls() {
+        let mut world = World::new();
+        let knight = world.add_character_at("Knight", "knights", 20, Position::new(0, 0));
+        let goblin = world.add_character_at("Goblin", "goblins", 20, Position::new(0, 1));
+        let knight = world.get_character(&knight);
+        let goblin = world.get_character(&goblin);
+        let fist = get_active_weapon(knight, &world);
+        let (mut natural_1, mut natural_20) = (false, false);
+        for seed in 0..200 {
+            dice::seed(seed);
+            let roll = compute_attack_roll(fist, None, 0, knight, goblin, &world);
+            assert!((1..=20).contains(&roll.natural));
+            match roll.natural {
+                // a natural 20 hits an AC it misses by 5, a natural 1 misses an AC it beats by 5.
+                20 => {
+                    natural_20 = true;
+                    let degree = get_strike_degree(&roll, roll.value + 5);
+                    assert_eq!(degree, DegreeOfSuccess::Success);
+                }
+                1 => {
+                    natural_1 = true;
+                    let degree = get_strike_degree(&roll, roll.value - 5);
+                    assert_eq!(degree, DegreeOfSuccess::Failure);
+                }
+                _ => {
+                    let degree = get_strike_degree(&roll, roll.value + 5);
+                    assert_eq!(degree, DegreeOfSuccess::Failure);
+                }
+            }
+        }
+        assert!(natural_1 && natural_20);
+    }
 }
@@ -0,0 +1,70 @@
+use crate::{
+    ai::{get_healing_utility, Utility},
+    character::{skill::Skill, Character},
+    fact,
+    healing::{battle_medicine, expect_treatment},
+    status::StatusType,
+    timeline::CharacterId,
+    world::World,
+};
+
+use super::{ActionTrait, Activity};
+
+/**
+p258 Battle Medicine: Treat Wounds as a single action on an adjacent creature, or yourself.
+The most wounded one is treated first, the unconscious ones above all.
+*/
+#[derive(Clone, Debug)]
+pub struct Action;
+
+impl Action {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+fn find_patient(character: &Character, world: &World) -> Option<CharacterId> {
+    world
+        .get_characters()
+        .into_iter()
+        .filter(|c| c.party == character.party && c.hp < c.max_hp)
+        .filter(|c| !c.has_status(StatusType::BattleMedicineImmunity))
+        .filter(|c| character.position.is_within_reach(&c.position, 5))
+        .min_by_key(|c| (c.hp, c.id.clone()))
+        .map(|c| c.id.clone())
+}
+
+impl Activity for Action {
+    fn can_be_used(&self, character: &Character, world: &World) -> bool {
+        character.hp > 0
+            && character.is_trained(Skill::Medicine)
+            && find_patient(character, world).is_some()
+    }
+
+    // worth the HP the treatment is expected to restore, and more to get an ally back on its feet.
+    fn ai_utility(&self, character: &Character, world: &World) -> Option<Utility> {
+        let patient = match find_patient(character, world) {
+            Some(id) => world.get_character(&id),
+            None => return Some(Utility::default()),
+        };
+        Some(get_healing_utility(
+            patient,
+            expect_treatment(character),
+            world,
+        ))
+    }
+
+    fn resolve(&mut self, character: &Character, world: &mut World, facts: &mut fact::Facts) {
+        if let Some(patient_id) = find_patient(character, world) {
+            battle_medicine(character, &patient_id, world, facts);
+        }
+    }
+
+    fn get_traits(&self) -> Vec<ActionTrait> {
+        vec![ActionTrait::Manipulate]
+    }
+
+    fn get_name(&self) -> &str {
+        "Battle Medicine"
+    }
+}
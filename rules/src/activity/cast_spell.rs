@@ -0,0 +1,665 @@
+use dice::Roll;
+
+use crate::{
+    ai::{get_healing_utility, get_status_utility, get_threat, Utility},
+    area::{find_creatures, Affects, Area},
+    character::{
+        spellcasting::{SpellSlot, SpellcastingEntry},
+        Character,
+    },
+    check::{flat_check, get_chances, DegreeOfSuccess},
+    cover::{get_cover, get_cover_from, get_flat_check_dc},
+    damage::{adjust_damage, apply_damage, Damage},
+    defense::{compute_ac, compute_save, Save},
+    fact::{self, Fact},
+    healing::apply_healing,
+    inventory::{get_held_consumables, use_charge},
+    item::{weapon::DamageType, ItemId},
+    position::Position,
+    spell::{
+        DamageScale, LastingEmanation, SpellArea, SpellData, SpellDefense, SpellEffect,
+        SpellTargets,
+    },
+    status::{apply_status, Duration, StatusEffect},
+    timeline::CharacterId,
+    world::World,
+};
+
+use super::{attack::StrikeOdds, ActionTrait, Activity};
+
+/**
+p302 Cast a Spell from one of the spellcasting entries of the character, spending its slot.
+A spell with a variable number of actions gives one activity per number of actions.
+p564 a spell cast from a scroll uses the entry of the same tradition, and uses the scroll up.
+*/
+#[derive(Clone, Debug)]
+pub struct Action {
+    name: String,
+    entry: usize,
+    spell: String,
+    rank: i64,
+    slot: SpellSlot,
+    actions: i64,
+    scroll: Option<ItemId>,
+}
+
+impl Action {
+    pub fn new(
+        entry: usize,
+        spell: &str,
+        rank: i64,
+        slot: SpellSlot,
+        data: &SpellData,
+        actions: i64,
+    ) -> Self {
+        let mut name = data.name.clone();
+        if rank > data.rank && !data.is_cantrip() {
+            name = format!("{} (rank {})", name, rank);
+        }
+        if data.actions.get_choices().len() > 1 {
+            let plural = if actions > 1 { "s" } else { "" };
+            name = format!("{} ({} action{})", name, actions, plural);
+        }
+        Self {
+            name,
+            entry,
+            spell: String::from(spell),
+            rank,
+            slot,
+            actions,
+            scroll: None,
+        }
+    }
+
+    pub fn with_scroll(mut self, item_id: &str) -> Self {
+        self.name = format!("{} (scroll)", self.name);
+        self.scroll = Some(String::from(item_id));
+        self
+    }
+
+    fn get_data<'a>(&self, world: &'a World) -> &'a SpellData {
+        world
+            .spellbook
+            .get(&self.spell)
+            .expect("spellcasting entries only hold known spells")
+    }
+
+    // the spell as cast with the actions of this activity.
+    fn get_spell(&self, world: &World) -> SpellData {
+        self.get_data(world).with_actions(self.actions)
+    }
+}
+
+// the closest conscious enemy within `distance` and line of effect, where area spells are aimed.
+fn find_aim(character: &Character, distance: i64, world: &World) -> Option<Position> {
+    world
+        .get_characters()
+        .into_iter()
+        .filter(|c| c.hp > 0 && c.party != character.party)
+        .filter(|c| character.position.distance(&c.position) <= distance)
+        .filter(|c| {
+            world
+                .map
+                .has_line_of_effect(&character.position, &c.position)
+        })
+        .min_by_key(|c| (character.position.distance(&c.position), c.id.clone()))
+        .map(|c| c.position)
+}
+
+fn get_area(spell: &SpellData, character: &Character, world: &World) -> Option<Area> {
+    let origin = character.position;
+    Some(match spell.area? {
+        SpellArea::Burst(radius) => Area::Burst {
+            corner: find_aim(character, spell.range, world)?,
+            radius,
+        },
+        SpellArea::Cone(length) => Area::Cone {
+            origin,
+            towards: find_aim(character, length, world)?,
+            length,
+        },
+        SpellArea::Line(length) => Area::Line {
+            origin,
+            towards: find_aim(character, length, world)?,
+            length,
+        },
+        SpellArea::Emanation(radius) => Area::Emanation { origin, radius },
+    })
+}
+
+// p477 cover against an area is drawn from the origin of the area, from the caster otherwise.
+fn get_cover_origin(spell: &SpellData, character: &Character, world: &World) -> Position {
+    get_area(spell, character, world).map_or(character.position, |area| area.get_origin())
+}
+
+// the creatures affected by the spell, sorted so that the outcome does not depend on the HashMap.
+fn find_targets(spell: &SpellData, character: &Character, world: &World) -> Vec<CharacterId> {
+    match spell.targets {
+        SpellTargets::Enemies(count) => {
+            let mut enemies: Vec<&Character> = world
+                .get_characters()
+                .into_iter()
+                .filter(|c| c.hp > 0 && c.party != character.party)
+                .filter(|c| character.position.distance(&c.position) <= spell.range)
+                .filter(|c| {
+                    world
+                        .map
+                        .has_line_of_effect(&character.position, &c.position)
+                })
+                .collect();
+            enemies.sort_by_key(|c| (character.position.distance(&c.position), c.id.clone()));
+            let mut ids: Vec<CharacterId> =
+                enemies.iter().take(count).map(|c| c.id.clone()).collect();
+            ids.sort();
+            ids
+        }
+        SpellTargets::Ally => world
+            .get_characters()
+            .into_iter()
+            .filter(|c| c.party == character.party)
+            .filter(|c| character.position.distance(&c.position) <= spell.range)
+            .filter(|c| {
+                world
+                    .map
+                    .has_line_of_effect(&character.position, &c.position)
+            })
+            .min_by_key(|c| (c.hp - c.max_hp, c.hp, c.id.clone()))
+            .map(|c| vec![c.id.clone()])
+            .unwrap_or_default(),
+        SpellTargets::Creatures | SpellTargets::Allies => {
+            let area = match get_area(spell, character, world) {
+                Some(area) => area,
+                None => return vec![],
+            };
+            let affects = match spell.targets {
+                SpellTargets::Allies => Affects::Allies,
+                _ => Affects::Everyone,
+            };
+            let mut ids = find_creatures(&area, &character.party, affects, world);
+            // healing reaches the unconscious creatures too, to bring them back.
+            if spell.healing.is_none() {
+                ids.retain(|id| world.get_character(id).hp > 0);
+            }
+            // p457 an emanation does not affect its own creature, unless it is for allies.
+            if let (SpellTargets::Creatures, Area::Emanation { .. }) = (spell.targets, area) {
+                ids.retain(|id| *id != character.id);
+            }
+            ids
+        }
+    }
+}
+
+// the chance of every degree of success of the spell against a target, indexed by degree.
+fn get_chances_against(
+    spell: &SpellData,
+    entry: &SpellcastingEntry,
+    character: &Character,
+    target: &Character,
+    origin: &Position,
+    world: &World,
+) -> [f64; 4] {
+    match spell.defense {
+        SpellDefense::None => {
+            let mut chances = [0.0; 4];
+            chances[DegreeOfSuccess::Success as usize] = 1.0;
+            chances
+        }
+        SpellDefense::Attack => {
+            // p618 a failed flat check against a concealed or hidden target misses.
+            let flat_check = get_flat_check_dc(character, target, world)
+                .map_or(1.0, |dc| ((21 - dc) as f64 / 20.0).clamp(0.0, 1.0));
+            let ac = compute_ac(target, world).roll()
+                + get_cover(character, target, true, world).get_ac_bonus();
+            let mut chances = get_chances(entry.get_spell_attack(character).roll(), ac);
+            for chance in chances.iter_mut() {
+                *chance *= flat_check;
+            }
+            chances[DegreeOfSuccess::Failure as usize] += 1.0 - flat_check;
+            chances
+        }
+        SpellDefense::Save(save) | SpellDefense::BasicSave(save) => {
+            let mut modifier = compute_save(target, save, world).roll();
+            if save == Save::Reflex {
+                modifier += get_cover_from(origin, target, true, world).get_reflex_bonus();
+            }
+            get_chances(modifier, entry.get_spell_dc(character))
+        }
+    }
+}
+
+// the damage a target is expected to take from a distribution of the spell damage.
+fn expect_damage(
+    target: &Character,
+    distribution: &[(i64, f64)],
+    scale: DamageScale,
+    damage_type: DamageType,
+) -> StrikeOdds {
+    let mut odds = StrikeOdds::default();
+    for (value, p) in distribution {
+        let amount = match scale {
+            DamageScale::Half => value / 2,
+            DamageScale::Full => *value,
+            DamageScale::Double => value * 2,
+        };
+        let amount = adjust_damage(target, damage_type, amount);
+        let hp = target.hp.max(0) + target.temp_hp;
+        odds.damage += p * amount.min(hp) as f64;
+        if target.hp > 0 && amount >= hp {
+            odds.kill += p;
+        }
+    }
+    odds
+}
+
+impl Activity for Action {
+    fn can_be_used(&self, character: &Character, world: &World) -> bool {
+        let holds_scroll = self.scroll.as_ref().is_none_or(|scroll| {
+            get_held_consumables(character, world)
+                .iter()
+                .any(|(id, _)| id == scroll)
+        });
+        character.hp > 0
+            && holds_scroll
+            && !find_targets(&self.get_spell(world), character, world).is_empty()
+    }
+
+    /**
+    Worth what the outcomes of the spell bring on each target, weighed by the chances of the
+    attack roll or of the saving throws. A lasting emanation already up is worth nothing.
+    */
+    fn ai_utility(&self, character: &Character, world: &World) -> Option<Utility> {
+        if character.emanations.iter().any(|e| e.spell == self.spell) {
+            return Some(Utility::default());
+        }
+        let spell = self.get_spell(world);
+        let entry = &character.spellcasting[self.entry];
+        let ability = character.ability_score.get_modifier(entry.ability);
+        let damage = spell
+            .get_damage_roll(self.rank, self.actions, ability)
+            .map(|roll| roll.distribution())
+            .unwrap_or_default();
+        let healing = spell
+            .get_healing_roll(self.rank, self.actions)
+            .map_or(0.0, |roll| roll.average());
+        let outcomes = spell.get_outcomes();
+        let origin = get_cover_origin(&spell, character, world);
+        let utility = find_targets(&spell, character, world)
+            .iter()
+            .map(|id| {
+                let target = world.get_character(id);
+                let ally = target.party == character.party;
+                let chances = get_chances_against(&spell, entry, character, target, &origin, world);
+                outcomes
+                    .iter()
+                    .map(|(degree, effects)| {
+                        let utility: Utility = effects
+                            .iter()
+                            .map(|effect| match *effect {
+                                SpellEffect::Damage(scale) => {
+                                    let damage_type = spell
+                                        .damage
+                                        .as_ref()
+                                        .expect("spell should be validated")
+                                        .damage_type;
+                                    let odds = expect_damage(target, &damage, scale, damage_type);
+                                    let sign = if ally { -1.0 } else { 1.0 };
+                                    Utility {
+                                        damage: sign * odds.damage,
+                                        kill: sign * odds.kill,
+                                        threat: if ally {
+                                            0.0
+                                        } else {
+                                            odds.kill * get_threat(target, character, world)
+                                        },
+                                        ..Utility::default()
+                                    }
+                                }
+                                SpellEffect::Status(status_type, _) => {
+                                    get_status_utility(character, target, status_type, world)
+                                }
+                                SpellEffect::Heal if ally => {
+                                    get_healing_utility(target, healing, world)
+                                }
+                                SpellEffect::Heal => Utility {
+                                    damage: -get_healing_utility(target, healing, world).buff,
+                                    ..Utility::default()
+                                },
+                                SpellEffect::TemporaryHp(hp) => {
+                                    let added = (hp + ability - target.temp_hp).max(0) as f64;
+                                    Utility {
+                                        buff: if ally { added } else { -added },
+                                        ..Utility::default()
+                                    }
+                                }
+                            })
+                            .sum();
+                        utility * chances[*degree as usize]
+                    })
+                    .sum::<Utility>()
+            })
+            .sum();
+        Some(utility)
+    }
+
+    fn resolve(&mut self, character: &Character, world: &mut World, facts: &mut fact::Facts) {
+        let spell = self.get_spell(world);
+        let targets = find_targets(&spell, character, world);
+        let entry = character.spellcasting[self.entry].clone();
+        world.get_mut_character(&character.id).spellcasting[self.entry].expend(self.slot);
+        if let Some(scroll) = &self.scroll {
+            use_charge(&character.id, scroll, world);
+        }
+        facts.push(Fact::CastSpell {
+            character_id: character.id.clone(),
+            spell: spell.name.clone(),
+            rank: self.rank,
+        });
+        match spell.area {
+            Some(SpellArea::Emanation(radius)) if spell.duration > 0 => {
+                let emanation = LastingEmanation::new(&self.spell, &spell, radius);
+                let caster = world.get_mut_character(&character.id);
+                caster.emanations.retain(|e| e.spell != self.spell);
+                caster.emanations.push(emanation);
+                return;
+            }
+            _ => {}
+        }
+
+        // p450 the damage of an area spell is rolled once for every target.
+        let ability = character.ability_score.get_modifier(entry.ability);
+        let mut damage = spell.get_damage_roll(self.rank, self.actions, ability);
+        let damage_value = damage.as_mut().map_or(0, |d| d.resolve());
+        let healing_value = spell
+            .get_healing_roll(self.rank, self.actions)
+            .map_or(0, |mut h| h.resolve());
+        let outcomes = spell.get_outcomes();
+        let spell_dc = entry.get_spell_dc(character);
+        let origin = get_cover_origin(&spell, character, world);
+
+        for target_id in targets {
+            let target = world.get_character(&target_id);
+            let degree = match spell.defense {
+                SpellDefense::None => DegreeOfSuccess::Success,
+                SpellDefense::Attack => match get_flat_check_dc(character, target, world) {
+                    // p618 a failed flat check against a concealed or hidden target misses.
+                    Some(dc) if !flat_check(&character.id, dc, facts) => DegreeOfSuccess::Failure,
+                    _ => {
+                        let natural = Roll::d("", 1, 20).roll();
+                        let attack =
+                            Roll::flat("natural", natural) + entry.get_spell_attack(character);
+                        let total = attack.roll();
+                        let ac = compute_ac(target, world).roll()
+                            + get_cover(character, target, true, world).get_ac_bonus();
+                        let degree = DegreeOfSuccess::from_check(total, natural, ac);
+                        facts.push(Fact::Attack {
+                            character_id: character.id.clone(),
+                            target_id: target_id.clone(),
+                            weapon: spell.name.clone(),
+                            total,
+                            details: attack.to_string(),
+                            ac,
+                            degree,
+                        });
+                        degree
+                    }
+                },
+                SpellDefense::Save(save) | SpellDefense::BasicSave(save) => {
+                    let natural = Roll::d("", 1, 20).roll();
+                    let mut total = natural + compute_save(target, save, world).roll();
+                    // p477 cover also protects against Reflex saves.
+                    if save == Save::Reflex {
+                        total += get_cover_from(&origin, target, true, world).get_reflex_bonus();
+                    }
+                    let degree = DegreeOfSuccess::from_check(total, natural, spell_dc);
+                    facts.push(Fact::Check {
+                        character_id: target_id.clone(),
+                        check: format!("{:?} save against {}", save, spell.name),
+                        total,
+                        dc: spell_dc,
+                        degree,
+                    });
+                    degree
+                }
+            };
+            for effect in outcomes.get(&degree).cloned().unwrap_or_default() {
+                match effect {
+                    SpellEffect::Damage(scale) => {
+                        let spell_damage =
+                            spell.damage.as_ref().expect("spell should be validated");
+                        let amount = match scale {
+                            DamageScale::Half => damage_value / 2,
+                            DamageScale::Full => damage_value,
+                            DamageScale::Double => damage_value * 2,
+                        };
+                        let target = world.get_character(&target_id);
+                        let amount = adjust_damage(target, spell_damage.damage_type, amount);
+                        let damage = Damage::new(amount)
+                            .of_type(spell_damage.damage_type)
+                            .details(&damage.as_ref().map(|d| d.to_string()).unwrap_or_default());
+                        apply_damage(&character.id, &target_id, damage, world, facts);
+                    }
+                    SpellEffect::Status(status_type, rounds) => {
+                        let status = StatusEffect {
+                            duration: Duration::Round(rounds),
+                            status_type,
+                        };
+                        apply_status(&target_id, status, world, facts);
+                    }
+                    SpellEffect::Heal => {
+                        apply_healing(&target_id, healing_value, world, facts);
+                    }
+                    SpellEffect::TemporaryHp(hp) => {
+                        world
+                            .get_mut_character(&target_id)
+                            .add_temp_hp(hp + ability);
+                        facts.push(Fact::TemporaryHp {
+                            target_id: target_id.clone(),
+                            amount: hp + ability,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    // p303 casting with verbal and somatic components
+    fn get_traits(&self) -> Vec<ActionTrait> {
+        vec![ActionTrait::Concentrate, ActionTrait::Manipulate]
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_cost(&self) -> i64 {
+        self.actions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        activity::perform,
+        character::{
+            abilities::Ability,
+            spellcasting::{CastingType, SpellcastingEntry},
+        },
+        position::Position,
+        spell::{get_emanation_statuses, Tradition},
+        status::StatusType,
+    };
+
+    fn get_spells(world: &World, id: &str) -> Vec<Box<dyn Activity>> {
+        let character = world.get_character(id);
+        character
+            .get_activities(world)
+            .into_iter()
+            .filter(|a| a.get_traits().contains(&ActionTrait::Concentrate))
+            .collect()
+    }
+
+    #[test]
+    fn magic_missile() {
+        let mut world = World::new();
+        let wizard = world.add_character_at("Ezren", "heroes", 30, Position::new(0, 0));
+        let goblin = world.add_character_at("Goblin", "goblins", 30, Position::new(0, 10));
+        let mut entry = SpellcastingEntry::new(
+            "Arcane Prepared Spells",
+            Tradition::Arcane,
+            CastingType::Prepared,
+            Ability::Intelligence,
+        )
+        .slots(1, 1);
+        entry.prepare(&world.spellbook, "magic-missile", 1).unwrap();
+        world.get_mut_character(&wizard).spellcasting.push(entry);
+
+        let mut spells = get_spells(&world, &wizard);
+        assert_eq!(
+            spells.iter().map(|s| s.get_cost()).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(spells[1].get_name(), "Magic Missile (2 actions)");
+        let caster = world.get_character(&wizard).clone();
+        let mut facts = fact::Facts::new();
+        perform(&mut spells[1], &caster, &mut world, &mut facts);
+        // two missiles of 1d4+1, no attack roll
+        assert_eq!(world.get_character(&goblin).hp, 30 - 2 * 3);
+        // the slot is spent
+        assert!(get_spells(&world, &wizard).is_empty());
+    }
+
+    #[test]
+    fn heal() {
+        let mut world = World::new();
+        let cleric = world.add_character_at("Kyra", "heroes", 30, Position::new(0, 0));
+        let fighter = world.add_character_at("Valeros", "heroes", 30, Position::new(1, 0));
+        let rogue = world.add_character_at("Merisiel", "heroes", 30, Position::new(4, 0));
+        world.get_mut_character(&fighter).hp = -3;
+        world.get_mut_character(&rogue).hp = 10;
+        let mut entry = SpellcastingEntry::new(
+            "Divine Prepared Spells",
+            Tradition::Divine,
+            CastingType::Prepared,
+            Ability::Wisdom,
+        )
+        .slots(1, 1);
+        entry.prepare(&world.spellbook, "heal", 1).unwrap();
+        world.get_mut_character(&cleric).spellcasting.push(entry);
+
+        let mut spells = get_spells(&world, &cleric);
+        let caster = world.get_character(&cleric).clone();
+        assert_eq!(spells[0].get_name(), "Heal (1 action)");
+        // the unconscious fighter first, then both the fighter and the rogue in the emanation
+        assert_eq!(
+            spells
+                .iter()
+                .map(|s| s.ai_utility(&caster, &world).unwrap().buff)
+                .collect::<Vec<_>>(),
+            vec![4.5, 12.5, 9.0]
+        );
+        let mut facts = fact::Facts::new();
+        perform(&mut spells[1], &caster, &mut world, &mut facts);
+        // healed from 0 HP, and conscious again
+        assert_eq!(world.get_character(&fighter).hp, 6 + 8);
+        assert_eq!(world.get_character(&rogue).hp, 10);
+    }
+
+    #[test]
+    fn bless() {
+        let mut world = World::new();
+        let cleric = world.add_character_at("Kyra", "heroes", 30, Position::new(0, 0));
+        let fighter = world.add_character_at("Valeros", "heroes", 30, Position::new(1, 0));
+        let rogue = world.add_character_at("Merisiel", "heroes", 30, Position::new(2, 0));
+        let wizard = world.add_character_at("Ezren", "heroes", 30, Position::new(3, 0));
+        world.add_character_at("Goblin", "goblins", 30, Position::new(6, 0));
+        let mut entry = SpellcastingEntry::new(
+            "Divine Prepared Spells",
+            Tradition::Divine,
+            CastingType::Prepared,
+            Ability::Wisdom,
+        )
+        .slots(1, 1);
+        entry.prepare(&world.spellbook, "bless", 1).unwrap();
+        world.get_mut_character(&cleric).spellcasting.push(entry);
+        let blessed = |world: &World, id: &str| {
+            get_emanation_statuses(world.get_character(id), world).contains(&StatusType::Bless)
+        };
+
+        let mut spells = get_spells(&world, &cleric);
+        let caster = world.get_character(&cleric).clone();
+        // worth the damage the blessed allies will deal more to the goblin
+        let utility = spells[0].ai_utility(&caster, &world).unwrap();
+        assert!(utility.buff > 0.0 && utility.damage == 0.0);
+        let mut facts = fact::Facts::new();
+        perform(&mut spells[0], &caster, &mut world, &mut facts);
+        assert!(blessed(&world, &cleric));
+        assert!(blessed(&world, &fighter));
+        assert!(!blessed(&world, &rogue));
+        assert_eq!(world.get_character(&cleric).actions_used, 2);
+
+        // sustained from the next turn, the emanation grows by 10 feet
+        let mut sustain = get_spells(&world, &cleric).remove(0);
+        assert_eq!(sustain.get_name(), "Sustain Bless");
+        assert!(!sustain.can_be_used(world.get_character(&cleric), &world));
+        world.get_mut_character(&cleric).start_turn();
+        let caster = world.get_character(&cleric).clone();
+        assert!(sustain.ai_utility(&caster, &world).unwrap().buff > 0.0);
+        perform(&mut sustain, &caster, &mut world, &mut facts);
+        assert_eq!(world.get_character(&cleric).emanations[0].radius, 15);
+        assert!(blessed(&world, &rogue));
+        assert!(blessed(&world, &wizard));
+        assert!(!sustain.can_be_used(world.get_character(&cleric), &world));
+
+        // it follows the cleric
+        world.get_mut_character(&cleric).position = Position::new(10, 10);
+        assert!(blessed(&world, &cleric));
+        assert!(!blessed(&world, &fighter));
+        for _ in 0..10 {
+            world.tick_down(&mut facts);
+        }
+        assert!(!blessed(&world, &cleric));
+    }
+
+    #[test]
+    fn saves_and_attacks() {
+        let mut world = World::new();
+        let wizard = world.add_character_at("Ezren", "heroes", 30, Position::new(0, 0));
+        let a = world.add_character_at("Goblin", "goblins", 30, Position::new(10, 0));
+        let b = world.add_character_at("Goblin", "goblins", 30, Position::new(12, 0));
+        let mut entry = SpellcastingEntry::new(
+            "Arcane Prepared Spells",
+            Tradition::Arcane,
+            CastingType::Prepared,
+            Ability::Intelligence,
+        )
+        .slots(3, 2);
+        entry.add_cantrip(&world.spellbook, "ray-of-frost").unwrap();
+        entry.prepare(&world.spellbook, "fireball", 3).unwrap();
+        entry
+            .prepare(&world.spellbook, "lightning-bolt", 3)
+            .unwrap();
+        world.get_mut_character(&wizard).spellcasting.push(entry);
+        world.get_mut_character(&wizard).ability_score.intelligence = 18;
+
+        let mut spells = get_spells(&world, &wizard);
+        assert_eq!(spells[1].get_name(), "Fireball");
+        let caster = world.get_character(&wizard).clone();
+        let mut facts = fact::Facts::new();
+        // 18 + 0 dex vs DC 17: a success, half of 6d6 fire
+        perform(&mut spells[1], &caster, &mut world, &mut facts);
+        assert_eq!(world.get_character(&a).hp, 30 - 12);
+        assert_eq!(world.get_character(&b).hp, 30 - 12);
+
+        // 18 + 7 vs AC 10: a critical success, double 2d4 cold
+        let caster = world.get_character(&wizard).clone();
+        perform(&mut spells[0], &caster, &mut world, &mut facts);
+        assert_eq!(world.get_character(&a).hp, 30 - 12 - 8);
+
+        // the bolt goes through both goblins, half of 4d12 electricity
+        let caster = world.get_character(&wizard).clone();
+        perform(&mut spells[2], &caster, &mut world, &mut facts);
+        assert_eq!(world.get_character(&a).hp, 30 - 12 - 8 - 20);
+        assert_eq!(world.get_character(&b).hp, 30 - 12 - 20);
+    }
+}
@@ -0,0 +1,101 @@
+use dice::Roll;
+
+use crate::{
+    ai::{get_status_utility, Utility},
+    character::{skill::Skill, Character},
+    check::{get_chances, DegreeOfSuccess},
+    defense::{compute_save, Save},
+    fact::{self, Fact},
+    status::{apply_status, Duration, StatusEffect, StatusType},
+    timeline::CharacterId,
+    world::World,
+};
+
+use super::{ActionTrait, Activity};
+
+/**
+p247 Demoralize: an Intimidation check against the Will DC of a creature within 30 feet.
+Creatures already frightened are left alone, they would be temporarily immune anyway.
+*/
+#[derive(Clone, Debug)]
+pub struct Action;
+
+impl Action {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+fn find_target(character: &Character, world: &World) -> Option<CharacterId> {
+    let mut ids: Vec<CharacterId> = world
+        .get_characters()
+        .iter()
+        .filter(|c| c.party != character.party && c.hp > 0)
+        .filter(|c| !c.has_status(StatusType::Frightened))
+        .filter(|c| character.position.distance(&c.position) <= 30)
+        .map(|c| c.id.clone())
+        .collect();
+    ids.sort();
+    ids.into_iter().next()
+}
+
+impl Activity for Action {
+    fn can_be_used(&self, character: &Character, world: &World) -> bool {
+        character.hp > 0 && find_target(character, world).is_some()
+    }
+
+    // worth frightening the target, from the chances of the Intimidation check.
+    fn ai_utility(&self, character: &Character, world: &World) -> Option<Utility> {
+        let target = match find_target(character, world) {
+            Some(id) => world.get_character(&id),
+            None => return Some(Utility::default()),
+        };
+        let dc = 10 + compute_save(target, Save::Will, world).roll();
+        let chances = get_chances(character.get_skill_modifier(Skill::Intimidation), dc);
+        let frightened = get_status_utility(character, target, StatusType::Frightened, world);
+        Some(
+            frightened
+                * (chances[DegreeOfSuccess::Success as usize]
+                    + chances[DegreeOfSuccess::CriticalSuccess as usize]),
+        )
+    }
+
+    fn resolve(&mut self, character: &Character, world: &mut World, facts: &mut fact::Facts) {
+        let target_id = match find_target(character, world) {
+            Some(id) => id,
+            None => return,
+        };
+        let target = world.get_character(&target_id);
+        let dc = 10 + compute_save(target, Save::Will, world).roll();
+        let natural = Roll::d("", 1, 20).roll();
+        let total = natural + character.get_skill_modifier(Skill::Intimidation);
+        let degree = DegreeOfSuccess::from_check(total, natural, dc);
+        facts.push(Fact::Check {
+            character_id: character.id.clone(),
+            check: format!("demoralizes {}", target.name),
+            total,
+            dc,
+            degree,
+        });
+        let rounds = match degree {
+            DegreeOfSuccess::CriticalSuccess => 2,
+            DegreeOfSuccess::Success => 1,
+            _ => 0,
+        };
+        if rounds > 0 {
+            let frightened = StatusEffect {
+                duration: Duration::Round(rounds),
+                status_type: StatusType::Frightened,
+            };
+            apply_status(&target_id, frightened, world, facts);
+        }
+    }
+
+    fn get_traits(&self) -> Vec<ActionTrait> {
+        vec![ActionTrait::Concentrate]
+    }
+
+    fn get_name(&self) -> &str {
+        "Demoralize"
+    }
+}
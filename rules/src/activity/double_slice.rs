@@ -0,0 +1,135 @@
+use crate::{
+    ai::Utility,
+    character::Character,
+    fact,
+    item::{weapon::WeaponItem, AnyItem},
+    timeline::CharacterId,
+    utils::get_wielded_weapons,
+    world::World,
+};
+
+use super::{
+    attack::{expect_strike, get_strike_utility, strike_with, StrikeModifiers},
+    find_target::find_first_conscious_enemy_in_reach,
+    ActionTrait, Activity,
+};
+
+/**
+p143 Double Slice: a Strike with each of the two melee weapons wielded, against the same target.
+The second Strike takes a -2 penalty, unless its weapon is agile.
+*/
+#[derive(Clone, Debug)]
+pub struct Action;
+
+impl Action {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+// one weapon in each hand, two-handed weapons and shields do not count.
+fn get_weapons(character: &Character, world: &World) -> Option<(WeaponItem, WeaponItem)> {
+    let weapons: Vec<WeaponItem> = get_wielded_weapons(character, world)
+        .into_iter()
+        .filter(|(id, w)| {
+            !w.is_ranged
+                && !w.is_two_hands
+                && matches!(world.items.get(id), Some(AnyItem::WeaponItem(_)))
+        })
+        .map(|(_, w)| w.clone())
+        .collect();
+    match weapons.as_slice() {
+        [first, second] => Some((first.clone(), second.clone())),
+        _ => None,
+    }
+}
+
+fn get_second_strike(second: &WeaponItem) -> StrikeModifiers {
+    let agile = second.info.traits.iter().any(|t| t == "agile");
+    StrikeModifiers {
+        penalty: if agile { 0 } else { 2 },
+        ..StrikeModifiers::default()
+    }
+}
+
+// a target within the reach of both weapons.
+fn find_target(
+    character: &Character,
+    weapons: &(WeaponItem, WeaponItem),
+    world: &World,
+) -> Option<CharacterId> {
+    let reach = weapons.0.get_reach().min(weapons.1.get_reach());
+    find_first_conscious_enemy_in_reach(character, reach, world)
+}
+
+impl Activity for Action {
+    fn can_be_used(&self, character: &Character, world: &World) -> bool {
+        character.hp > 0
+            && get_weapons(character, world)
+                .is_some_and(|weapons| find_target(character, &weapons, world).is_some())
+    }
+
+    fn ai_utility(&self, character: &Character, world: &World) -> Option<Utility> {
+        let weapons = match get_weapons(character, world) {
+            Some(weapons) => weapons,
+            None => return Some(Utility::default()),
+        };
+        let target = match find_target(character, &weapons, world) {
+            Some(id) => world.get_character(&id),
+            None => return Some(Utility::default()),
+        };
+        Some(
+            [
+                (&weapons.0, StrikeModifiers::default()),
+                (&weapons.1, get_second_strike(&weapons.1)),
+            ]
+            .iter()
+            .map(|(weapon, modifiers)| {
+                let odds = expect_strike(character, target, weapon, None, *modifiers, world);
+                get_strike_utility(character, target, odds, world)
+            })
+            .sum(),
+        )
+    }
+
+    fn resolve(&mut self, character: &Character, world: &mut World, facts: &mut fact::Facts) {
+        let weapons = match get_weapons(character, world) {
+            Some(weapons) => weapons,
+            None => return,
+        };
+        if let Some(id) = find_target(character, &weapons, world) {
+            let first = StrikeModifiers::default();
+            strike_with(character, &id, &weapons.0, None, first, world, facts);
+            let second = get_second_strike(&weapons.1);
+            strike_with(character, &id, &weapons.1, None, second, world, facts);
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        "Double Slice"
+    }
+
+    fn get_cost(&self) -> i64 {
+        2
+    }
+
+    fn get_traits(&self) -> Vec<ActionTrait> {
+        vec![ActionTrait::Attack]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn second_strike() {
+        let world = World::new();
+        let longsword = world.catalog.get_weapon("longsword").unwrap();
+        let shortsword = world.catalog.get_weapon("shortsword").unwrap();
+        assert_eq!(get_second_strike(&longsword).penalty, 2);
+        assert_eq!(get_second_strike(&shortsword).penalty, 0);
+        // it ends Take Cover like any other attack.
+        assert!(Action::new().get_traits().contains(&ActionTrait::Attack));
+    }
+}
@@ -0,0 +1,138 @@
+use dice::Roll;
+
+use crate::{
+    ai::{get_healing_utility, Utility},
+    character::Character,
+    fact,
+    healing::apply_healing,
+    inventory::{get_held_consumables, use_charge},
+    item::{
+        consumable::{ConsumableEffect, ConsumableItem},
+        ItemId,
+    },
+    world::World,
+};
+
+use super::{ActionTrait, Activity};
+
+/**
+p563 drinking a held healing potion: an Interact action, the potion is used up.
+It has to be drawn first.
+*/
+#[derive(Clone, Debug)]
+pub struct Action {
+    name: String,
+    item_id: ItemId,
+}
+
+impl Action {
+    pub fn new(item_id: &str, potion: &ConsumableItem) -> Self {
+        Self {
+            name: format!("Drink {}", potion.info.name),
+            item_id: String::from(item_id),
+        }
+    }
+
+    fn get_potion<'a>(
+        &self,
+        character: &Character,
+        world: &'a World,
+    ) -> Option<&'a ConsumableItem> {
+        get_held_consumables(character, world)
+            .into_iter()
+            .find(|(id, _)| *id == self.item_id)
+            .map(|(_, potion)| potion)
+    }
+}
+
+fn get_healing_roll(potion: &ConsumableItem) -> Option<Roll> {
+    match potion.effect {
+        ConsumableEffect::Heal {
+            nb_dice,
+            dice_faces,
+            bonus,
+        } => Some(Roll::d(&potion.info.name, nb_dice, dice_faces) + Roll::flat("bonus", bonus)),
+        _ => None,
+    }
+}
+
+impl Activity for Action {
+    fn can_be_used(&self, character: &Character, world: &World) -> bool {
+        character.hp > 0 && self.get_potion(character, world).is_some()
+    }
+
+    // worth the HP it is expected to restore, once the character is down to half its HP.
+    fn ai_utility(&self, character: &Character, world: &World) -> Option<Utility> {
+        match self.get_potion(character, world).and_then(get_healing_roll) {
+            Some(roll) if character.hp * 2 <= character.max_hp => {
+                Some(get_healing_utility(character, roll.average(), world))
+            }
+            _ => Some(Utility::default()),
+        }
+    }
+
+    fn resolve(&mut self, character: &Character, world: &mut World, facts: &mut fact::Facts) {
+        let mut roll = match self.get_potion(character, world).and_then(get_healing_roll) {
+            Some(roll) => roll,
+            None => return,
+        };
+        use_charge(&character.id, &self.item_id, world);
+        apply_healing(&character.id, roll.resolve(), world, facts);
+    }
+
+    fn get_traits(&self) -> Vec<ActionTrait> {
+        vec![ActionTrait::Manipulate]
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{activity::perform, ai::plan_turn, inventory::add_item};
+
+    fn find(character: &Character, world: &World, name: &str) -> Box<dyn Activity> {
+        character
+            .get_activities(world)
+            .into_iter()
+            .find(|a| a.get_name() == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn drink_potion() {
+        let mut world = World::new();
+        let mut character = Character::new(String::from("Valeros"), String::from("heroes"), 20);
+        character.hp = 5;
+        let id = character.id.clone();
+        world.characters.insert(id.clone(), character);
+        let potion = world.spawn_item("minor-healing-potion").unwrap();
+        add_item(&id, &potion, &mut world).unwrap();
+
+        // drawn first
+        let character = world.get_character(&id).clone();
+        let plan = plan_turn(&character, 3, &mut world);
+        assert_eq!(
+            plan.activities,
+            vec!["Draw Minor Healing Potion", "Drink Minor Healing Potion"]
+        );
+        let mut draw = find(&character, &world, "Draw Minor Healing Potion");
+        assert_eq!(
+            draw.ai_utility(&character, &world),
+            Some(Utility::default())
+        );
+        perform(&mut draw, &character, &mut world, &mut fact::Facts::new());
+
+        let character = world.get_character(&id).clone();
+        let mut drink = find(&character, &world, "Drink Minor Healing Potion");
+        assert_eq!(drink.ai_utility(&character, &world).unwrap().buff, 4.5);
+        perform(&mut drink, &character, &mut world, &mut fact::Facts::new());
+        let character = world.get_character(&id);
+        assert_eq!(character.hp, 5 + 6);
+        assert_eq!(character.loadout.right_hand, None);
+        assert!(!world.items.contains_key(&potion));
+    }
+}
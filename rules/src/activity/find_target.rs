@@ -1,3 +1,4 @@
+use crate::character::Character;
 use crate::timeline::CharacterId;
 use crate::world::World;
 /**
@@ -9,45 +10,59 @@ Plenty of ideas:
 ...
 should read about it
 */
-pub fn find_first_conscious_enemy(party: &str, world: &World) -> Option<CharacterId> {
-    let characters = world.get_characters();
-    let ids: Vec<String> = characters
+// p457 the conscious enemies in line of effect, sorted by id.
+pub fn find_conscious_enemies(character: &Character, world: &World) -> Vec<CharacterId> {
+    let mut ids: Vec<String> = world
+        .get_characters()
         .iter()
-        .filter(|c| c.party != party)
+        .filter(|c| c.party != character.party)
         .filter(|c| c.hp > 0)
+        .filter(|c| {
+            world
+                .map
+                .has_line_of_effect(&character.position, &c.position)
+        })
         .map(|c| String::from(&c.id))
         .collect();
-    // find unconscious
-    if ids.len() == 0 {
-        return find_first_enemy(party, world);
-    }
-    return Some(ids[0].clone());
+    ids.sort();
+    ids
 }
 
-pub fn find_first_enemy(party: &str, world: &World) -> Option<CharacterId> {
-    let characters = world.get_characters();
-    let ids: Vec<String> = characters
-        .iter()
-        .filter(|c| c.party != party)
-        .map(|c| String::from(&c.id))
-        .collect();
-    // find unconscious
-    if ids.len() == 0 {
-        return None;
-    }
-    return Some(ids[0].clone());
+pub fn find_conscious_enemies_in_reach(
+    character: &Character,
+    reach: i64,
+    world: &World,
+) -> Vec<CharacterId> {
+    find_conscious_enemies(character, world)
+        .into_iter()
+        .filter(|id| {
+            character
+                .position
+                .is_within_reach(&world.get_character(id).position, reach)
+        })
+        .collect()
 }
 
-pub fn find_all_friends(party: &str, world: &World) -> Vec<CharacterId> {
+pub fn find_first_conscious_enemy(character: &Character, world: &World) -> Option<CharacterId> {
+    find_conscious_enemies(character, world).into_iter().next()
+}
+
+pub fn find_first_conscious_enemy_in_reach(
+    character: &Character,
+    reach: i64,
+    world: &World,
+) -> Option<CharacterId> {
+    find_conscious_enemies_in_reach(character, reach, world)
+        .into_iter()
+        .next()
+}
+
+pub fn find_closest_conscious_enemy(character: &Character, world: &World) -> Option<CharacterId> {
     world
         .get_characters()
         .iter()
-        .filter_map(|c| {
-            if c.party == party {
-                Some(c.id.to_string())
-            } else {
-                None
-            }
-        })
-        .collect()
+        .filter(|c| c.party != character.party)
+        .filter(|c| c.hp > 0)
+        .min_by_key(|c| (character.position.distance(&c.position), c.id.clone()))
+        .map(|c| String::from(&c.id))
 }
@@ -0,0 +1,107 @@
+use crate::{
+    ai::Utility,
+    character::Character,
+    fact,
+    inventory::{get_free_hands, grip_in_both_hands},
+    item::{weapon::WeaponItem, ItemId},
+    utils::get_weapon,
+    world::World,
+};
+
+use super::{ActionTrait, Activity};
+
+/**
+p272 Interact to change the grip on a weapon with the two-hand trait, from one hand to both.
+Its damage die becomes the two-hand one. Going back to one hand is a Release.
+*/
+#[derive(Clone, Debug)]
+pub struct Action {
+    name: String,
+    item_id: ItemId,
+}
+
+impl Action {
+    pub fn new(item_id: &str, weapon: &WeaponItem) -> Self {
+        Self {
+            name: format!("Grip {} (two hands)", weapon.info.name),
+            item_id: String::from(item_id),
+        }
+    }
+}
+
+impl Activity for Action {
+    fn can_be_used(&self, character: &Character, world: &World) -> bool {
+        let loadout = &character.loadout;
+        character.hp > 0
+            && get_free_hands(loadout) == 1
+            && [&loadout.right_hand, &loadout.left_hand]
+                .iter()
+                .any(|hand| hand.as_deref() == Some(self.item_id.as_str()))
+            && get_weapon(&self.item_id, world)
+                .get_two_hand_die()
+                .is_some()
+    }
+
+    // worth what it adds to the Strikes that follow it in the turn.
+    fn ai_utility(&self, _character: &Character, _world: &World) -> Option<Utility> {
+        Some(Utility::default())
+    }
+
+    fn resolve(&mut self, character: &Character, world: &mut World, facts: &mut fact::Facts) {
+        if let Err(e) = grip_in_both_hands(&character.id, &self.item_id, world) {
+            facts.info(&format!("\t{} cannot change grip: {}", character.name, e));
+        }
+    }
+
+    fn get_traits(&self) -> Vec<ActionTrait> {
+        vec![ActionTrait::Manipulate]
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{activity::perform, ai::plan_turn, inventory::wield, position::Position};
+
+    #[test]
+    fn two_hand_grip() {
+        let mut world = World::new();
+        let fighter = world.add_character_at("Valeros", "heroes", 50, Position::new(0, 0));
+        let goblin = world.add_character_at("Goblin", "goblins", 50, Position::new(1, 0));
+        let sword = world.spawn_item("bastard-sword").unwrap();
+        wield(&fighter, &sword, &mut world).unwrap();
+
+        let strike = |world: &mut World| {
+            let character = world.get_character(&fighter).clone();
+            let mut strike = character
+                .get_activities(world)
+                .into_iter()
+                .find(|a| a.get_name() == "Strike (Bastard Sword)")
+                .unwrap();
+            let hp = world.get_character(&goblin).hp;
+            perform(&mut strike, &character, world, &mut fact::Facts::new());
+            hp - world.get_character(&goblin).hp
+        };
+        // 1d8 in one hand, 1d12 in both
+        assert_eq!(strike(&mut world), 6);
+        let mut grip: Box<dyn Activity> = Box::new(Action::new(&sword, get_weapon(&sword, &world)));
+        let character = world.get_character(&fighter).clone();
+        assert!(grip.can_be_used(&character, &world));
+        assert_eq!(
+            grip.ai_utility(&character, &world),
+            Some(Utility::default())
+        );
+        // 2 Strikes of 1d12 are worth less than 3 of 1d8, the AI keeps its actions to strike.
+        assert_eq!(
+            plan_turn(&character, 3, &mut world).activities,
+            vec!["Strike (Bastard Sword)"; 3]
+        );
+        perform(&mut grip, &character, &mut world, &mut fact::Facts::new());
+        assert!(!grip.can_be_used(world.get_character(&fighter), &world));
+        assert_eq!(strike(&mut world), 10);
+    }
+}
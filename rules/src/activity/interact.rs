@@ -0,0 +1,85 @@
+use crate::{
+    ai::Utility,
+    character::Character,
+    fact,
+    inventory::{draw, get_free_hands, get_hands, stow},
+    item::{AnyItem, ItemId},
+    world::World,
+};
+
+use super::{ActionTrait, Activity};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Interaction {
+    Draw,
+    Stow,
+}
+
+/**
+p470 Interact to draw an item from the inventory, or to stow a held one.
+*/
+#[derive(Clone, Debug)]
+pub struct Action {
+    name: String,
+    interaction: Interaction,
+    item_id: ItemId,
+}
+
+impl Action {
+    pub fn new(interaction: Interaction, item_id: &str, item: &AnyItem) -> Self {
+        Self {
+            name: format!("{:?} {}", interaction, item.get_info().name),
+            interaction,
+            item_id: String::from(item_id),
+        }
+    }
+}
+
+impl Activity for Action {
+    fn can_be_used(&self, character: &Character, world: &World) -> bool {
+        let loadout = &character.loadout;
+        let hands = [&loadout.right_hand, &loadout.left_hand];
+        character.hp > 0
+            && match self.interaction {
+                Interaction::Draw => {
+                    character.inventory.contains(&self.item_id)
+                        && world
+                            .items
+                            .get(&self.item_id)
+                            .is_some_and(|item| get_free_hands(loadout) >= get_hands(item))
+                }
+                Interaction::Stow => hands
+                    .iter()
+                    .any(|hand| **hand == Some(self.item_id.clone())),
+            }
+    }
+
+    /**
+    Drawing is worth what the item is used for in the rest of the turn, like drinking a potion.
+    Stowing is left to players.
+    */
+    fn ai_utility(&self, _character: &Character, _world: &World) -> Option<Utility> {
+        match self.interaction {
+            Interaction::Draw => Some(Utility::default()),
+            Interaction::Stow => None,
+        }
+    }
+
+    fn resolve(&mut self, character: &Character, world: &mut World, facts: &mut fact::Facts) {
+        let result = match self.interaction {
+            Interaction::Draw => draw(&character.id, &self.item_id, world),
+            Interaction::Stow => stow(&character.id, &self.item_id, world),
+        };
+        if let Err(e) = result {
+            facts.info(&format!("\t{} cannot interact: {}", character.name, e));
+        }
+    }
+
+    fn get_traits(&self) -> Vec<ActionTrait> {
+        vec![ActionTrait::Manipulate]
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{character::Character, fact, world::World};
+use crate::{ai::Utility, character::Character, fact, world::World};
 
 use super::Activity;
 
@@ -16,8 +16,8 @@ impl Activity for Action {
         true
     }
 
-    fn ai_playing_value(&self, _character: &Character, _context: &World) -> i64 {
-        1
+    fn ai_utility(&self, _character: &Character, _context: &World) -> Option<Utility> {
+        Some(Utility::default())
     }
 
     fn resolve<'lworld>(
@@ -0,0 +1,69 @@
+use crate::{ai::Utility, character::Character, fact, utils::get_melee_weapon, world::World};
+
+use super::{
+    attack::{find_best_target, find_target, strike_with, StrikeModifiers},
+    check_traits, ActionTrait, Activity,
+};
+
+// p143 Power Attack: a melee Strike with an extra weapon damage die.
+#[derive(Clone, Debug)]
+pub struct Action;
+
+impl Action {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+// the extra dice grow at 10th and 18th level.
+fn get_extra_dice(level: i64) -> i64 {
+    match level {
+        l if l >= 18 => 3,
+        l if l >= 10 => 2,
+        _ => 1,
+    }
+}
+
+impl Activity for Action {
+    fn can_be_used(&self, character: &Character, world: &World) -> bool {
+        character.hp > 0
+            && character.creature.is_none()
+            && get_melee_weapon(character, world)
+                .is_some_and(|(weapon, _)| find_target(character, weapon, world).is_some())
+            && check_traits(&self.get_traits(), character)
+    }
+
+    fn ai_utility(&self, character: &Character, world: &World) -> Option<Utility> {
+        let extra_dice = get_extra_dice(character.level);
+        let best = get_melee_weapon(character, world)
+            .and_then(|(weapon, _)| find_best_target(character, weapon, None, extra_dice, world));
+        Some(best.map_or(Utility::default(), |(_, utility)| utility))
+    }
+
+    fn resolve(&mut self, source: &Character, world: &mut World, facts: &mut fact::Facts) {
+        let weapon = match get_melee_weapon(source, world) {
+            Some((weapon, _)) => weapon.clone(),
+            None => return,
+        };
+        let extra_dice = get_extra_dice(source.level);
+        if let Some((id, _)) = find_best_target(source, &weapon, None, extra_dice, world) {
+            let modifiers = StrikeModifiers {
+                extra_dice,
+                ..StrikeModifiers::default()
+            };
+            strike_with(source, &id, &weapon, None, modifiers, world, facts);
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        "Power Attack"
+    }
+
+    fn get_cost(&self) -> i64 {
+        2
+    }
+
+    fn get_traits(&self) -> Vec<ActionTrait> {
+        vec![ActionTrait::Attack, ActionTrait::Flourish]
+    }
+}
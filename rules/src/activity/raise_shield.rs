@@ -0,0 +1,57 @@
+use crate::{
+    ai::{get_status_utility, Utility},
+    character::Character,
+    fact,
+    status::{apply_status, Duration, StatusEffect, StatusType},
+    utils::get_shield,
+    world::World,
+};
+
+use super::Activity;
+
+// p472 Raise a Shield: its circumstance bonus to AC applies until the start of your next turn.
+#[derive(Clone, Debug)]
+pub struct Action;
+
+impl Action {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Activity for Action {
+    fn can_be_used(&self, character: &Character, world: &World) -> bool {
+        if character.hp <= 0 || character.has_status(StatusType::ShieldRaised) {
+            return false;
+        }
+        match get_shield(character, world) {
+            Some(shield) => !shield.is_broken(),
+            None => false,
+        }
+    }
+
+    // worth the damage the enemies are expected to miss.
+    fn ai_utility(&self, character: &Character, world: &World) -> Option<Utility> {
+        Some(get_status_utility(
+            character,
+            character,
+            StatusType::ShieldRaised,
+            world,
+        ))
+    }
+
+    fn resolve(&mut self, character: &Character, world: &mut World, facts: &mut fact::Facts) {
+        if get_shield(character, world).is_none() {
+            return;
+        }
+        let shield_raised = StatusEffect {
+            duration: Duration::StartOfNextTurn,
+            status_type: StatusType::ShieldRaised,
+        };
+        apply_status(&character.id, shield_raised, world, facts);
+    }
+
+    fn get_name(&self) -> &str {
+        "Raise Shield"
+    }
+}
@@ -0,0 +1,64 @@
+use crate::{
+    ai::Utility,
+    character::Character,
+    fact,
+    inventory::{get_hands, release},
+    item::{AnyItem, ItemId},
+    world::World,
+};
+
+use super::Activity;
+
+/**
+p470 Release: a free action to let go of a held item, or of one hand of a two-hand grip.
+It has the manipulate trait but does not trigger reactions, so it is left without traits here.
+*/
+#[derive(Clone, Debug)]
+pub struct Action {
+    name: String,
+    item_id: ItemId,
+}
+
+impl Action {
+    pub fn new(item_id: &str, item: &AnyItem, character: &Character) -> Self {
+        let grip = get_hands(item) == 1 && character.loadout.is_held_in_both_hands(item_id);
+        let name = item.get_info().name.clone();
+        Self {
+            name: if grip {
+                format!("Release {} (one hand)", name)
+            } else {
+                format!("Release {}", name)
+            },
+            item_id: String::from(item_id),
+        }
+    }
+}
+
+impl Activity for Action {
+    fn can_be_used(&self, character: &Character, _world: &World) -> bool {
+        let loadout = &character.loadout;
+        character.hp > 0
+            && [&loadout.right_hand, &loadout.left_hand]
+                .iter()
+                .any(|hand| hand.as_deref() == Some(self.item_id.as_str()))
+    }
+
+    // dropping gear is left to players.
+    fn ai_utility(&self, _character: &Character, _world: &World) -> Option<Utility> {
+        None
+    }
+
+    fn resolve(&mut self, character: &Character, world: &mut World, facts: &mut fact::Facts) {
+        if let Err(e) = release(&character.id, &self.item_id, world) {
+            facts.info(&format!("\t{} cannot release: {}", character.name, e));
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_cost(&self) -> i64 {
+        0
+    }
+}
@@ -0,0 +1,46 @@
+use crate::{
+    ai::{get_change_utility, Utility},
+    character::Character,
+    fact,
+    status::{end_status, StatusType},
+    world::World,
+};
+
+use super::{ActionTrait, Activity};
+
+// p471 Stand: you stand up from prone.
+#[derive(Clone, Debug)]
+pub struct Action;
+
+impl Action {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Activity for Action {
+    fn can_be_used(&self, character: &Character, _world: &World) -> bool {
+        character.hp > 0 && character.has_status(StatusType::Prone)
+    }
+
+    // worth the damage the character avoids and deals once it is no longer prone.
+    fn ai_utility(&self, character: &Character, world: &World) -> Option<Utility> {
+        let mut standing = character.clone();
+        standing
+            .status
+            .retain(|s| s.status_type != StatusType::Prone);
+        Some(get_change_utility(character, character, &standing, world))
+    }
+
+    fn resolve(&mut self, character: &Character, world: &mut World, facts: &mut fact::Facts) {
+        end_status(&character.id, StatusType::Prone, world, facts);
+    }
+
+    fn get_traits(&self) -> Vec<ActionTrait> {
+        vec![ActionTrait::Move]
+    }
+
+    fn get_name(&self) -> &str {
+        "Stand"
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{
+    ai::Utility,
+    character::Character,
+    fact::{self, Fact},
+    flanking::find_flanking_path,
+    position::Position,
+    utils::{get_active_weapon, get_speed},
+    world::World,
+};
+
+use super::{find_target::find_closest_conscious_enemy, ActionTrait, Activity};
+
+/**
+Moves the character up to `distance` feet, until the target is within the reach of its weapon.
+Returns the squares entered, in order.
+*/
+pub(super) fn move_towards(
+    character: &Character,
+    target_id: &str,
+    distance: i64,
+    world: &mut World,
+) -> Vec<Position> {
+    let goal = world.get_character(target_id).position;
+    let reach = get_active_weapon(character, world).get_reach().max(5);
+    let start = character.position;
+    let mut position = start;
+    let mut path = vec![];
+    while !position.is_within_reach(&goal, reach) {
+        // go around the creatures in the way
+        let next = position
+            .neighbours()
+            .into_iter()
+            .filter(|p| start.distance(p) <= distance && !world.is_occupied(p))
+            .min_by_key(|p| (p.distance(&goal), *p));
+        match next {
+            Some(next) if next.distance(&goal) < position.distance(&goal) => {
+                position = next;
+                path.push(next);
+            }
+            _ => break,
+        }
+    }
+    world.get_mut_character(&character.id).position = position;
+    path
+}
+
+// p471 Stride: move up to your speed.
+#[derive(Clone, Debug)]
+pub struct Action;
+
+impl Action {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Activity for Action {
+    fn can_be_used(&self, character: &Character, world: &World) -> bool {
+        character.hp > 0
+            && character.speed > 0
+            && find_closest_conscious_enemy(character, world).is_some()
+    }
+
+    // worth getting closer to the enemies, and the flanked Strikes that follow it in the turn.
+    fn ai_utility(&self, _character: &Character, _world: &World) -> Option<Utility> {
+        Some(Utility::default())
+    }
+
+    // p476 melee units prefer a square where they flank the enemy.
+    fn resolve(&mut self, character: &Character, world: &mut World, facts: &mut fact::Facts) {
+        let target_id = match find_closest_conscious_enemy(character, world) {
+            Some(id) => id,
+            None => return,
+        };
+        let speed = get_speed(character, world);
+        let target = world.get_character(&target_id);
+        let flanking = if get_active_weapon(character, world).is_ranged {
+            None
+        } else {
+            find_flanking_path(character, target, speed, world)
+        };
+        let path = match flanking {
+            Some(path) => {
+                if let Some(position) = path.last() {
+                    world.get_mut_character(&character.id).position = *position;
+                }
+                path
+            }
+            None => move_towards(character, &target_id, speed, world),
+        };
+        facts.push(Fact::Move {
+            character_id: character.id.clone(),
+            path,
+        });
+    }
+
+    fn get_traits(&self) -> Vec<ActionTrait> {
+        vec![ActionTrait::Move]
+    }
+
+    fn get_name(&self) -> &str {
+        "Stride"
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{
+    ai::Utility,
+    character::Character,
+    fact::{self, Fact},
+    utils::{get_melee_weapon, get_speed},
+    world::World,
+};
+
+use super::{
+    attack::{expect_strike, get_strike_utility, strike, StrikeModifiers},
+    check_traits,
+    find_target::{find_closest_conscious_enemy, find_first_conscious_enemy_in_reach},
+    stride::move_towards,
+    ActionTrait, Activity,
+};
+
+// p144 Sudden Charge: Stride twice, then Strike if an enemy is within reach.
+#[derive(Clone, Debug)]
+pub struct Action;
+
+impl Action {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Activity for Action {
+    fn can_be_used(&self, character: &Character, world: &World) -> bool {
+        character.hp > 0
+            && character.speed > 0
+            && check_traits(&self.get_traits(), character)
+            && get_melee_weapon(character, world).is_some()
+            && find_closest_conscious_enemy(character, world).is_some()
+    }
+
+    // worth the Strike at the end of the charge, when the closest enemy is within two Strides.
+    fn ai_utility(&self, character: &Character, world: &World) -> Option<Utility> {
+        let target = match find_closest_conscious_enemy(character, world) {
+            Some(id) => world.get_character(&id),
+            None => return Some(Utility::default()),
+        };
+        let (weapon, creature_strike) = match get_melee_weapon(character, world) {
+            Some(melee) => melee,
+            None => return Some(Utility::default()),
+        };
+        let distance = character.position.distance(&target.position);
+        if distance > 2 * get_speed(character, world) + weapon.get_reach().max(5) {
+            return Some(Utility::default());
+        }
+        let odds = expect_strike(
+            character,
+            target,
+            weapon,
+            creature_strike,
+            StrikeModifiers::default(),
+            world,
+        );
+        Some(get_strike_utility(character, target, odds, world))
+    }
+
+    fn resolve(&mut self, character: &Character, world: &mut World, facts: &mut fact::Facts) {
+        let target_id = match find_closest_conscious_enemy(character, world) {
+            Some(id) => id,
+            None => return,
+        };
+        let speed = get_speed(character, world);
+        let path = move_towards(character, &target_id, 2 * speed, world);
+        facts.push(Fact::Move {
+            character_id: character.id.clone(),
+            path,
+        });
+        let character = world.get_character(&character.id).clone();
+        let reach = get_melee_weapon(&character, world).map_or(0, |(w, _)| w.get_reach());
+        if let Some(id) = find_first_conscious_enemy_in_reach(&character, reach, world) {
+            strike(&character, &id, world, facts);
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        "Sudden Charge"
+    }
+
+    fn get_cost(&self) -> i64 {
+        2
+    }
+
+    fn get_traits(&self) -> Vec<ActionTrait> {
+        vec![ActionTrait::Flourish, ActionTrait::Open, ActionTrait::Move]
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{
+    ai::{get_status_utility, Utility},
+    character::Character,
+    fact::{self, Fact},
+    world::World,
+};
+
+use super::{ActionTrait, Activity};
+
+/**
+p305 Sustain a Spell: a lasting emanation of the character grows, once per turn
+and not on the turn it was cast.
+*/
+#[derive(Clone, Debug)]
+pub struct Action {
+    name: String,
+    index: usize,
+}
+
+impl Action {
+    pub fn new(index: usize, spell_name: &str) -> Self {
+        Self {
+            name: format!("Sustain {}", spell_name),
+            index,
+        }
+    }
+}
+
+impl Activity for Action {
+    fn can_be_used(&self, character: &Character, _world: &World) -> bool {
+        character.hp > 0
+            && character
+                .emanations
+                .get(self.index)
+                .is_some_and(|e| e.sustain_growth > 0 && !e.sustained)
+    }
+
+    // worth the statuses of the spell on the creatures the bigger emanation reaches.
+    fn ai_utility(&self, character: &Character, world: &World) -> Option<Utility> {
+        let emanation = &character.emanations[self.index];
+        let mut grown = emanation.clone();
+        grown.radius += grown.sustain_growth;
+        let covered = emanation.find_creatures(character, world);
+        Some(
+            grown
+                .find_creatures(character, world)
+                .iter()
+                .filter(|id| !covered.contains(id))
+                .flat_map(|id| {
+                    emanation.statuses.iter().map(move |status_type| {
+                        get_status_utility(character, world.get_character(id), *status_type, world)
+                    })
+                })
+                .sum(),
+        )
+    }
+
+    fn resolve(&mut self, character: &Character, world: &mut World, facts: &mut fact::Facts) {
+        let caster = world.get_mut_character(&character.id);
+        let emanation = &mut caster.emanations[self.index];
+        emanation.radius += emanation.sustain_growth;
+        emanation.sustained = true;
+        facts.push(Fact::Sustain {
+            character_id: character.id.clone(),
+            spell: emanation.name.clone(),
+            radius: emanation.radius,
+        });
+    }
+
+    fn get_traits(&self) -> Vec<ActionTrait> {
+        vec![ActionTrait::Concentrate]
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{
+    ai::{get_status_utility, Utility},
+    character::Character,
+    cover::{get_cover, Cover},
+    fact,
+    status::{apply_status, Duration, StatusEffect, StatusType},
+    world::World,
+};
+
+use super::Activity;
+
+/**
+p471 Take Cover: standard cover becomes greater cover, and a prone creature gets standard cover
+against ranged attacks. It lasts until the creature moves or attacks.
+*/
+#[derive(Clone, Debug)]
+pub struct Action;
+
+impl Action {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Activity for Action {
+    fn can_be_used(&self, character: &Character, world: &World) -> bool {
+        if character.hp <= 0 || character.has_status(StatusType::TakingCover) {
+            return false;
+        }
+        character.has_status(StatusType::Prone)
+            || world
+                .get_characters()
+                .iter()
+                .filter(|c| c.hp > 0 && c.party != character.party)
+                .any(|c| get_cover(c, character, false, world) >= Cover::Standard)
+    }
+
+    // worth the damage the enemies are expected to miss.
+    fn ai_utility(&self, character: &Character, world: &World) -> Option<Utility> {
+        Some(get_status_utility(
+            character,
+            character,
+            StatusType::TakingCover,
+            world,
+        ))
+    }
+
+    fn resolve(&mut self, character: &Character, world: &mut World, facts: &mut fact::Facts) {
+        let taking_cover = StatusEffect {
+            duration: Duration::UntilRemoved,
+            status_type: StatusType::TakingCover,
+        };
+        apply_status(&character.id, taking_cover, world, facts);
+    }
+
+    fn get_name(&self) -> &str {
+        "Take Cover"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        activity::{perform, stand},
+        map::Map,
+        position::Position,
+    };
+
+    #[test]
+    fn take_cover() {
+        let mut world = World::new();
+        world.map = Map::new().add_wall(Position::new(1, 0), Position::new(1, 1));
+        let archer = world.add_character_at("Archer", "goblins", 10, Position::new(0, 0));
+        let fighter = world.add_character_at("Valeros", "heroes", 10, Position::new(3, 1));
+
+        let mut activity: Box<dyn Activity> = Box::new(Action::new());
+        let character = world.get_character(&fighter).clone();
+        assert!(activity.can_be_used(&character, &world));
+        perform(
+            &mut activity,
+            &character,
+            &mut world,
+            &mut fact::Facts::new(),
+        );
+        let cover = |world: &World| {
+            let archer = world.get_character(&archer);
+            get_cover(archer, world.get_character(&fighter), true, world)
+        };
+        assert_eq!(cover(&world), Cover::Greater);
+        assert!(!activity.can_be_used(world.get_character(&fighter), &world));
+
+        // moving ends it
+        let mut activity: Box<dyn Activity> = Box::new(stand::Action::new());
+        let character = world.get_character(&fighter).clone();
+        perform(
+            &mut activity,
+            &character,
+            &mut world,
+            &mut fact::Facts::new(),
+        );
+        assert_eq!(cover(&world), Cover::Standard);
+    }
+}
@@ -0,0 +1,191 @@
+use crate::{
+    ai::Utility,
+    character::Character,
+    check::DegreeOfSuccess,
+    damage::{adjust_damage, apply_damage, Damage},
+    fact,
+    inventory::{get_held_consumables, use_charge},
+    item::{
+        consumable::{ConsumableEffect, ConsumableItem},
+        weapon::WeaponItem,
+        ItemId,
+    },
+    world::World,
+};
+
+use super::{
+    attack::{expect_strike, get_strike_utility, strike_with, StrikeModifiers},
+    find_target::find_first_conscious_enemy,
+    ActionTrait, Activity,
+};
+
+/**
+p544 Strike with a held alchemical bomb. Unless the attack is a critical failure,
+the splash damage hits the target and every creature within 5 feet of it.
+*/
+#[derive(Clone, Debug)]
+pub struct Action {
+    name: String,
+    item_id: ItemId,
+}
+
+impl Action {
+    pub fn new(item_id: &str, bomb: &ConsumableItem) -> Self {
+        Self {
+            name: format!("Throw {}", bomb.info.name),
+            item_id: String::from(item_id),
+        }
+    }
+
+    fn get_bomb(&self, character: &Character, world: &World) -> Option<(WeaponItem, i64)> {
+        get_held_consumables(character, world)
+            .into_iter()
+            .find(|(id, _)| *id == self.item_id)
+            .and_then(|(_, item)| match item.effect {
+                ConsumableEffect::Bomb { splash, .. } => Some((item.get_bomb()?, splash)),
+                _ => None,
+            })
+    }
+}
+
+fn splash(
+    source: &Character,
+    target_id: &str,
+    bomb: &WeaponItem,
+    amount: i64,
+    world: &mut World,
+    facts: &mut fact::Facts,
+) {
+    let center = world.get_character(target_id).position;
+    let mut ids: Vec<String> = world
+        .get_characters()
+        .iter()
+        .filter(|c| c.hp > 0 && c.position.is_within_reach(&center, 5))
+        .map(|c| c.id.clone())
+        .collect();
+    ids.sort();
+    for id in ids {
+        let damage_type = bomb.damage.damage_type;
+        let amount = adjust_damage(world.get_character(&id), damage_type, amount);
+        let damage = Damage::new(amount).of_type(damage_type).details("splash");
+        apply_damage(&source.id, &id, damage, world, facts);
+    }
+}
+
+impl Activity for Action {
+    fn can_be_used(&self, character: &Character, world: &World) -> bool {
+        character.hp > 0
+            && self.get_bomb(character, world).is_some()
+            && find_first_conscious_enemy(character, world).is_some()
+    }
+
+    // worth the Strike, and the splash on every creature around the target, allies included.
+    fn ai_utility(&self, character: &Character, world: &World) -> Option<Utility> {
+        let (bomb, splash_damage) = match self.get_bomb(character, world) {
+            Some(bomb) => bomb,
+            None => return Some(Utility::default()),
+        };
+        let target = match find_first_conscious_enemy(character, world) {
+            Some(id) => world.get_character(&id),
+            None => return Some(Utility::default()),
+        };
+        let odds = expect_strike(
+            character,
+            target,
+            &bomb,
+            None,
+            StrikeModifiers::default(),
+            world,
+        );
+        let splashed: Utility = world
+            .get_characters()
+            .into_iter()
+            .filter(|c| c.hp > 0 && c.position.is_within_reach(&target.position, 5))
+            .map(|c| {
+                let amount = adjust_damage(c, bomb.damage.damage_type, splash_damage) as f64;
+                Utility {
+                    damage: if c.party == character.party {
+                        -amount
+                    } else {
+                        amount
+                    },
+                    ..Utility::default()
+                }
+            })
+            .sum();
+        Some(
+            get_strike_utility(character, target, odds, world)
+                + splashed * (1.0 - odds.critical_failure),
+        )
+    }
+
+    fn resolve(&mut self, character: &Character, world: &mut World, facts: &mut fact::Facts) {
+        let (bomb, splash_damage) = match self.get_bomb(character, world) {
+            Some(bomb) => bomb,
+            None => return,
+        };
+        let target_id = match find_first_conscious_enemy(character, world) {
+            Some(id) => id,
+            None => return,
+        };
+        use_charge(&character.id, &self.item_id, world);
+        let degree = strike_with(
+            character,
+            &target_id,
+            &bomb,
+            None,
+            StrikeModifiers::default(),
+            world,
+            facts,
+        );
+        if degree != DegreeOfSuccess::CriticalFailure {
+            splash(character, &target_id, &bomb, splash_damage, world, facts);
+        }
+    }
+
+    fn get_traits(&self) -> Vec<ActionTrait> {
+        vec![ActionTrait::Attack, ActionTrait::Manipulate]
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{activity::perform, item::AnyItem, position::Position};
+
+    #[test]
+    fn throw_bomb() {
+        let mut world = World::new();
+        let alchemist = world.add_character_at("Fumbus", "goblins", 30, Position::new(0, 0));
+        let fighter = world.add_character_at("Valeros", "heroes", 30, Position::new(4, 0));
+        let goblin = world.add_character_at("Goblin", "goblins", 30, Position::new(5, 0));
+        let archer = world.add_character_at("Archer", "goblins", 30, Position::new(7, 0));
+        let bomb = world.spawn_item("lesser-alchemists-fire").unwrap();
+        world.get_mut_character(&alchemist).loadout.right_hand = Some(bomb.clone());
+
+        let mut activity: Box<dyn Activity> = match world.items.get(&bomb) {
+            Some(AnyItem::ConsumableItem(item)) => Box::new(Action::new(&bomb, item)),
+            _ => unreachable!(),
+        };
+        assert_eq!(activity.get_name(), "Throw Lesser Alchemist's Fire");
+        let character = world.get_character(&alchemist).clone();
+        assert!(activity.can_be_used(&character, &world));
+        perform(
+            &mut activity,
+            &character,
+            &mut world,
+            &mut fact::Facts::new(),
+        );
+        let hp = |id: &str| world.get_character(id).hp;
+        // a hit for 6 and 1 splash, the splash also burns the goblin next to it
+        assert_eq!(hp(&fighter), 30 - 6 - 1);
+        assert_eq!(hp(&goblin), 30 - 1);
+        assert_eq!(hp(&archer), 30);
+        assert!(!world.items.contains_key(&bomb));
+        assert_eq!(world.get_character(&alchemist).loadout.right_hand, None);
+    }
+}
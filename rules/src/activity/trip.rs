@@ -0,0 +1,115 @@
+use dice::Roll;
+
+use crate::{
+    ai::{get_status_utility, Utility},
+    character::{skill::Skill, Character},
+    check::{get_chances, DegreeOfSuccess},
+    damage::{apply_damage, Damage},
+    defense::{compute_save, Save},
+    fact::{self, Fact},
+    status::{apply_status, Duration, StatusEffect, StatusType},
+    timeline::CharacterId,
+    world::World,
+};
+
+use super::{ActionTrait, Activity};
+
+// p243 Trip: an Athletics check against the Reflex DC of an adjacent creature.
+#[derive(Clone, Debug)]
+pub struct Action;
+
+impl Action {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+fn find_target(character: &Character, world: &World) -> Option<CharacterId> {
+    let mut ids: Vec<CharacterId> = world
+        .get_characters()
+        .iter()
+        .filter(|c| c.party != character.party && c.hp > 0)
+        .filter(|c| !c.has_status(StatusType::Prone))
+        .filter(|c| character.position.is_within_reach(&c.position, 5))
+        .map(|c| c.id.clone())
+        .collect();
+    ids.sort();
+    ids.into_iter().next()
+}
+
+fn knock_prone(id: &str, world: &mut World, facts: &mut fact::Facts) {
+    let prone = StatusEffect {
+        duration: Duration::UntilRemoved,
+        status_type: StatusType::Prone,
+    };
+    apply_status(id, prone, world, facts);
+}
+
+impl Activity for Action {
+    fn can_be_used(&self, character: &Character, world: &World) -> bool {
+        character.hp > 0 && find_target(character, world).is_some()
+    }
+
+    /**
+    Worth knocking the target prone, with the 1d6 damage of a critical success,
+    from the chances of the Athletics check. A critical failure knocks the character prone instead.
+    */
+    fn ai_utility(&self, character: &Character, world: &World) -> Option<Utility> {
+        let target = match find_target(character, world) {
+            Some(id) => world.get_character(&id),
+            None => return Some(Utility::default()),
+        };
+        let dc = 10 + compute_save(target, Save::Reflex, world).roll();
+        let chances = get_chances(character.get_skill_modifier(Skill::Athletics), dc);
+        let prone = get_status_utility(character, target, StatusType::Prone, world);
+        let damage = Utility {
+            damage: Roll::d("", 1, 6).average(),
+            ..Utility::default()
+        };
+        let fallen = get_status_utility(character, character, StatusType::Prone, world);
+        Some(
+            (prone + damage) * chances[DegreeOfSuccess::CriticalSuccess as usize]
+                + prone * chances[DegreeOfSuccess::Success as usize]
+                + fallen * chances[DegreeOfSuccess::CriticalFailure as usize],
+        )
+    }
+
+    fn resolve(&mut self, character: &Character, world: &mut World, facts: &mut fact::Facts) {
+        let target_id = match find_target(character, world) {
+            Some(id) => id,
+            None => return,
+        };
+        let target = world.get_character(&target_id);
+        let target_name = target.name.clone();
+        let dc = 10 + compute_save(target, Save::Reflex, world).roll();
+        let natural = Roll::d("", 1, 20).roll();
+        let total = natural + character.get_skill_modifier(Skill::Athletics);
+        let degree = DegreeOfSuccess::from_check(total, natural, dc);
+        facts.push(Fact::Check {
+            character_id: character.id.clone(),
+            check: format!("trips {}", target_name),
+            total,
+            dc,
+            degree,
+        });
+        match degree {
+            DegreeOfSuccess::CriticalSuccess => {
+                knock_prone(&target_id, world, facts);
+                let roll = Roll::d("", 1, 6);
+                let damage = Damage::new(roll.roll()).details(&roll.to_string());
+                apply_damage(&character.id, &target_id, damage, world, facts);
+            }
+            DegreeOfSuccess::Success => knock_prone(&target_id, world, facts),
+            DegreeOfSuccess::Failure => {}
+            DegreeOfSuccess::CriticalFailure => knock_prone(&character.id, world, facts),
+        }
+    }
+
+    fn get_traits(&self) -> Vec<ActionTrait> {
+        vec![ActionTrait::Attack]
+    }
+
+    fn get_name(&self) -> &str {
+        "Trip"
+    }
+}
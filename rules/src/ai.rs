@@ -0,0 +1,457 @@
+use std::{iter, mem, ops, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    activity::{
+        attack::expect_best_strike_damage, can_choose, find_target::find_closest_conscious_enemy,
+        perform,
+    },
+    character::Character,
+    fact::Facts,
+    reaction::AiReactionPolicy,
+    spell::get_emanation_statuses,
+    status::{Duration, StatusEffect, StatusType},
+    utils::get_active_weapon,
+    world::World,
+};
+
+use self::mcts::Budget;
+pub mod mcts;
+
+// how many activities ahead the AI looks, free actions included.
+const MAX_STEPS: usize = 5;
+// how many times an activity is played to see it take a creature out, and to see it fail to.
+const ATTEMPTS: usize = 4;
+// the dice of the plans are seeded apart from the dice of the game, the AI cannot foresee its rolls.
+const PLANNING_SEED: u64 = 0x5851_F42D_4C95_7F2D;
+
+/**
+What a creature cares about: the weights of the utility of its activities, in damage dealt.
+The defaults weigh an enemy taken out like 5 damage. A brute cares more for damage and kills,
+a cautious creature for the damage it avoids.
+*/
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Personality {
+    pub damage: f64,
+    pub kill: f64,
+    pub threat: f64,
+    pub buff: f64,
+}
+
+impl Default for Personality {
+    fn default() -> Self {
+        Personality {
+            damage: 1.0,
+            kill: 5.0,
+            threat: 1.0,
+            buff: 1.0,
+        }
+    }
+}
+
+/**
+What an activity is expected to bring, computed from the chances of the dice instead of rolling them.
+*/
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Utility {
+    // damage dealt to the enemies, less the damage dealt to allies.
+    pub damage: f64,
+    // enemies taken out, less the allies.
+    pub kill: f64,
+    // damage the enemies will not deal during their next turns.
+    pub threat: f64,
+    // HP and bonuses given to allies, in HP and in damage they will deal more.
+    pub buff: f64,
+}
+
+impl Utility {
+    pub fn score(&self, personality: &Personality) -> f64 {
+        self.damage * personality.damage
+            + self.kill * personality.kill
+            + self.threat * personality.threat
+            + self.buff * personality.buff
+    }
+}
+
+impl ops::Add for Utility {
+    type Output = Utility;
+
+    fn add(self, other: Utility) -> Utility {
+        Utility {
+            damage: self.damage + other.damage,
+            kill: self.kill + other.kill,
+            threat: self.threat + other.threat,
+            buff: self.buff + other.buff,
+        }
+    }
+}
+
+// an outcome weighed by its chance.
+impl ops::Mul<f64> for Utility {
+    type Output = Utility;
+
+    fn mul(self, chance: f64) -> Utility {
+        Utility {
+            damage: self.damage * chance,
+            kill: self.kill * chance,
+            threat: self.threat * chance,
+            buff: self.buff * chance,
+        }
+    }
+}
+
+impl iter::Sum for Utility {
+    fn sum<I: Iterator<Item = Utility>>(iter: I) -> Utility {
+        iter.fold(Utility::default(), |a, b| a + b)
+    }
+}
+
+/**
+The damage the attacker is expected to deal to the target during its next turn, with a Strike of
+its best weapon for every action. A rough estimate: the attacker may have to move first.
+p622 a prone attacker spends one of its actions to Stand.
+*/
+pub(crate) fn get_threat(attacker: &Character, target: &Character, world: &World) -> f64 {
+    if attacker.hp <= 0 || attacker.party == target.party {
+        return 0.0;
+    }
+    let strikes = if attacker.has_status(StatusType::Prone) {
+        2.0
+    } else {
+        3.0
+    };
+    strikes * expect_best_strike_damage(attacker, target, world)
+}
+
+// the damage the enemies of the character are expected to deal to it, if they all went for it.
+pub(crate) fn get_incoming_threat(character: &Character, world: &World) -> f64 {
+    world
+        .get_characters()
+        .into_iter()
+        .map(|enemy| get_threat(enemy, character, world))
+        .sum()
+}
+
+// the damage the character is expected to deal to its closest enemy during its next turn.
+pub(crate) fn get_offense(character: &Character, world: &World) -> f64 {
+    find_closest_conscious_enemy(character, world).map_or(0.0, |id| {
+        get_threat(character, world.get_character(&id), world)
+    })
+}
+
+/**
+What a change to a creature, like a status, is worth to the character.
+For its allies, the damage they will deal more and avoid. For its enemies, the damage they will deal less.
+*/
+pub(crate) fn get_change_utility(
+    character: &Character,
+    before: &Character,
+    after: &Character,
+    world: &World,
+) -> Utility {
+    if before.party == character.party {
+        Utility {
+            threat: get_incoming_threat(before, world) - get_incoming_threat(after, world),
+            buff: get_offense(after, world) - get_offense(before, world),
+            ..Utility::default()
+        }
+    } else {
+        Utility {
+            threat: get_offense(before, world) - get_offense(after, world),
+            ..Utility::default()
+        }
+    }
+}
+
+// a status given to a creature, it is worth nothing to a creature that has it already.
+pub(crate) fn get_status_utility(
+    character: &Character,
+    target: &Character,
+    status_type: StatusType,
+    world: &World,
+) -> Utility {
+    if target.has_status(status_type)
+        || get_emanation_statuses(target, world).contains(&status_type)
+    {
+        return Utility::default();
+    }
+    let mut after = target.clone();
+    after.status.push(StatusEffect {
+        duration: Duration::Round(1),
+        status_type,
+    });
+    get_change_utility(character, target, &after, world)
+}
+
+// the HP given back to an ally, and its Strikes when it gets back on its feet.
+pub(crate) fn get_healing_utility(patient: &Character, amount: f64, world: &World) -> Utility {
+    let healed = amount.min((patient.max_hp - patient.hp.max(0)) as f64);
+    let mut buff = healed;
+    if patient.hp <= 0 && healed >= 1.0 {
+        let mut conscious = patient.clone();
+        conscious.hp = 1;
+        buff += get_offense(&conscious, world);
+    }
+    Utility {
+        buff,
+        ..Utility::default()
+    }
+}
+
+/**
+Where the character ends its turn: melee creatures want to get close to their enemies,
+0.1 per 5 feet out of reach. A creature taken out during its own turn counts as a kill for the enemies.
+*/
+fn get_position_value(character: &Character, personality: &Personality, world: &World) -> f64 {
+    if character.hp <= 0 {
+        return -personality.kill;
+    }
+    let weapon = get_active_weapon(character, world);
+    if weapon.is_ranged {
+        return 0.0;
+    }
+    match find_closest_conscious_enemy(character, world) {
+        Some(id) => {
+            let distance = character
+                .position
+                .distance(&world.get_character(&id).position);
+            -0.1 * ((distance - weapon.get_reach().max(5)).max(0) / 5) as f64
+        }
+        None => 0.0,
+    }
+}
+
+/**
+How a creature plans its turns. The utility planner scores its activities before playing them,
+the search plays whole turns and the answers of the other creatures: stronger and slower, for bosses.
+*/
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Planner {
+    #[default]
+    Utility,
+    Search(Budget),
+}
+
+// the rest of a turn as planned by the AI.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Plan {
+    // the index of the first activity among the activities of the character, none to pass.
+    pub first: Option<usize>,
+    pub activities: Vec<String>,
+    pub score: f64,
+}
+
+/**
+Plans the rest of the turn of a character with its planner, and the weights of its personality.
+The utility planner plays every sequence of activities on the world, each activity is scored before
+it is played, and the sequence worth the most is kept. Passing is kept unless something is worth more.
+The world is given back as it was, and the plans roll their own dice: planning does not change
+the rolls of the game.
+*/
+pub fn plan_turn(character: &Character, action_left: i64, world: &mut World) -> Plan {
+    let snapshot = world.snapshot();
+    // the players would be asked about the reactions of the plans of the AI.
+    let reaction_policy = mem::replace(&mut world.reaction_policy, Arc::new(AiReactionPolicy));
+    let dice = dice::get_state();
+    let seed = dice.unwrap_or(0) ^ PLANNING_SEED;
+    let plan = match character.planner {
+        Planner::Utility => search(
+            &character.id,
+            action_left,
+            MAX_STEPS,
+            &character.personality,
+            seed,
+            world,
+        ),
+        Planner::Search(budget) => mcts::search(&character.id, action_left, budget, seed, world),
+    };
+    dice::set_state(dice);
+    world.reaction_policy = reaction_policy;
+    world.restore(snapshot);
+    plan
+}
+
+fn count_conscious(world: &World) -> usize {
+    world.get_characters().iter().filter(|c| c.hp > 0).count()
+}
+
+fn search(
+    id: &str,
+    action_left: i64,
+    steps: usize,
+    personality: &Personality,
+    seed: u64,
+    world: &mut World,
+) -> Plan {
+    let character = world.get_character(id).clone();
+    let mut best = Plan {
+        first: None,
+        activities: vec![],
+        score: get_position_value(&character, personality, world),
+    };
+    if action_left <= 0 || steps == 0 || character.hp <= 0 {
+        return best;
+    }
+    let activities = character.get_activities(world);
+    for (index, activity) in activities.iter().enumerate() {
+        if !can_choose(activity.as_ref(), &character, action_left, world) {
+            continue;
+        }
+        let utility = match activity.ai_utility(&character, world) {
+            Some(utility) => utility,
+            None => continue,
+        };
+        // the rest of the turn once no creature is taken out, and once one is.
+        let mut outcomes: [Option<Plan>; 2] = [None, None];
+        let kill = utility.kill.abs().min(1.0);
+        // the last activity of the turn is played once, nothing is planned after it.
+        let attempts = if action_left > activity.get_cost() {
+            ATTEMPTS
+        } else {
+            1
+        };
+        for attempt in 0..attempts {
+            // the activities played at the same step roll the same dice, so that no plan is
+            // chosen for its luck.
+            dice::seed(seed.wrapping_add((steps * ATTEMPTS + attempt) as u64));
+            let snapshot = world.snapshot();
+            let conscious = count_conscious(world);
+            // a fresh activity, in case it keeps what happened.
+            let mut played = character.get_activities(world).swap_remove(index);
+            perform(&mut played, &character, world, &mut Facts::new());
+            let down = (count_conscious(world) < conscious) as usize;
+            if outcomes[down].is_none() {
+                outcomes[down] = Some(search(
+                    id,
+                    action_left - activity.get_cost(),
+                    steps - 1,
+                    personality,
+                    seed,
+                    world,
+                ));
+            }
+            world.restore(snapshot);
+            if outcomes.iter().all(Option::is_some) || kill == 0.0 || kill == 1.0 {
+                break;
+            }
+        }
+        let rest = match outcomes {
+            [Some(standing), Some(down)] => Plan {
+                score: (1.0 - kill) * standing.score + kill * down.score,
+                ..if kill > 0.5 { down } else { standing }
+            },
+            [Some(plan), None] | [None, Some(plan)] => plan,
+            [None, None] => unreachable!("an activity is played at least once"),
+        };
+        let score = utility.score(personality) + rest.score;
+        if score > best.score + 1e-9 {
+            let mut activities = vec![String::from(activity.get_name())];
+            activities.extend(rest.activities);
+            best = Plan {
+                first: Some(index),
+                activities,
+                score,
+            };
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        activity::attack::{expect_strike, StrikeModifiers},
+        position::Position,
+    };
+
+    #[test]
+    fn strike_odds() {
+        let (world, orc, kobold) =
+            World::orc_duel("kobold-warrior", "kobolds", Position::new(0, 1));
+        let orc = world.get_character(&orc);
+        let hook = &orc.creature.as_ref().unwrap().strikes[0];
+        let odds = expect_strike(
+            orc,
+            world.get_character(&kobold),
+            &hook.weapon,
+            Some(hook),
+            StrikeModifiers::default(),
+            &world,
+        );
+        // +7 against AC 16: a hit from 9, a critical hit from 19.
+        assert!((odds.hit - 0.6).abs() < 1e-9);
+        // 1d10+3 kills the 8 HP kobold from 5 on the die, a critical hit always does.
+        assert!((odds.kill - (0.5 * 0.6 + 0.1)).abs() < 1e-9);
+        // no more than 8 damage.
+        assert!((odds.damage - (0.5 * (0.4 * 5.5 + 0.6 * 8.0) + 0.1 * 8.0)).abs() < 1e-9);
+        // a natural 1 turns the miss into a critical miss.
+        assert!((odds.critical_failure - 0.05).abs() < 1e-9);
+    }
+
+    #[test]
+    fn plan() {
+        let (mut world, orc, kobold) =
+            World::orc_duel("kobold-warrior", "kobolds", Position::new(0, 6));
+        let orc = world.get_character(&orc).clone();
+        let plan = plan_turn(&orc, 3, &mut world);
+        assert_eq!(
+            plan.activities,
+            vec!["Stride", "Strike (Ogre Hook)", "Strike (Ogre Hook)"]
+        );
+        assert_eq!(
+            orc.get_activities(&world)[plan.first.unwrap()].get_name(),
+            "Stride"
+        );
+        // the world is left as it was.
+        assert_eq!(world.get_character(&orc.id).position, orc.position);
+        // the kobold slings from afar.
+        let plan = plan_turn(&world.get_character(&kobold).clone(), 3, &mut world);
+        assert_eq!(plan.activities[0], "Strike (Sling)");
+        // nothing to do once the enemies are down.
+        world.get_mut_character(&kobold).hp = 0;
+        assert_eq!(plan_turn(&orc, 3, &mut world), Plan::default());
+    }
+
+    #[test]
+    fn planning_rolls_no_dice() {
+        let (mut world, orc, _) = World::orc_duel("kobold-warrior", "kobolds", Position::new(0, 1));
+        let orc = world.get_character(&orc).clone();
+        dice::seed(9);
+        let state = dice::get_state();
+        let plan = plan_turn(&orc, 3, &mut world);
+        assert_eq!(dice::get_state(), state);
+        assert_eq!(plan_turn(&orc, 3, &mut world), plan);
+    }
+
+    #[test]
+    fn personality() {
+        let (mut world, orc, _) = World::orc_duel("kobold-warrior", "kobolds", Position::new(0, 1));
+        let scout = world
+            .spawn_creature("kobold-scout", "kobolds", Position::new(1, 1))
+            .unwrap();
+        world.get_mut_character(&scout).hp = 2;
+        let strike = |personality: Personality| {
+            let mut orc = world.get_character(&orc).clone();
+            orc.personality = personality;
+            let activities = orc.get_activities(&world);
+            let hook = activities
+                .iter()
+                .find(|a| a.get_name() == "Strike (Ogre Hook)")
+                .unwrap();
+            hook.ai_utility(&orc, &world).unwrap()
+        };
+        // a butcher hits the kobold with the most HP to lose, the brute finishes the scout.
+        let butcher = strike(Personality {
+            damage: 1.0,
+            kill: 0.0,
+            threat: 0.0,
+            buff: 0.0,
+        });
+        let brute = strike(Personality::default());
+        assert!(butcher.damage > brute.damage);
+        assert!(brute.kill > butcher.kill);
+    }
+}
@@ -0,0 +1,372 @@
+use std::{iter, time::Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    activity::{can_choose, perform, take_activity},
+    character::Character,
+    fact::Facts,
+    world::World,
+};
+
+use super::{get_position_value, Personality, Plan, MAX_STEPS};
+
+// how much the search tries the activities it knows the least, in rewards seen.
+const EXPLORATION: f64 = 1.4;
+
+/**
+How long the search goes on, in turns played or in milliseconds.
+A time budget depends on the machine: a game is no longer replayed as it was played.
+*/
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Budget {
+    Iterations(usize),
+    Millis(u64),
+}
+
+impl Budget {
+    fn is_spent(&self, iterations: usize, start: &Instant) -> bool {
+        match self {
+            Budget::Iterations(limit) => iterations >= *limit,
+            Budget::Millis(limit) => start.elapsed().as_millis() >= *limit as u128,
+        }
+    }
+}
+
+// an activity of the planned turn, none to pass.
+struct Node {
+    activity: Option<String>,
+    visits: u32,
+    reward: f64,
+    children: Vec<usize>,
+}
+
+impl Node {
+    fn new(activity: Option<String>) -> Self {
+        Node {
+            activity,
+            visits: 0,
+            reward: 0.0,
+            children: vec![],
+        }
+    }
+
+    fn mean(&self) -> f64 {
+        self.reward / self.visits.max(1) as f64
+    }
+}
+
+/**
+What the world is worth to the character: the HP and the standing creatures of its party,
+less the ones of the others, and where it stands.
+The HP of its party are weighed as threat, the damage the enemies did not deal.
+*/
+fn evaluate(id: &str, personality: &Personality, world: &World) -> f64 {
+    let character = world.get_character(id);
+    let mut value = if character.hp > 0 {
+        get_position_value(character, personality, world)
+    } else {
+        0.0
+    };
+    for creature in world.get_characters() {
+        let hp = creature.hp.max(0) as f64;
+        let standing = if creature.hp > 0 { 1.0 } else { 0.0 };
+        if creature.party == character.party {
+            value += hp * personality.threat + standing * personality.kill;
+        } else {
+            value -= hp * personality.damage + standing * personality.kill;
+        }
+    }
+    value
+}
+
+/**
+The activities the character can choose, Pass first.
+The ones the AI has no utility for are left out, like they are by the utility planner.
+*/
+fn get_choices(character: &Character, action_left: i64, world: &World) -> Vec<Option<usize>> {
+    let activities = character.get_activities(world);
+    let playable = activities.iter().enumerate().filter(|(_, activity)| {
+        can_choose(activity.as_ref(), character, action_left, world)
+            && activity.ai_utility(character, world).is_some()
+    });
+    iter::once(None)
+        .chain(playable.map(|(index, _)| Some(index)))
+        .collect()
+}
+
+/**
+How the turns are played past the activities of the tree: the activity worth the most right away.
+When none is worth anything, a melee creature out of reach moves towards its enemies.
+*/
+fn choose_greedy(character: &Character, action_left: i64, world: &mut World) -> Option<usize> {
+    let activities = character.get_activities(world);
+    let mut best = None;
+    let mut best_score = 0.0;
+    for (index, activity) in activities.iter().enumerate() {
+        if !can_choose(activity.as_ref(), character, action_left, world) {
+            continue;
+        }
+        if let Some(utility) = activity.ai_utility(character, world) {
+            let score = utility.score(&character.personality);
+            if score > best_score {
+                best = Some(index);
+                best_score = score;
+            }
+        }
+    }
+    let personality = &character.personality;
+    let mut best_position = get_position_value(character, personality, world);
+    if best.is_some() || best_position >= 0.0 {
+        return best;
+    }
+    for (index, activity) in activities.iter().enumerate() {
+        let worthless = activity
+            .ai_utility(character, world)
+            .is_some_and(|u| u.score(personality) == 0.0);
+        if !worthless || !can_choose(activity.as_ref(), character, action_left, world) {
+            continue;
+        }
+        let snapshot = world.snapshot();
+        let mut played = character.get_activities(world).swap_remove(index);
+        perform(&mut played, character, world, &mut Facts::new());
+        let moved = world.get_character(&character.id);
+        let position = if moved.hp > 0 {
+            get_position_value(moved, personality, world)
+        } else {
+            -personality.kill
+        };
+        world.restore(snapshot);
+        if position > best_position {
+            best = Some(index);
+            best_position = position;
+        }
+    }
+    best
+}
+
+// plays the rest of a turn of a creature with the greedy policy.
+fn play_greedy(id: &str, mut action_left: i64, mut steps: usize, world: &mut World) {
+    while action_left > 0 && steps > 0 {
+        let character = world.get_character(id).clone();
+        if character.hp <= 0 {
+            return;
+        }
+        let choice = match choose_greedy(&character, action_left, world) {
+            Some(index) => index,
+            None => return,
+        };
+        let mut activity = take_activity(character.get_activities(world), Some(choice));
+        action_left -= activity.get_cost();
+        perform(&mut activity, &character, world, &mut Facts::new());
+        steps -= 1;
+    }
+}
+
+/**
+The other creatures answer, each with a whole turn, before the character plays again.
+The planner does not know the initiative, they play in the order of their ids.
+*/
+fn respond(id: &str, world: &mut World) {
+    let others: Vec<String> = world
+        .characters
+        .keys()
+        .filter(|other| *other != id)
+        .cloned()
+        .collect();
+    for other in others {
+        if world.get_character(&other).hp > 0 {
+            world.get_mut_character(&other).start_turn();
+            play_greedy(&other, 3, MAX_STEPS, world);
+        }
+    }
+}
+
+// the child of a node for a choice, created when it was never tried.
+fn get_child(nodes: &mut Vec<Node>, parent: usize, activity: Option<String>) -> usize {
+    let found = nodes[parent]
+        .children
+        .iter()
+        .copied()
+        .find(|&child| nodes[child].activity == activity);
+    match found {
+        Some(child) => child,
+        None => {
+            nodes.push(Node::new(activity));
+            let child = nodes.len() - 1;
+            nodes[parent].children.push(child);
+            child
+        }
+    }
+}
+
+// UCB1, the rewards are scaled by how far apart they were seen.
+fn get_bound(node: &Node, parent_visits: u32, spread: f64) -> f64 {
+    node.mean()
+        + EXPLORATION * spread * ((parent_visits as f64).ln() / node.visits.max(1) as f64).sqrt()
+}
+
+/**
+Plays the activities of the tree from the root, until an activity never tried is played or the turn ends.
+The dice differ at every iteration: a node is worth what its activity brought on average.
+Returns the nodes played and what is left of the turn.
+*/
+fn descend(
+    id: &str,
+    mut action_left: i64,
+    nodes: &mut Vec<Node>,
+    spread: f64,
+    world: &mut World,
+) -> (Vec<usize>, i64, usize) {
+    let mut path = vec![0];
+    let mut steps = MAX_STEPS;
+    while action_left > 0 && steps > 0 {
+        let character = world.get_character(id).clone();
+        if character.hp <= 0 {
+            break;
+        }
+        let mut activities = character.get_activities(world);
+        let parent = *path.last().unwrap();
+        let choices = get_choices(&character, action_left, world);
+        let name = |choice: Option<usize>| choice.map(|i| String::from(activities[i].get_name()));
+        let untried = choices.iter().copied().find(|&choice| {
+            let activity = name(choice);
+            !nodes[parent]
+                .children
+                .iter()
+                .any(|&child| nodes[child].activity == activity)
+        });
+        let choice = untried.unwrap_or_else(|| {
+            let parent_visits = nodes[parent].visits;
+            let mut best = (None, f64::MIN);
+            for &choice in &choices {
+                let child = get_child(nodes, parent, name(choice));
+                let bound = get_bound(&nodes[child], parent_visits, spread);
+                if bound > best.1 {
+                    best = (choice, bound);
+                }
+            }
+            best.0
+        });
+        let child = get_child(nodes, parent, name(choice));
+        path.push(child);
+        match choice {
+            None => return (path, 0, steps),
+            Some(index) => {
+                let mut activity = activities.swap_remove(index);
+                action_left -= activity.get_cost();
+                perform(&mut activity, &character, world, &mut Facts::new());
+                steps -= 1;
+            }
+        }
+        if untried.is_some() {
+            break;
+        }
+    }
+    (path, action_left, steps)
+}
+
+/**
+Monte Carlo Tree Search over the activities of the turn of a character.
+Every iteration plays a turn from the tree, ends it with the greedy policy, lets the other creatures
+answer, and scores the world it ends in. The turn kept is the one of the activities played the most.
+*/
+pub(super) fn search(
+    id: &str,
+    action_left: i64,
+    budget: Budget,
+    seed: u64,
+    world: &mut World,
+) -> Plan {
+    let character = world.get_character(id).clone();
+    if action_left <= 0 || character.hp <= 0 {
+        return Plan::default();
+    }
+    let personality = character.personality;
+    let start = evaluate(id, &personality, world);
+    let mut nodes = vec![Node::new(None)];
+    let (mut lowest, mut highest) = (0.0_f64, 0.0_f64);
+    let timer = Instant::now();
+    let mut iterations = 0;
+    while !budget.is_spent(iterations, &timer) {
+        dice::seed(seed.wrapping_add(iterations as u64));
+        let snapshot = world.snapshot();
+        let spread = if highest > lowest {
+            highest - lowest
+        } else {
+            1.0
+        };
+        let (path, action_left, steps) = descend(id, action_left, &mut nodes, spread, world);
+        play_greedy(id, action_left, steps, world);
+        respond(id, world);
+        let reward = evaluate(id, &personality, world) - start;
+        lowest = lowest.min(reward);
+        highest = highest.max(reward);
+        for node in path {
+            nodes[node].visits += 1;
+            nodes[node].reward += reward;
+        }
+        world.restore(snapshot);
+        iterations += 1;
+    }
+
+    let mut plan = Plan::default();
+    let mut node = 0;
+    loop {
+        // the most played child, the better one of the ties, Pass when nothing is better.
+        let mut best: Option<usize> = None;
+        for &child in &nodes[node].children {
+            let is_better = best.is_none_or(|b| {
+                (nodes[child].visits, nodes[child].mean()) > (nodes[b].visits, nodes[b].mean())
+            });
+            if is_better {
+                best = Some(child);
+            }
+        }
+        let child = match best {
+            Some(child) => child,
+            None => break,
+        };
+        let activity = match &nodes[child].activity {
+            Some(activity) => activity.clone(),
+            None => break,
+        };
+        if node == 0 {
+            plan.first = character
+                .get_activities(world)
+                .iter()
+                .position(|a| a.get_name() == activity);
+            plan.score = nodes[child].mean();
+        }
+        plan.activities.push(activity);
+        node = child;
+    }
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ai::plan_turn, ai::Planner, position::Position};
+
+    #[test]
+    fn search_turn() {
+        let (mut world, orc, kobold) =
+            World::orc_duel("kobold-warrior", "kobolds", Position::new(0, 6));
+        world.get_mut_character(&orc).planner = Planner::Search(Budget::Iterations(60));
+        let orc = world.get_character(&orc).clone();
+        let plan = plan_turn(&orc, 3, &mut world);
+        assert_eq!(plan.activities[0], "Stride");
+        assert!(plan.activities[1..].contains(&String::from("Strike (Ogre Hook)")));
+        assert_eq!(
+            orc.get_activities(&world)[plan.first.unwrap()].get_name(),
+            "Stride"
+        );
+        // the world is left as it was, and the same dice plan the same turn.
+        assert_eq!(world.get_character(&orc.id).position, orc.position);
+        assert_eq!(plan_turn(&orc, 3, &mut world), plan);
+
+        world.get_mut_character(&kobold).hp = 0;
+        assert_eq!(plan_turn(&orc, 3, &mut world).first, None);
+    }
+}
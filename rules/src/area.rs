@@ -0,0 +1,259 @@
+use std::cmp::{max, min};
+
+use crate::{position::Position, timeline::CharacterId, world::World};
+
+/**
+p456 areas on the grid, measured in feet like movement: every second diagonal counts as 10 feet.
+A creature is in the area when its square is.
+*/
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Area {
+    // centered on a grid intersection: the top left corner of the `corner` square.
+    Burst {
+        corner: Position,
+        radius: i64,
+    },
+    // a quarter circle from the edge of the origin square, towards one of the 8 directions.
+    Cone {
+        origin: Position,
+        towards: Position,
+        length: i64,
+    },
+    // from the edge of the origin square, through every square it passes.
+    Line {
+        origin: Position,
+        towards: Position,
+        length: i64,
+    },
+    // around the origin square, which is included.
+    Emanation {
+        origin: Position,
+        radius: i64,
+    },
+}
+
+// the closest of the 8 directions, a diagonal unless the target is mostly straight ahead.
+fn get_direction(origin: &Position, towards: &Position) -> (i64, i64) {
+    let (dx, dy) = (towards.x - origin.x, towards.y - origin.y);
+    if dx.abs() >= 2 * dy.abs() {
+        (dx.signum(), 0)
+    } else if dy.abs() >= 2 * dx.abs() {
+        (0, dy.signum())
+    } else {
+        (dx.signum(), dy.signum())
+    }
+}
+
+impl Area {
+    pub fn contains(&self, square: &Position) -> bool {
+        match self {
+            Area::Burst { corner, radius } => {
+                // squares counted from the intersection, the first one is 5 feet away.
+                let count = |s: i64, c: i64| if s >= c { s - c + 1 } else { c - s };
+                let (dx, dy) = (count(square.x, corner.x), count(square.y, corner.y));
+                (max(dx, dy) + min(dx, dy) / 2) * 5 <= *radius
+            }
+            Area::Cone {
+                origin,
+                towards,
+                length,
+            } => {
+                let (dx, dy) = (square.x - origin.x, square.y - origin.y);
+                let inside = match get_direction(origin, towards) {
+                    (0, 0) => false,
+                    (sx, 0) => dx * sx >= 1 && dy.abs() < dx * sx,
+                    (0, sy) => dy * sy >= 1 && dx.abs() < dy * sy,
+                    (sx, sy) => dx * sx >= 1 && dy * sy >= 1,
+                };
+                inside && origin.distance(square) <= *length
+            }
+            Area::Line { .. } => self.get_line().contains(square),
+            Area::Emanation { origin, radius } => origin.distance(square) <= *radius,
+        }
+    }
+
+    // the square the area spreads from, which needs line of effect to the creatures it affects.
+    pub fn get_origin(&self) -> Position {
+        match self {
+            Area::Burst { corner, .. } => *corner,
+            Area::Cone { origin, .. }
+            | Area::Line { origin, .. }
+            | Area::Emanation { origin, .. } => *origin,
+        }
+    }
+
+    // the squares a line passes through, from the one next to its origin.
+    fn get_line(&self) -> Vec<Position> {
+        let (origin, towards, length) = match self {
+            Area::Line {
+                origin,
+                towards,
+                length,
+            } => (origin, towards, *length),
+            _ => return vec![],
+        };
+        let (dx, dy) = (towards.x - origin.x, towards.y - origin.y);
+        let steps = max(dx.abs(), dy.abs());
+        if steps == 0 {
+            return vec![];
+        }
+        let mut squares = vec![];
+        for i in 1.. {
+            let square = Position::new(
+                origin.x + (i as f64 * dx as f64 / steps as f64).round() as i64,
+                origin.y + (i as f64 * dy as f64 / steps as f64).round() as i64,
+            );
+            if origin.distance(&square) > length {
+                break;
+            }
+            squares.push(square);
+        }
+        squares
+    }
+
+    // the squares of the area, for instance to draw it.
+    pub fn get_squares(&self) -> Vec<Position> {
+        let (center, size) = match self {
+            Area::Burst { corner, radius } => (*corner, radius / 5 + 1),
+            Area::Cone { origin, length, .. } | Area::Line { origin, length, .. } => {
+                (*origin, length / 5 + 1)
+            }
+            Area::Emanation { origin, radius } => (*origin, radius / 5 + 1),
+        };
+        let mut squares = vec![];
+        for x in center.x - size..=center.x + size {
+            for y in center.y - size..=center.y + size {
+                let square = Position::new(x, y);
+                if self.contains(&square) {
+                    squares.push(square);
+                }
+            }
+        }
+        squares
+    }
+}
+
+// which creatures an area affects, relative to the party of its creator.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Affects {
+    Everyone,
+    Allies,
+    Enemies,
+}
+
+// the creatures in the area and in line of effect of its origin, unconscious ones included, sorted so that the outcome does not depend on the HashMap.
+pub fn find_creatures(
+    area: &Area,
+    party: &str,
+    affects: Affects,
+    world: &World,
+) -> Vec<CharacterId> {
+    let mut ids: Vec<CharacterId> = world
+        .get_characters()
+        .iter()
+        .filter(|c| area.contains(&c.position))
+        .filter(|c| {
+            world
+                .map
+                .has_line_of_effect(&area.get_origin(), &c.position)
+        })
+        .filter(|c| match affects {
+            Affects::Everyone => true,
+            Affects::Allies => c.party == party,
+            Affects::Enemies => c.party != party,
+        })
+        .map(|c| c.id.clone())
+        .collect();
+    ids.sort();
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn templates() {
+        let origin = Position::new(0, 0);
+        // p457 a 5-foot burst covers the 4 squares around its intersection
+        let burst = Area::Burst {
+            corner: origin,
+            radius: 5,
+        };
+        assert_eq!(burst.get_squares().len(), 4);
+        assert!(burst.contains(&Position::new(-1, -1)));
+        assert!(!burst.contains(&Position::new(1, 0)));
+        // a 10-foot burst is a 4x4 square, minus its corners
+        let burst = Area::Burst {
+            corner: origin,
+            radius: 10,
+        };
+        assert_eq!(burst.get_squares().len(), 12);
+
+        let emanation = Area::Emanation { origin, radius: 5 };
+        assert_eq!(emanation.get_squares().len(), 9);
+
+        // a quarter circle: 1, 3 and 3 squares wide
+        let cone = Area::Cone {
+            origin,
+            towards: Position::new(5, 1),
+            length: 15,
+        };
+        assert_eq!(cone.get_squares().len(), 7);
+        assert!(!cone.contains(&origin));
+        assert!(cone.contains(&Position::new(3, 1)));
+        assert!(!cone.contains(&Position::new(3, 2)));
+        let cone = Area::Cone {
+            origin,
+            towards: Position::new(-2, 2),
+            length: 15,
+        };
+        assert_eq!(
+            cone.get_squares(),
+            vec![
+                Position::new(-3, 1),
+                Position::new(-2, 1),
+                Position::new(-2, 2),
+                Position::new(-1, 1),
+                Position::new(-1, 2),
+                Position::new(-1, 3)
+            ]
+        );
+
+        let line = Area::Line {
+            origin,
+            towards: Position::new(4, 2),
+            length: 30,
+        };
+        assert_eq!(line.get_squares().len(), 5);
+        assert!(line.contains(&Position::new(5, 3)));
+        assert!(!line.contains(&Position::new(5, 2)));
+    }
+
+    #[test]
+    fn creatures() {
+        let mut world = World::new();
+        let cleric = world.add_character_at("Kyra", "heroes", 10, Position::new(0, 0));
+        let goblin = world.add_character_at("Goblin", "goblins", 10, Position::new(1, 1));
+        world.add_character_at("Merisiel", "heroes", 10, Position::new(3, 0));
+        let fighter = world.add_character_at("Valeros", "heroes", 0, Position::new(0, 1));
+        let area = Area::Emanation {
+            origin: Position::new(0, 0),
+            radius: 5,
+        };
+        assert_eq!(
+            find_creatures(&area, "heroes", Affects::Allies, &world),
+            vec![cleric.clone(), fighter.clone()]
+        );
+        assert_eq!(
+            find_creatures(&area, "heroes", Affects::Enemies, &world),
+            vec![goblin.clone()]
+        );
+        let mut expected = vec![cleric, goblin, fighter];
+        expected.sort();
+        assert_eq!(
+            find_creatures(&area, "heroes", Affects::Everyone, &world),
+            expected
+        );
+    }
+}
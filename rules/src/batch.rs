@@ -0,0 +1,314 @@
+use std::{collections::BTreeMap, fmt, thread};
+
+use serde::Serialize;
+
+use crate::{
+    fact::Fact,
+    timeline::{CharacterId, PartyId},
+    GameState,
+};
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CharacterOutcome {
+    pub id: CharacterId,
+    pub name: String,
+    pub party: PartyId,
+    pub damage_dealt: i64,
+    pub hp: i64,
+    // there are no dying rules yet, a character at 0 HP at the end of the encounter is dead.
+    pub dead: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct EncounterOutcome {
+    pub seed: u64,
+    // the party left standing, none when the rounds limit is reached first.
+    pub winner: Option<PartyId>,
+    pub rounds: i64,
+    pub characters: Vec<CharacterOutcome>,
+}
+
+/**
+Plays a copy of the encounter from the start, initiative included, with dice seeded for this run.
+The dice are seeded per thread, so runs on other threads do not change the outcome.
+*/
+pub fn simulate(game_state: &GameState, seed: u64, rounds_limit: i64) -> EncounterOutcome {
+    dice::seed(seed);
+    let mut game_state = GameState::from_world(game_state.get_world().clone());
+    let facts = game_state.run(rounds_limit);
+
+    let mut damage_dealt: BTreeMap<&str, i64> = BTreeMap::new();
+    for fact in &facts {
+        if let Fact::Damage {
+            source_id, amount, ..
+        } = fact
+        {
+            *damage_dealt.entry(source_id).or_insert(0) += amount;
+        }
+    }
+    let world = game_state.get_world();
+    let mut characters: Vec<CharacterOutcome> = world
+        .get_characters()
+        .iter()
+        .map(|c| CharacterOutcome {
+            id: c.id.clone(),
+            name: c.name.clone(),
+            party: c.party.clone(),
+            damage_dealt: damage_dealt.get(c.id.as_str()).copied().unwrap_or(0),
+            hp: c.hp.max(0),
+            dead: c.hp <= 0,
+        })
+        .collect();
+    characters.sort_by(|a, b| (&a.party, &a.name, &a.id).cmp(&(&b.party, &b.name, &b.id)));
+
+    let mut standing: Vec<&PartyId> = characters
+        .iter()
+        .filter(|c| !c.dead)
+        .map(|c| &c.party)
+        .collect();
+    standing.dedup();
+    let is_over = facts.iter().any(|f| *f == Fact::EncounterOver);
+    EncounterOutcome {
+        seed,
+        winner: match standing.as_slice() {
+            [party] if is_over => Some((*party).clone()),
+            _ => None,
+        },
+        rounds: game_state.get_round().min(rounds_limit),
+        characters,
+    }
+}
+
+/**
+Runs the same encounter many times to balance it, the runs are spread over threads.
+Run `i` uses the seed `seed + i`, so a batch gives the same report whatever the number of threads.
+*/
+pub struct Batch {
+    game_state: GameState,
+    runs: u64,
+    seed: u64,
+    threads: u64,
+    rounds_limit: i64,
+}
+
+impl Batch {
+    pub fn new(game_state: GameState) -> Self {
+        Batch {
+            game_state,
+            runs: 1000,
+            seed: 0,
+            threads: 4,
+            rounds_limit: 100,
+        }
+    }
+
+    pub fn runs(mut self, runs: u64) -> Self {
+        self.runs = runs;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn threads(mut self, threads: u64) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    pub fn rounds_limit(mut self, rounds_limit: i64) -> Self {
+        self.rounds_limit = rounds_limit;
+        self
+    }
+
+    pub fn run(&self) -> BatchReport {
+        let mut outcomes: Vec<EncounterOutcome> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..self.threads)
+                .map(|worker| {
+                    scope.spawn(move || {
+                        (worker..self.runs)
+                            .step_by(self.threads as usize)
+                            .map(|run| {
+                                let seed = self.seed.wrapping_add(run);
+                                simulate(&self.game_state, seed, self.rounds_limit)
+                            })
+                            .collect::<Vec<EncounterOutcome>>()
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|w| w.join().expect("a simulation panicked"))
+                .collect()
+        });
+        outcomes.sort_by_key(|o| o.seed.wrapping_sub(self.seed));
+        BatchReport { outcomes }
+    }
+}
+
+// Averages of a character over every run of the batch.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CharacterStats {
+    pub name: String,
+    pub party: PartyId,
+    pub damage_dealt: f64,
+    pub hp: f64,
+    pub deaths: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct BatchReport {
+    pub outcomes: Vec<EncounterOutcome>,
+}
+
+impl BatchReport {
+    fn average(&self, value: impl Fn(&EncounterOutcome) -> f64) -> f64 {
+        if self.outcomes.is_empty() {
+            return 0.0;
+        }
+        self.outcomes.iter().map(value).sum::<f64>() / self.outcomes.len() as f64
+    }
+
+    // the share of the runs won by every party, draws are left out.
+    pub fn get_win_rates(&self) -> BTreeMap<PartyId, f64> {
+        let mut rates = BTreeMap::new();
+        for outcome in &self.outcomes {
+            for c in &outcome.characters {
+                rates.entry(c.party.clone()).or_insert(0.0);
+            }
+        }
+        for (party, rate) in rates.iter_mut() {
+            *rate = self.average(|o| (o.winner.as_ref() == Some(party)) as i64 as f64);
+        }
+        rates
+    }
+
+    pub fn get_draw_rate(&self) -> f64 {
+        self.average(|o| o.winner.is_none() as i64 as f64)
+    }
+
+    pub fn get_average_rounds(&self) -> f64 {
+        self.average(|o| o.rounds as f64)
+    }
+
+    // every run has the same characters, in the same order.
+    pub fn get_character_stats(&self) -> Vec<CharacterStats> {
+        let first = match self.outcomes.first() {
+            Some(outcome) => outcome,
+            None => return vec![],
+        };
+        first
+            .characters
+            .iter()
+            .enumerate()
+            .map(|(i, c)| CharacterStats {
+                name: c.name.clone(),
+                party: c.party.clone(),
+                damage_dealt: self.average(|o| o.characters[i].damage_dealt as f64),
+                hp: self.average(|o| o.characters[i].hp as f64),
+                deaths: self
+                    .outcomes
+                    .iter()
+                    .filter(|o| o.characters[i].dead)
+                    .count() as u64,
+            })
+            .collect()
+    }
+
+    // one line per character and per run.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("seed,winner,rounds,character,party,damage_dealt,hp,dead\n");
+        for outcome in &self.outcomes {
+            for c in &outcome.characters {
+                csv.push_str(&format!(
+                    "{},{},{},{},{},{},{},{}\n",
+                    outcome.seed,
+                    escape(outcome.winner.as_deref().unwrap_or("")),
+                    outcome.rounds,
+                    escape(&c.name),
+                    escape(&c.party),
+                    c.damage_dealt,
+                    c.hp,
+                    c.dead
+                ));
+            }
+        }
+        csv
+    }
+}
+
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        String::from(field)
+    }
+}
+
+// The summary table.
+impl fmt::Display for BatchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} runs, {:.1} rounds on average",
+            self.outcomes.len(),
+            self.get_average_rounds()
+        )?;
+        writeln!(f, "{:<24} {:>8}", "party", "win rate")?;
+        for (party, rate) in self.get_win_rates() {
+            writeln!(f, "{:<24} {:>7.1}%", party, rate * 100.0)?;
+        }
+        writeln!(f, "{:<24} {:>7.1}%", "draws", self.get_draw_rate() * 100.0)?;
+        writeln!(
+            f,
+            "{:<24} {:<12} {:>8} {:>8} {:>8}",
+            "character", "party", "damage", "hp", "deaths"
+        )?;
+        for c in self.get_character_stats() {
+            writeln!(
+                f,
+                "{:<24} {:<12} {:>8.1} {:>8.1} {:>8}",
+                c.name, c.party, c.damage_dealt, c.hp, c.deaths
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{position::Position, world::World};
+
+    fn duel() -> GameState {
+        let (world, _, _) = World::orc_duel("wolf", "wolves", Position::new(3, 0));
+        GameState::from_world(world)
+    }
+
+    fn is_send_and_clone<T: Send + Clone>() {}
+
+    #[test]
+    fn batch() {
+        is_send_and_clone::<GameState>();
+        let duel = duel();
+        let report = Batch::new(duel.clone()).runs(6).seed(7).threads(3).run();
+        assert_eq!(report.outcomes.len(), 6);
+        assert_eq!(report.outcomes[5].seed, 12);
+        // a duel always ends with one of them down.
+        let rates = report.get_win_rates();
+        assert_eq!(rates.len(), 2);
+        assert!((rates.values().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert_eq!(report.get_draw_rate(), 0.0);
+        let stats = report.get_character_stats();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats.iter().map(|c| c.deaths).sum::<u64>(), 6);
+
+        // the seeds, not the threads, decide the outcomes.
+        assert_eq!(report, Batch::new(duel).runs(6).seed(7).threads(1).run());
+        let csv = report.to_csv();
+        assert_eq!(csv.lines().count(), 1 + 6 * 2);
+        assert!(csv.starts_with("seed,winner,rounds,"));
+        assert!(report.to_string().starts_with("6 runs"));
+    }
+}
@@ -0,0 +1,343 @@
+use std::{collections::BTreeMap, fmt, fs, path::Path};
+
+use serde::Deserialize;
+
+use crate::{
+    ai::{Personality, Planner},
+    character::{
+        abilities::AbilityScore,
+        creature::{CreatureAbility, CreatureStats, CreatureStrike},
+        Character,
+    },
+    item::{
+        catalog::{parse_dice, parse_trait},
+        rune::Runes,
+        weapon::{CombatProperties, DamageType, WeaponCategory, WeaponGroup, WeaponItem},
+        Bulk, ItemInfo,
+    },
+    rules::Rule,
+};
+
+// the creatures shipped with the crate.
+const CORE_BESTIARY: &str = include_str!("../data/bestiary.ron");
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BestiaryError {
+    Io(String),
+    Parse(String),
+    InvalidEntry { key: String, message: String },
+    UnknownCreature(String),
+}
+
+impl fmt::Display for BestiaryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BestiaryError::Io(e) => write!(f, "cannot read the bestiary: {}", e),
+            BestiaryError::Parse(e) => write!(f, "malformed bestiary: {}", e),
+            BestiaryError::InvalidEntry { key, message } => {
+                write!(f, "invalid stat block \"{}\": {}", key, message)
+            }
+            BestiaryError::UnknownCreature(key) => {
+                write!(f, "no creature \"{}\" in the bestiary", key)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AbilityModifiers {
+    pub str: i64,
+    pub dex: i64,
+    pub con: i64,
+    pub int: i64,
+    pub wis: i64,
+    pub cha: i64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Saves {
+    pub fortitude: i64,
+    pub reflex: i64,
+    pub will: i64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StrikeBlock {
+    pub name: String,
+    pub attack: i64,
+    // dice notation with the damage bonus, like "1d8+4"
+    pub damage: String,
+    pub damage_type: DamageType,
+    // natural attacks have no group.
+    #[serde(default = "natural_group")]
+    pub group: WeaponGroup,
+    #[serde(default)]
+    pub range: i64,
+    #[serde(default)]
+    pub traits: Vec<String>,
+}
+
+fn natural_group() -> WeaponGroup {
+    WeaponGroup::Brawling
+}
+
+/**
+Bestiary p6 a creature stat block.
+The numbers are final: they already include abilities, proficiency and items.
+*/
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StatBlock {
+    pub name: String,
+    pub level: i64,
+    #[serde(default)]
+    pub traits: Vec<String>,
+    pub perception: i64,
+    #[serde(default)]
+    pub skills: BTreeMap<String, i64>,
+    pub abilities: AbilityModifiers,
+    pub ac: i64,
+    pub saves: Saves,
+    pub hp: i64,
+    pub speed: i64,
+    #[serde(default)]
+    pub resistances: Vec<(DamageType, i64)>,
+    // "resistances all", like the ones of incorporeal creatures
+    #[serde(default)]
+    pub resist_all: i64,
+    #[serde(default)]
+    pub weaknesses: Vec<(DamageType, i64)>,
+    pub strikes: Vec<StrikeBlock>,
+    #[serde(default)]
+    pub special: Vec<CreatureAbility>,
+    // what its AI cares about
+    #[serde(default)]
+    pub personality: Personality,
+    // the utility planner, unless the creature is worth the time of a search.
+    #[serde(default)]
+    pub planner: Planner,
+}
+
+impl StatBlock {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(-1..=25).contains(&self.level) {
+            return Err(format!(
+                "level must be between -1 and 25, got {}",
+                self.level
+            ));
+        }
+        if self.hp <= 0 {
+            return Err(format!("hp must be positive, got {}", self.hp));
+        }
+        if self.speed < 0 {
+            return Err(format!("speed must be positive, got {}", self.speed));
+        }
+        if self.strikes.is_empty() {
+            return Err(String::from("a creature needs at least one Strike"));
+        }
+        for strike in self.strikes.iter() {
+            build_strike(strike).map_err(|e| format!("Strike {}: {}", strike.name, e))?;
+        }
+        Ok(())
+    }
+
+    /**
+    Builds the character, its Strikes become its attack activities.
+    The stat block should have been validated.
+    */
+    pub fn to_character(&self, party: &str) -> Character {
+        let mut character = Character::new(self.name.clone(), String::from(party), self.hp);
+        character.level = self.level;
+        character.speed = self.speed;
+        character.personality = self.personality;
+        character.planner = self.planner;
+        character.traits = self
+            .traits
+            .iter()
+            .filter_map(|t| creature_trait(t))
+            .collect();
+        // an ability score that gives the stat block modifier.
+        let score = |modifier: i64| 10 + 2 * modifier;
+        let a = &self.abilities;
+        character.ability_score = AbilityScore {
+            strength: score(a.str),
+            dexterity: score(a.dex),
+            constitution: score(a.con),
+            intelligence: score(a.int),
+            wisdom: score(a.wis),
+            charisma: score(a.cha),
+        };
+        character.creature = Some(CreatureStats {
+            ac: self.ac,
+            fortitude: self.saves.fortitude,
+            reflex: self.saves.reflex,
+            will: self.saves.will,
+            perception: self.perception,
+            skills: self.skills.clone(),
+            strikes: self
+                .strikes
+                .iter()
+                .map(|s| build_strike(s).expect("stat block should be validated"))
+                .collect(),
+            resistances: self.resistances.clone(),
+            resist_all: self.resist_all,
+            weaknesses: self.weaknesses.clone(),
+            abilities: self.special.clone(),
+        });
+        character
+    }
+}
+
+fn build_strike(strike: &StrikeBlock) -> Result<CreatureStrike, String> {
+    let (nb_dice, dice_faces, damage_bonus) = parse_dice(&strike.damage)?;
+    let mut rules = vec![];
+    for t in strike.traits.iter() {
+        match parse_trait(t)? {
+            // the damage bonus of a stat block already counts the ability modifier.
+            Some(Rule::Finesse) | Some(Rule::Propulsive) | None => {}
+            Some(rule) => rules.push(rule),
+        }
+    }
+    let mut info = ItemInfo::new(&strike.name, Bulk::NEGLIGIBLE, rules);
+    info.traits = strike.traits.clone();
+    Ok(CreatureStrike {
+        weapon: WeaponItem {
+            info,
+            runes: Runes::default(),
+            damage: CombatProperties {
+                damage_type: strike.damage_type,
+                dice_faces,
+                nb_dice,
+            },
+            // creature attack bonuses already include their proficiency.
+            category: WeaponCategory::Simple,
+            group: strike.group,
+            is_two_hands: false,
+            is_ranged: strike.range > 0,
+            range: strike.range,
+        },
+        attack_bonus: strike.attack,
+        damage_bonus,
+    })
+}
+
+// creature traits with a rule, the others are only descriptive.
+fn creature_trait(t: &str) -> Option<Rule> {
+    match t {
+        "LE" | "NE" | "CE" => Some(Rule::Evil),
+        "Incorporeal" => Some(Rule::Incorporeal),
+        "Dwarf" => Some(Rule::Dwarf),
+        "Elf" => Some(Rule::Elf),
+        "Gnome" => Some(Rule::Gnome),
+        "Goblin" => Some(Rule::Goblin),
+        "Orc" => Some(Rule::Orc),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Bestiary {
+    creatures: BTreeMap<String, StatBlock>,
+}
+
+impl Bestiary {
+    pub fn core() -> Self {
+        Bestiary::parse(CORE_BESTIARY).expect("the core bestiary should be valid")
+    }
+
+    pub fn load(path: &Path) -> Result<Self, BestiaryError> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| BestiaryError::Io(format!("{}: {}", path.display(), e)))?;
+        Bestiary::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Self, BestiaryError> {
+        let creatures: BTreeMap<String, StatBlock> =
+            ron::from_str(content).map_err(|e| BestiaryError::Parse(e.to_string()))?;
+        for (key, block) in creatures.iter() {
+            block
+                .validate()
+                .map_err(|message| BestiaryError::InvalidEntry {
+                    key: key.clone(),
+                    message,
+                })?;
+        }
+        Ok(Bestiary { creatures })
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.creatures.keys()
+    }
+
+    pub fn get(&self, key: &str) -> Result<&StatBlock, BestiaryError> {
+        self.creatures
+            .get(key)
+            .ok_or_else(|| BestiaryError::UnknownCreature(String::from(key)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        damage::adjust_damage,
+        defense::{compute_ac, compute_save, Save},
+        position::Position,
+        utils::get_active_weapon,
+        world::World,
+    };
+
+    #[test]
+    fn stat_block() {
+        let mut world = World::new();
+        let id = world
+            .spawn_creature("skeleton-guard", "undead", Position::new(0, 0))
+            .unwrap();
+        let skeleton = world.get_character(&id);
+        assert_eq!(skeleton.name, "Skeleton Guard");
+        assert_eq!(skeleton.level, -1);
+        assert_eq!(skeleton.hp, 4);
+        assert_eq!(skeleton.ability_score.dexterity, 18);
+        assert!(skeleton.traits.contains(&Rule::Evil));
+        assert_eq!(compute_ac(skeleton, &world).roll(), 16);
+        assert_eq!(compute_save(skeleton, Save::Reflex, &world).roll(), 8);
+        assert_eq!(get_active_weapon(skeleton, &world).info.name, "Scimitar");
+        assert_eq!(adjust_damage(skeleton, DamageType::Slashing, 7), 2);
+        assert_eq!(adjust_damage(skeleton, DamageType::Bludgeoning, 7), 7);
+
+        let creature = skeleton.creature.as_ref().unwrap();
+        assert_eq!(creature.strikes.len(), 2);
+        assert_eq!(creature.strikes[1].attack_bonus, 6);
+        assert_eq!(creature.strikes[1].damage_bonus, 2);
+        let claw = &creature.strikes[1].weapon.info;
+        assert!(claw.traits.contains(&String::from("finesse")));
+        assert!(!claw.rules.contains(&Rule::Finesse));
+        assert_eq!(
+            skeleton.get_activities(&world)[1].get_name(),
+            "Strike (Claw)"
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Bestiary::core().get("tarrasque").unwrap_err().to_string(),
+            "no creature \"tarrasque\" in the bestiary"
+        );
+        let block = r#"{ "blob": (name: "Blob", level: 0, perception: 0,
+            abilities: (str: 0, dex: 0, con: 0, int: 0, wis: 0, cha: 0),
+            ac: 10, saves: (fortitude: 0, reflex: 0, will: 0), hp: 10, speed: 10,
+            strikes: [(name: "Slam", attack: 4, damage: "1d5", damage_type: Bludgeoning)]) }"#;
+        assert_eq!(
+            Bestiary::parse(block).unwrap_err().to_string(),
+            "invalid stat block \"blob\": Strike Slam: \"1d5\" is not in dice notation like 1d8 or 2d8+5"
+        );
+        assert!(matches!(
+            Bestiary::parse(&block.replace("level: 0", "lvl: 0")),
+            Err(BestiaryError::Parse(_))
+        ));
+    }
+}
@@ -1,41 +1,145 @@
-use crate::{item::Loadout, status::StatusEffect, ui::log};
+use serde::{Deserialize, Serialize};
 
-use self::abilities::AbilityScore;
+use crate::{
+    ai::{Personality, Planner},
+    item::{ItemId, Loadout},
+    position::Position,
+    rules::Rule,
+    spell::LastingEmanation,
+    status::{Duration, StatusEffect, StatusType},
+};
+
+use self::{
+    abilities::{Ability, AbilityScore},
+    creature::CreatureStats,
+    feat::Feat,
+    proficiency::Proficiencies,
+    spellcasting::SpellcastingEntry,
+};
 pub mod abilities;
+pub mod builder;
+pub mod creature;
+pub mod feat;
+pub mod proficiency;
+pub mod progression;
+pub mod skill;
+pub mod spellcasting;
+
+// p473 creature sizes
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Size {
+    Tiny,
+    Small,
+    #[default]
+    Medium,
+    Large,
+    Huge,
+    Gargantuan,
+}
 
-#[derive(Clone, Default)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct Character {
     pub id: String,
     pub max_hp: i64,
     pub name: String,
     pub party: String,
+    // ancestry and creature traits, like Dwarf or Evil.
+    pub traits: Vec<Rule>,
     pub hp: i64,
+    // p461 lost before hit points, they do not stack.
+    pub temp_hp: i64,
     pub initiative: i64,
+    pub level: i64,
+    // p508 experience towards the next level
+    pub xp: i64,
+    pub size: Size,
+    // catalog keys of the character options, set by the character builder.
+    pub ancestry: Option<String>,
+    pub background: Option<String>,
+    pub class: Option<String>,
+    pub key_ability: Option<Ability>,
+    pub feats: Vec<Feat>,
+    pub spellcasting: Vec<SpellcastingEntry>,
+    // the lasting emanations of the spells it cast, like Bless.
+    pub emanations: Vec<LastingEmanation>,
     pub ability_score: AbilityScore,
+    pub proficiencies: Proficiencies,
     pub loadout: Loadout,
+    // p271 the items carried but not equipped, they have to be drawn to be used.
+    pub inventory: Vec<ItemId>,
     pub status: Vec<StatusEffect>,
+    pub position: Position,
+    // in feet
+    pub speed: i64,
+    // p470 you get one reaction per round, refreshed at the start of your turn.
+    pub reaction_available: bool,
+    // p17 actions spent this turn, for the open trait, and one flourish per turn.
+    pub actions_used: i64,
+    pub flourish_used: bool,
+    // set for creatures spawned from a bestiary stat block.
+    pub creature: Option<CreatureStats>,
+    // what its AI cares about.
+    pub personality: Personality,
+    // how its AI plans its turns.
+    pub planner: Planner,
 }
 impl Character {
     pub fn new(name: String, party: String, max_hp: i64) -> Character {
         Character {
-            id: format!("{}-{}", name, snowflake::ProcessUniqueId::new()),
+            // replaced by a unique one when the character is added to the world.
+            id: name.clone(),
             name,
             party,
             max_hp,
             hp: max_hp,
+            level: 1,
+            speed: 25,
+            reaction_available: true,
             ..Default::default()
         }
     }
 
+    // p468 things that happen at the start of your turn.
+    // returns the statuses that ended with the start of the turn.
+    pub fn start_turn(&mut self) -> Vec<StatusType> {
+        self.reaction_available = true;
+        self.actions_used = 0;
+        self.flourish_used = false;
+        self.emanations.iter_mut().for_each(|e| e.sustained = false);
+        let expired = self
+            .status
+            .iter()
+            .filter(|s| s.duration == Duration::StartOfNextTurn)
+            .map(|s| s.status_type)
+            .collect();
+        self.status
+            .retain(|s| s.duration != Duration::StartOfNextTurn);
+        expired
+    }
+
     // we can get negative HP in pathfinder
     #[allow(dead_code)]
     pub fn sub_hp(&mut self, hp: i64) {
-        self.hp = self.hp.saturating_sub(hp);
+        let absorbed = hp.clamp(0, self.temp_hp);
+        self.temp_hp -= absorbed;
+        self.hp = self.hp.saturating_sub(hp - absorbed);
         if self.hp > self.max_hp {
             self.hp = self.max_hp;
         }
-        if self.hp <= 0 {
-            log(&format!("\t{} is unconscious!", self.name));
-        }
+    }
+
+    /**
+    p459 healing starts from 0 HP, so a creature healed above 0 HP is no longer unconscious.
+    Returns the HP regained.
+    */
+    pub fn heal(&mut self, hp: i64) -> i64 {
+        let start = self.hp.max(0);
+        self.hp = (start + hp.max(0)).min(self.max_hp).max(self.hp);
+        self.hp - start
+    }
+
+    // p461 the new temporary HP replace the current ones if they are higher.
+    pub fn add_temp_hp(&mut self, hp: i64) {
+        self.temp_hp = self.temp_hp.max(hp);
     }
 }
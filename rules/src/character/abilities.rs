@@ -1,4 +1,8 @@
-#[derive(Debug, Clone)]
+use serde::{Deserialize, Serialize};
+
+use crate::timeline::get_modifier;
+
+#[derive(Copy, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub enum Ability {
     Strength,
@@ -9,7 +13,7 @@ pub enum Ability {
     Charisma,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AbilityScore {
     pub strength: i64,
     pub dexterity: i64,
@@ -22,12 +26,70 @@ pub struct AbilityScore {
 impl Default for AbilityScore {
     fn default() -> Self {
         AbilityScore {
-        strength: 10,
-        dexterity: 10,
-        constitution: 10,
-        intelligence: 10,
-        wisdom: 10,
-        charisma: 10,
+            strength: 10,
+            dexterity: 10,
+            constitution: 10,
+            intelligence: 10,
+            wisdom: 10,
+            charisma: 10,
+        }
+    }
+}
+
+impl AbilityScore {
+    pub fn get(&self, ability: Ability) -> i64 {
+        match ability {
+            Ability::Strength => self.strength,
+            Ability::Dexterity => self.dexterity,
+            Ability::Constitution => self.constitution,
+            Ability::Intelligence => self.intelligence,
+            Ability::Wisdom => self.wisdom,
+            Ability::Charisma => self.charisma,
+        }
+    }
+
+    pub fn get_modifier(&self, ability: Ability) -> i64 {
+        get_modifier(self.get(ability))
+    }
+
+    fn get_mut(&mut self, ability: Ability) -> &mut i64 {
+        match ability {
+            Ability::Strength => &mut self.strength,
+            Ability::Dexterity => &mut self.dexterity,
+            Ability::Constitution => &mut self.constitution,
+            Ability::Intelligence => &mut self.intelligence,
+            Ability::Wisdom => &mut self.wisdom,
+            Ability::Charisma => &mut self.charisma,
+        }
+    }
+
+    // p20 a boost adds 2, or only 1 from 18.
+    pub fn boost(&mut self, ability: Ability) {
+        let score = self.get_mut(ability);
+        *score += if *score >= 18 { 1 } else { 2 };
+    }
+
+    pub fn flaw(&mut self, ability: Ability) {
+        *self.get_mut(ability) -= 2;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boost() {
+        let mut score = AbilityScore::default();
+        score.boost(Ability::Strength);
+        score.boost(Ability::Strength);
+        score.boost(Ability::Strength);
+        score.boost(Ability::Strength);
+        assert_eq!(score.strength, 18);
+        score.boost(Ability::Strength);
+        assert_eq!(score.get(Ability::Strength), 19);
+        assert_eq!(score.get_modifier(Ability::Strength), 4);
+        score.flaw(Ability::Charisma);
+        assert_eq!(score.get_modifier(Ability::Charisma), -1);
+    }
 }
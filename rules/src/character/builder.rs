@@ -0,0 +1,508 @@
+use std::{collections::BTreeMap, fmt, fs, path::Path};
+
+use serde::Deserialize;
+
+use crate::rules::Rule;
+
+use super::{
+    abilities::{Ability, AbilityScore},
+    feat::Feat,
+    proficiency::{Proficiencies, Proficiency, ProficiencyKind},
+    progression::take_feats,
+    skill::Skill,
+    Character, Size,
+};
+
+// the Core Rulebook options, shipped with the crate.
+const CORE_OPTIONS: &str = include_str!("../../data/character.ron");
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BuildError {
+    Io(String),
+    Parse(String),
+    Unknown { kind: &'static str, key: String },
+    Missing(&'static str),
+    InvalidBoosts(String),
+    InvalidSkills(String),
+    InvalidFeats(String),
+    InvalidLevel(String),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::Io(e) => write!(f, "cannot read the character options: {}", e),
+            BuildError::Parse(e) => write!(f, "malformed character options: {}", e),
+            BuildError::Unknown { kind, key } => write!(f, "unknown {} \"{}\"", kind, key),
+            BuildError::Missing(kind) => write!(f, "the character needs {}", kind),
+            BuildError::InvalidBoosts(e) => write!(f, "invalid ability boosts: {}", e),
+            BuildError::InvalidSkills(e) => write!(f, "invalid skills: {}", e),
+            BuildError::InvalidFeats(e) => write!(f, "invalid feats: {}", e),
+            BuildError::InvalidLevel(e) => write!(f, "cannot level up: {}", e),
+        }
+    }
+}
+
+// p33 ancestry entry
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Ancestry {
+    pub name: String,
+    pub hp: i64,
+    pub size: Size,
+    pub speed: i64,
+    #[serde(default)]
+    pub boosts: Vec<Ability>,
+    #[serde(default)]
+    pub free_boosts: usize,
+    #[serde(default)]
+    pub flaws: Vec<Ability>,
+    #[serde(default)]
+    pub traits: Vec<Rule>,
+}
+
+// p60 background entry, the lore skill is not modelled.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Background {
+    pub name: String,
+    pub boosts: Vec<Ability>,
+    pub skill: Skill,
+}
+
+// p67 class entry, with its initial proficiencies.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Class {
+    pub name: String,
+    pub key_abilities: Vec<Ability>,
+    // gained at each level, on top of the Constitution modifier.
+    pub hp: i64,
+    pub perception: Proficiency,
+    pub fortitude: Proficiency,
+    pub reflex: Proficiency,
+    pub will: Proficiency,
+    #[serde(default)]
+    pub skills: Vec<Skill>,
+    // trained in one of these, like the fighter's Acrobatics or Athletics.
+    #[serde(default)]
+    pub skill_choice: Vec<Skill>,
+    // on top of the Intelligence modifier.
+    pub additional_skills: i64,
+    #[serde(default)]
+    pub unarmed: Proficiency,
+    #[serde(default)]
+    pub simple_weapons: Proficiency,
+    #[serde(default)]
+    pub martial_weapons: Proficiency,
+    #[serde(default)]
+    pub advanced_weapons: Proficiency,
+    #[serde(default)]
+    pub unarmored: Proficiency,
+    #[serde(default)]
+    pub light_armor: Proficiency,
+    #[serde(default)]
+    pub medium_armor: Proficiency,
+    #[serde(default)]
+    pub heavy_armor: Proficiency,
+    // p30 levels granting a class feat, a skill feat or a skill increase.
+    #[serde(default = "even_levels")]
+    pub class_feats: Vec<i64>,
+    #[serde(default = "even_levels")]
+    pub skill_feats: Vec<i64>,
+    #[serde(default = "odd_levels")]
+    pub skill_increases: Vec<i64>,
+    // class features raising proficiencies, by level.
+    #[serde(default)]
+    pub increases: BTreeMap<i64, Vec<(ProficiencyKind, Proficiency)>>,
+}
+
+fn even_levels() -> Vec<i64> {
+    (2..=20).step_by(2).collect()
+}
+
+fn odd_levels() -> Vec<i64> {
+    (3..=19).step_by(2).collect()
+}
+
+impl Class {
+    fn get_proficiencies(&self) -> Proficiencies {
+        Proficiencies {
+            unarmored: self.unarmored,
+            light_armor: self.light_armor,
+            medium_armor: self.medium_armor,
+            heavy_armor: self.heavy_armor,
+            fortitude: self.fortitude,
+            reflex: self.reflex,
+            will: self.will,
+            perception: self.perception,
+            unarmed: self.unarmed,
+            simple_weapons: self.simple_weapons,
+            martial_weapons: self.martial_weapons,
+            advanced_weapons: self.advanced_weapons,
+            class_dc: Proficiency::Trained,
+            skills: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CharacterOptions {
+    pub ancestries: BTreeMap<String, Ancestry>,
+    pub backgrounds: BTreeMap<String, Background>,
+    pub classes: BTreeMap<String, Class>,
+    // the feat registry
+    #[serde(default)]
+    pub feats: BTreeMap<String, Feat>,
+}
+
+impl CharacterOptions {
+    pub fn core() -> Self {
+        CharacterOptions::parse(CORE_OPTIONS).expect("the core character options should be valid")
+    }
+
+    pub fn load(path: &Path) -> Result<Self, BuildError> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| BuildError::Io(format!("{}: {}", path.display(), e)))?;
+        CharacterOptions::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Self, BuildError> {
+        ron::from_str(content).map_err(|e| BuildError::Parse(e.to_string()))
+    }
+
+    pub fn get_ancestry(&self, key: &str) -> Result<&Ancestry, BuildError> {
+        self.ancestries
+            .get(key)
+            .ok_or_else(|| unknown("ancestry", key))
+    }
+
+    pub fn get_background(&self, key: &str) -> Result<&Background, BuildError> {
+        self.backgrounds
+            .get(key)
+            .ok_or_else(|| unknown("background", key))
+    }
+
+    pub fn get_class(&self, key: &str) -> Result<&Class, BuildError> {
+        self.classes.get(key).ok_or_else(|| unknown("class", key))
+    }
+
+    pub fn get_feat(&self, key: &str) -> Result<&Feat, BuildError> {
+        self.feats.get(key).ok_or_else(|| unknown("feat", key))
+    }
+}
+
+fn unknown(kind: &'static str, key: &str) -> BuildError {
+    BuildError::Unknown {
+        kind,
+        key: String::from(key),
+    }
+}
+
+// p26 Hit Points: ancestry HP, then class HP + Constitution modifier at each level.
+pub fn compute_max_hp(ancestry: &Ancestry, class: &Class, character: &Character) -> i64 {
+    let con = character.ability_score.get_modifier(Ability::Constitution);
+    let feats: i64 = character.feats.iter().map(|f| f.hp_per_level).sum();
+    ancestry.hp + (class.hp + con + feats) * character.level
+}
+
+/**
+p21 Builds a level 1 character step by step:
+ancestry, background, class, then the four free ability boosts and the class skills.
+Each step lists the boosts left to the player's choice.
+*/
+#[derive(Clone, Debug, Default)]
+pub struct CharacterBuilder {
+    name: String,
+    party: String,
+    ancestry: Option<(String, Vec<Ability>)>,
+    background: Option<(String, Vec<Ability>)>,
+    class: Option<(String, Ability)>,
+    free_boosts: Vec<Ability>,
+    skills: Vec<Skill>,
+    feats: Vec<String>,
+}
+
+impl CharacterBuilder {
+    pub fn new(name: &str, party: &str) -> Self {
+        CharacterBuilder {
+            name: String::from(name),
+            party: String::from(party),
+            ..Default::default()
+        }
+    }
+
+    pub fn ancestry(mut self, key: &str, free_boosts: &[Ability]) -> Self {
+        self.ancestry = Some((String::from(key), free_boosts.to_vec()));
+        self
+    }
+
+    // the first boost is one of the two of the background, the second is free.
+    pub fn background(mut self, key: &str, boosts: &[Ability]) -> Self {
+        self.background = Some((String::from(key), boosts.to_vec()));
+        self
+    }
+
+    pub fn class(mut self, key: &str, key_ability: Ability) -> Self {
+        self.class = Some((String::from(key), key_ability));
+        self
+    }
+
+    pub fn free_boosts(mut self, boosts: &[Ability]) -> Self {
+        self.free_boosts = boosts.to_vec();
+        self
+    }
+
+    // the skills the class lets the player pick.
+    pub fn skills(mut self, skills: &[Skill]) -> Self {
+        self.skills = skills.to_vec();
+        self
+    }
+
+    // p30 an ancestry feat, and a class feat for some classes.
+    pub fn feats(mut self, feats: &[&str]) -> Self {
+        self.feats = feats.iter().map(|f| String::from(*f)).collect();
+        self
+    }
+
+    pub fn build(&self, options: &CharacterOptions) -> Result<Character, BuildError> {
+        let (ancestry_key, ancestry_boosts) = self
+            .ancestry
+            .as_ref()
+            .ok_or(BuildError::Missing("an ancestry"))?;
+        let (background_key, background_boosts) = self
+            .background
+            .as_ref()
+            .ok_or(BuildError::Missing("a background"))?;
+        let (class_key, key_ability) = self.class.as_ref().ok_or(BuildError::Missing("a class"))?;
+        let ancestry = options.get_ancestry(ancestry_key)?;
+        let background = options.get_background(background_key)?;
+        let class = options.get_class(class_key)?;
+
+        let mut score = AbilityScore::default();
+
+        // ancestry
+        if ancestry_boosts.len() != ancestry.free_boosts {
+            return Err(BuildError::InvalidBoosts(format!(
+                "{} gives {} free boosts, got {}",
+                ancestry.name,
+                ancestry.free_boosts,
+                ancestry_boosts.len()
+            )));
+        }
+        let boosts: Vec<Ability> = ancestry
+            .boosts
+            .iter()
+            .chain(ancestry_boosts.iter())
+            .copied()
+            .collect();
+        check_distinct(&ancestry.name, &boosts)?;
+        boosts.iter().for_each(|a| score.boost(*a));
+        ancestry.flaws.iter().for_each(|a| score.flaw(*a));
+
+        // background
+        match background_boosts.as_slice() {
+            [first, _] if !background.boosts.contains(first) => {
+                return Err(BuildError::InvalidBoosts(format!(
+                    "{} boosts one of {:?}, got {:?}",
+                    background.name, background.boosts, first
+                )))
+            }
+            [_, _] => check_distinct(&background.name, background_boosts)?,
+            _ => {
+                return Err(BuildError::InvalidBoosts(format!(
+                    "{} gives 2 boosts, got {}",
+                    background.name,
+                    background_boosts.len()
+                )))
+            }
+        }
+        background_boosts.iter().for_each(|a| score.boost(*a));
+
+        // class
+        if !class.key_abilities.contains(key_ability) {
+            return Err(BuildError::InvalidBoosts(format!(
+                "the key ability of a {} is one of {:?}, got {:?}",
+                class.name, class.key_abilities, key_ability
+            )));
+        }
+        score.boost(*key_ability);
+
+        // p21 four free boosts
+        if self.free_boosts.len() != 4 {
+            return Err(BuildError::InvalidBoosts(format!(
+                "4 free boosts are needed, got {}",
+                self.free_boosts.len()
+            )));
+        }
+        check_distinct("free boosts", &self.free_boosts)?;
+        self.free_boosts.iter().for_each(|a| score.boost(*a));
+
+        let mut proficiencies = class.get_proficiencies();
+        for skill in self.get_trained_skills(background, class, &score)? {
+            proficiencies.skills.insert(skill, Proficiency::Trained);
+        }
+
+        let mut character = Character::new(self.name.clone(), self.party.clone(), 0);
+        character.ability_score = score;
+        character.proficiencies = proficiencies;
+        character.size = ancestry.size;
+        character.speed = ancestry.speed;
+        character.traits = ancestry.traits.clone();
+        character.ancestry = Some(ancestry_key.clone());
+        character.background = Some(background_key.clone());
+        character.class = Some(class_key.clone());
+        character.key_ability = Some(*key_ability);
+        take_feats(&mut character, &self.feats, class, options)?;
+        character.max_hp = compute_max_hp(ancestry, class, &character);
+        character.hp = character.max_hp;
+        Ok(character)
+    }
+
+    // p22 the background and class skills, then the Intelligence modifier + class ones.
+    fn get_trained_skills(
+        &self,
+        background: &Background,
+        class: &Class,
+        score: &AbilityScore,
+    ) -> Result<Vec<Skill>, BuildError> {
+        let mut trained = vec![background.skill];
+        trained.extend(class.skills.iter().copied());
+        let mut expected = class.additional_skills + score.get_modifier(Ability::Intelligence);
+        if !class.skill_choice.is_empty() {
+            expected += 1;
+        }
+        if self.skills.len() as i64 != expected.max(0) {
+            return Err(BuildError::InvalidSkills(format!(
+                "a {} picks {} skills, got {}",
+                class.name,
+                expected.max(0),
+                self.skills.len()
+            )));
+        }
+        for skill in self.skills.iter() {
+            if trained.contains(skill) {
+                return Err(BuildError::InvalidSkills(format!(
+                    "{:?} is already trained",
+                    skill
+                )));
+            }
+            trained.push(*skill);
+        }
+        if !class.skill_choice.is_empty() && !class.skill_choice.iter().any(|s| trained.contains(s))
+        {
+            return Err(BuildError::InvalidSkills(format!(
+                "a {} is trained in one of {:?}",
+                class.name, class.skill_choice
+            )));
+        }
+        Ok(trained)
+    }
+}
+
+// p20 boosts gained at the same step must go to different abilities.
+pub(super) fn check_distinct(step: &str, boosts: &[Ability]) -> Result<(), BuildError> {
+    for (i, boost) in boosts.iter().enumerate() {
+        if boosts[..i].contains(boost) {
+            return Err(BuildError::InvalidBoosts(format!(
+                "{} boosts {:?} twice",
+                step, boost
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Ability::*;
+
+    fn dwarf_fighter() -> CharacterBuilder {
+        CharacterBuilder::new("Harsk", "heroes")
+            .ancestry("dwarf", &[Strength])
+            .background("warrior", &[Strength, Constitution])
+            .class("fighter", Strength)
+            .free_boosts(&[Strength, Dexterity, Constitution, Wisdom])
+            .skills(&[
+                Skill::Athletics,
+                Skill::Medicine,
+                Skill::Survival,
+                Skill::Crafting,
+            ])
+    }
+
+    #[test]
+    fn build() {
+        let c = dwarf_fighter().build(&CharacterOptions::core()).unwrap();
+        assert_eq!(c.ability_score.strength, 18);
+        assert_eq!(c.ability_score.dexterity, 12);
+        assert_eq!(c.ability_score.constitution, 16);
+        assert_eq!(c.ability_score.wisdom, 14);
+        assert_eq!(c.ability_score.charisma, 8);
+        // 10 dwarf + 10 fighter + 3 con
+        assert_eq!(c.max_hp, 23);
+        assert_eq!(c.hp, 23);
+        assert_eq!(c.speed, 20);
+        assert_eq!(c.traits, vec![Rule::Dwarf]);
+        assert_eq!(c.proficiencies.perception, Proficiency::Expert);
+        assert_eq!(c.proficiencies.martial_weapons, Proficiency::Expert);
+        assert_eq!(
+            c.proficiencies.get_skill(Skill::Intimidation),
+            Proficiency::Trained
+        );
+        assert_eq!(
+            c.proficiencies.get_skill(Skill::Thievery),
+            Proficiency::Untrained
+        );
+    }
+
+    #[test]
+    fn validation() {
+        let options = CharacterOptions::core();
+        let err = |b: CharacterBuilder| b.build(&options).err().unwrap().to_string();
+        assert_eq!(
+            err(dwarf_fighter().ancestry("dwarf", &[Constitution])),
+            "invalid ability boosts: Dwarf boosts Constitution twice"
+        );
+        assert_eq!(
+            err(dwarf_fighter().background("warrior", &[Wisdom, Strength])),
+            "invalid ability boosts: Warrior boosts one of [Strength, Constitution], got Wisdom"
+        );
+        assert_eq!(
+            err(dwarf_fighter().class("fighter", Intelligence)),
+            "invalid ability boosts: the key ability of a Fighter is one of [Strength, Dexterity], got Intelligence"
+        );
+        assert_eq!(
+            err(dwarf_fighter().skills(&[Skill::Medicine])),
+            "invalid skills: a Fighter picks 4 skills, got 1"
+        );
+        assert_eq!(
+            err(dwarf_fighter().skills(&[
+                Skill::Intimidation,
+                Skill::Medicine,
+                Skill::Survival,
+                Skill::Crafting
+            ])),
+            "invalid skills: Intimidation is already trained"
+        );
+        assert_eq!(
+            err(dwarf_fighter().skills(&[
+                Skill::Arcana,
+                Skill::Medicine,
+                Skill::Survival,
+                Skill::Crafting
+            ])),
+            "invalid skills: a Fighter is trained in one of [Acrobatics, Athletics]"
+        );
+        assert_eq!(
+            err(dwarf_fighter().class("oracle", Charisma)),
+            "unknown class \"oracle\""
+        );
+        assert_eq!(
+            err(CharacterBuilder::new("Nobody", "heroes")),
+            "the character needs an ancestry"
+        );
+    }
+}
@@ -0,0 +1,47 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::item::weapon::{DamageType, WeaponItem};
+
+/**
+Creatures built from a stat block use its numbers as they are,
+instead of computing them from ability scores, proficiencies and items.
+*/
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CreatureStats {
+    pub ac: i64,
+    pub fortitude: i64,
+    pub reflex: i64,
+    pub will: i64,
+    pub perception: i64,
+    pub skills: BTreeMap<String, i64>,
+    // the first Strike is the one used for reactions and to decide to close in.
+    pub strikes: Vec<CreatureStrike>,
+    pub resistances: Vec<(DamageType, i64)>,
+    // p301 the resistance to all damage of incorporeal creatures, force damage excepted.
+    #[serde(default)]
+    pub resist_all: i64,
+    pub weaknesses: Vec<(DamageType, i64)>,
+    pub abilities: Vec<CreatureAbility>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CreatureStrike {
+    pub weapon: WeaponItem,
+    pub attack_bonus: i64,
+    pub damage_bonus: i64,
+}
+
+// the special abilities of a stat block that have an implementation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CreatureAbility {
+    AttackOfOpportunity,
+    ShieldBlock,
+}
+
+impl CreatureStats {
+    pub fn has_ability(&self, ability: CreatureAbility) -> bool {
+        self.abilities.contains(&ability)
+    }
+}
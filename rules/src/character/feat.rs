@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+use crate::rules::Rule;
+
+// p30 the kinds of feat slots a character gains while levelling up.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FeatType {
+    Ancestry,
+    Class,
+    General,
+    Skill,
+}
+
+impl FeatType {
+    pub const ALL: [FeatType; 4] = [
+        FeatType::Ancestry,
+        FeatType::Class,
+        FeatType::General,
+        FeatType::Skill,
+    ];
+}
+
+// activities a feat adds to the ones of the character, see `Character::get_activities`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FeatActivity {
+    PowerAttack,
+    SuddenCharge,
+    DoubleSlice,
+    BattleMedicine,
+}
+
+/**
+An entry of the feat registry, with its prerequisites and what it grants.
+Feats without grants are only descriptive.
+*/
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Feat {
+    pub name: String,
+    pub level: i64,
+    #[serde(rename = "type")]
+    pub feat_type: FeatType,
+    // class or ancestry keys allowed to take the feat, anyone if empty.
+    #[serde(default)]
+    pub classes: Vec<String>,
+    #[serde(default)]
+    pub ancestries: Vec<String>,
+    #[serde(default)]
+    pub activities: Vec<FeatActivity>,
+    // rules added to the Strikes of the character.
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+    // like Toughness p266
+    #[serde(default)]
+    pub hp_per_level: i64,
+    #[serde(default)]
+    pub speed: i64,
+}
+
+impl Feat {
+    pub fn check_prerequisites(
+        &self,
+        level: i64,
+        ancestry: &str,
+        class: &str,
+    ) -> Result<(), String> {
+        if self.level > level {
+            return Err(format!("{} needs level {}", self.name, self.level));
+        }
+        if !self.ancestries.is_empty() && !self.ancestries.iter().any(|a| a == ancestry) {
+            return Err(format!("{} is for {:?} only", self.name, self.ancestries));
+        }
+        if !self.classes.is_empty() && !self.classes.iter().any(|c| c == class) {
+            return Err(format!("{} is for {:?} only", self.name, self.classes));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::item::weapon::WeaponCategory;
+
+use super::skill::Skill;
+
+// p13 proficiency ranks. Being trained adds your level + 2, each rank above adds 2 more.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Proficiency {
+    #[default]
+    Untrained,
+    Trained,
+    Expert,
+    Master,
+    Legendary,
+}
+
+impl Proficiency {
+    pub fn bonus(&self, level: i64) -> i64 {
+        match self {
+            Proficiency::Untrained => 0,
+            Proficiency::Trained => level + 2,
+            Proficiency::Expert => level + 4,
+            Proficiency::Master => level + 6,
+            Proficiency::Legendary => level + 8,
+        }
+    }
+
+    pub fn next(&self) -> Option<Proficiency> {
+        match self {
+            Proficiency::Untrained => Some(Proficiency::Trained),
+            Proficiency::Trained => Some(Proficiency::Expert),
+            Proficiency::Expert => Some(Proficiency::Master),
+            Proficiency::Master => Some(Proficiency::Legendary),
+            Proficiency::Legendary => None,
+        }
+    }
+}
+
+// what a class feature can raise, skills are increased one at a time instead.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+pub enum ProficiencyKind {
+    Unarmored,
+    LightArmor,
+    MediumArmor,
+    HeavyArmor,
+    Fortitude,
+    Reflex,
+    Will,
+    Perception,
+    Unarmed,
+    SimpleWeapons,
+    MartialWeapons,
+    AdvancedWeapons,
+    ClassDc,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Proficiencies {
+    pub unarmored: Proficiency,
+    pub light_armor: Proficiency,
+    pub medium_armor: Proficiency,
+    pub heavy_armor: Proficiency,
+    pub fortitude: Proficiency,
+    pub reflex: Proficiency,
+    pub will: Proficiency,
+    pub perception: Proficiency,
+    pub unarmed: Proficiency,
+    pub simple_weapons: Proficiency,
+    pub martial_weapons: Proficiency,
+    pub advanced_weapons: Proficiency,
+    pub class_dc: Proficiency,
+    // untrained skills are left out.
+    pub skills: BTreeMap<Skill, Proficiency>,
+}
+
+impl Proficiencies {
+    // what most martial classes start with.
+    pub fn trained() -> Self {
+        Proficiencies {
+            unarmored: Proficiency::Trained,
+            light_armor: Proficiency::Trained,
+            medium_armor: Proficiency::Trained,
+            heavy_armor: Proficiency::Trained,
+            fortitude: Proficiency::Trained,
+            reflex: Proficiency::Trained,
+            will: Proficiency::Trained,
+            perception: Proficiency::Trained,
+            unarmed: Proficiency::Trained,
+            simple_weapons: Proficiency::Trained,
+            martial_weapons: Proficiency::Trained,
+            advanced_weapons: Proficiency::Untrained,
+            class_dc: Proficiency::Trained,
+            skills: BTreeMap::new(),
+        }
+    }
+
+    pub fn get_weapon(&self, category: WeaponCategory) -> Proficiency {
+        match category {
+            WeaponCategory::Unarmed => self.unarmed,
+            WeaponCategory::Simple => self.simple_weapons,
+            WeaponCategory::Martial => self.martial_weapons,
+            WeaponCategory::Advanced => self.advanced_weapons,
+        }
+    }
+
+    pub fn get_skill(&self, skill: Skill) -> Proficiency {
+        self.skills.get(&skill).copied().unwrap_or_default()
+    }
+
+    pub fn get_mut(&mut self, kind: ProficiencyKind) -> &mut Proficiency {
+        match kind {
+            ProficiencyKind::Unarmored => &mut self.unarmored,
+            ProficiencyKind::LightArmor => &mut self.light_armor,
+            ProficiencyKind::MediumArmor => &mut self.medium_armor,
+            ProficiencyKind::HeavyArmor => &mut self.heavy_armor,
+            ProficiencyKind::Fortitude => &mut self.fortitude,
+            ProficiencyKind::Reflex => &mut self.reflex,
+            ProficiencyKind::Will => &mut self.will,
+            ProficiencyKind::Perception => &mut self.perception,
+            ProficiencyKind::Unarmed => &mut self.unarmed,
+            ProficiencyKind::SimpleWeapons => &mut self.simple_weapons,
+            ProficiencyKind::MartialWeapons => &mut self.martial_weapons,
+            ProficiencyKind::AdvancedWeapons => &mut self.advanced_weapons,
+            ProficiencyKind::ClassDc => &mut self.class_dc,
+        }
+    }
+
+    // class features never lower a proficiency gained some other way.
+    pub fn raise(&mut self, kind: ProficiencyKind, rank: Proficiency) {
+        let current = self.get_mut(kind);
+        *current = (*current).max(rank);
+    }
+}
@@ -0,0 +1,332 @@
+use super::{
+    abilities::Ability,
+    builder::{check_distinct, compute_max_hp, BuildError, CharacterOptions, Class},
+    feat::FeatType,
+    proficiency::Proficiency,
+    skill::Skill,
+    Character,
+};
+
+// p508 experience points needed to gain a level, they are spent when levelling up.
+pub const XP_PER_LEVEL: i64 = 1000;
+pub const MAX_LEVEL: i64 = 20;
+// p31 four ability boosts at these levels.
+const BOOST_LEVELS: [i64; 4] = [5, 10, 15, 20];
+
+// p30 ancestry feats at 1st level then every 4 levels, general feats from 3rd.
+pub fn get_feat_slots(class: &Class, level: i64) -> Vec<FeatType> {
+    FeatType::ALL
+        .iter()
+        .filter(|t| match t {
+            FeatType::Ancestry => level % 4 == 1,
+            FeatType::Class => class.class_feats.contains(&level),
+            FeatType::General => level % 4 == 3,
+            FeatType::Skill => class.skill_feats.contains(&level),
+        })
+        .copied()
+        .collect()
+}
+
+/**
+Fills the feat slots of the current level of the character with feats from the registry.
+Slots can be left empty. p258 skill feats are general feats, they can fill a general slot.
+*/
+pub(crate) fn take_feats(
+    character: &mut Character,
+    keys: &[String],
+    class: &Class,
+    options: &CharacterOptions,
+) -> Result<(), BuildError> {
+    let mut slots = get_feat_slots(class, character.level);
+    for key in keys {
+        let feat = options.get_feat(key)?;
+        if character.feats.iter().any(|f| f.name == feat.name) {
+            return Err(BuildError::InvalidFeats(format!(
+                "{} is already taken",
+                feat.name
+            )));
+        }
+        feat.check_prerequisites(
+            character.level,
+            character.ancestry.as_deref().unwrap_or_default(),
+            character.class.as_deref().unwrap_or_default(),
+        )
+        .map_err(BuildError::InvalidFeats)?;
+        let slot = slots
+            .iter()
+            .position(|s| *s == feat.feat_type)
+            .or_else(|| match feat.feat_type {
+                FeatType::Skill => slots.iter().position(|s| *s == FeatType::General),
+                _ => None,
+            })
+            .ok_or_else(|| {
+                BuildError::InvalidFeats(format!(
+                    "no {:?} feat slot left for {} at level {}",
+                    feat.feat_type, feat.name, character.level
+                ))
+            })?;
+        slots.remove(slot);
+        character.speed += feat.speed;
+        character.feats.push(feat.clone());
+    }
+    Ok(())
+}
+
+// p31 the choices made when gaining a level, the class features are automatic.
+#[derive(Clone, Debug, Default)]
+pub struct LevelUp {
+    boosts: Vec<Ability>,
+    skill_increase: Option<Skill>,
+    feats: Vec<String>,
+}
+
+impl LevelUp {
+    pub fn new() -> Self {
+        LevelUp::default()
+    }
+
+    pub fn boosts(mut self, boosts: &[Ability]) -> Self {
+        self.boosts = boosts.to_vec();
+        self
+    }
+
+    pub fn skill_increase(mut self, skill: Skill) -> Self {
+        self.skill_increase = Some(skill);
+        self
+    }
+
+    pub fn feats(mut self, feats: &[&str]) -> Self {
+        self.feats = feats.iter().map(|f| String::from(*f)).collect();
+        self
+    }
+}
+
+impl Character {
+    pub fn gain_xp(&mut self, xp: i64) {
+        self.xp += xp;
+    }
+
+    pub fn can_level_up(&self) -> bool {
+        self.xp >= XP_PER_LEVEL && self.level < MAX_LEVEL
+    }
+
+    /**
+    p31 Gains a level: ability boosts, skill increase, class proficiencies, feats and HP.
+    The character is left untouched if one of the choices is invalid.
+    */
+    pub fn level_up(
+        &mut self,
+        choices: &LevelUp,
+        options: &CharacterOptions,
+    ) -> Result<(), BuildError> {
+        if self.level >= MAX_LEVEL {
+            return Err(BuildError::InvalidLevel(format!(
+                "{} is already level {}",
+                self.name, MAX_LEVEL
+            )));
+        }
+        if self.xp < XP_PER_LEVEL {
+            return Err(BuildError::InvalidLevel(format!(
+                "{} has {} XP out of {}",
+                self.name, self.xp, XP_PER_LEVEL
+            )));
+        }
+        let ancestry = options.get_ancestry(
+            self.ancestry
+                .as_deref()
+                .ok_or(BuildError::Missing("an ancestry"))?,
+        )?;
+        let class = options.get_class(
+            self.class
+                .as_deref()
+                .ok_or(BuildError::Missing("a class"))?,
+        )?;
+
+        let mut character = self.clone();
+        character.level += 1;
+        let level = character.level;
+
+        if BOOST_LEVELS.contains(&level) {
+            if choices.boosts.len() != 4 {
+                return Err(BuildError::InvalidBoosts(format!(
+                    "4 boosts are gained at level {}, got {}",
+                    level,
+                    choices.boosts.len()
+                )));
+            }
+            check_distinct(&format!("level {}", level), &choices.boosts)?;
+            for boost in choices.boosts.iter() {
+                character.ability_score.boost(*boost);
+            }
+        } else if !choices.boosts.is_empty() {
+            return Err(BuildError::InvalidBoosts(format!(
+                "no boosts are gained at level {}",
+                level
+            )));
+        }
+
+        match (
+            class.skill_increases.contains(&level),
+            choices.skill_increase,
+        ) {
+            (true, Some(skill)) => increase_skill(&mut character, skill)?,
+            (false, None) => {}
+            (true, None) => {
+                return Err(BuildError::InvalidSkills(format!(
+                    "a skill increase is gained at level {}",
+                    level
+                )))
+            }
+            (false, Some(_)) => {
+                return Err(BuildError::InvalidSkills(format!(
+                    "no skill increase is gained at level {}",
+                    level
+                )))
+            }
+        }
+
+        if let Some(increases) = class.increases.get(&level) {
+            for (kind, rank) in increases.iter() {
+                character.proficiencies.raise(*kind, *rank);
+            }
+        }
+
+        take_feats(&mut character, &choices.feats, class, options)?;
+
+        // p26 a higher Constitution modifier counts for the previous levels too.
+        let previous = character.max_hp;
+        character.max_hp = compute_max_hp(ancestry, class, &character);
+        character.hp += character.max_hp - previous;
+        character.xp -= XP_PER_LEVEL;
+        *self = character;
+        Ok(())
+    }
+}
+
+// p31 master from 7th level, legendary from 15th.
+fn increase_skill(character: &mut Character, skill: Skill) -> Result<(), BuildError> {
+    let rank = character
+        .proficiencies
+        .get_skill(skill)
+        .next()
+        .ok_or_else(|| BuildError::InvalidSkills(format!("{:?} is already legendary", skill)))?;
+    let min_level = match rank {
+        Proficiency::Master => 7,
+        Proficiency::Legendary => 15,
+        _ => 1,
+    };
+    if character.level < min_level {
+        return Err(BuildError::InvalidSkills(format!(
+            "{:?} cannot be {:?} before level {}",
+            skill, rank, min_level
+        )));
+    }
+    character.proficiencies.skills.insert(skill, rank);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{character::builder::CharacterBuilder, world::World};
+    use Ability::*;
+
+    fn fighter(feats: &[&str]) -> CharacterBuilder {
+        CharacterBuilder::new("Valeros", "heroes")
+            .ancestry("human", &[Strength, Constitution])
+            .background("warrior", &[Strength, Dexterity])
+            .class("fighter", Strength)
+            .free_boosts(&[Strength, Dexterity, Constitution, Wisdom])
+            .skills(&[
+                Skill::Athletics,
+                Skill::Medicine,
+                Skill::Survival,
+                Skill::Crafting,
+            ])
+            .feats(feats)
+    }
+
+    #[test]
+    fn level_up() {
+        let options = CharacterOptions::core();
+        let mut c = fighter(&["power-attack"]).build(&options).unwrap();
+        // 8 human + 10 fighter + 2 con
+        assert_eq!(c.max_hp, 20);
+        assert_eq!(c.feats[0].name, "Power Attack");
+        assert!(c
+            .get_activities(&World::new())
+            .iter()
+            .any(|a| a.get_name() == "Power Attack"));
+        assert!(!c.can_level_up());
+        assert!(matches!(
+            c.level_up(&LevelUp::new(), &options),
+            Err(BuildError::InvalidLevel(_))
+        ));
+
+        c.gain_xp(1000);
+        assert!(c.can_level_up());
+        c.level_up(&LevelUp::new(), &options).unwrap();
+        assert_eq!((c.level, c.xp, c.max_hp), (2, 0, 32));
+
+        c.gain_xp(1000);
+        let err = c
+            .level_up(&LevelUp::new().feats(&["toughness"]), &options)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            BuildError::InvalidSkills(String::from("a skill increase is gained at level 3"))
+        );
+        assert_eq!(c.level, 2);
+        let choices = LevelUp::new()
+            .skill_increase(Skill::Athletics)
+            .feats(&["toughness"]);
+        c.level_up(&choices, &options).unwrap();
+        // 8 + 3 * (10 + 2 + 1 toughness)
+        assert_eq!(c.max_hp, 47);
+        assert_eq!(
+            c.proficiencies.get_skill(Skill::Athletics),
+            Proficiency::Expert
+        );
+        assert_eq!(c.proficiencies.will, Proficiency::Expert);
+
+        c.gain_xp(2000);
+        c.level_up(&LevelUp::new(), &options).unwrap();
+        let choices = LevelUp::new()
+            .boosts(&[Strength, Dexterity, Constitution, Wisdom])
+            .skill_increase(Skill::Medicine);
+        assert!(matches!(
+            c.level_up(&choices.clone().skill_increase(Skill::Athletics), &options),
+            Err(BuildError::InvalidSkills(_))
+        ));
+        c.level_up(&choices, &options).unwrap();
+        assert_eq!(c.ability_score.strength, 19);
+        // con 14 -> 16 also counts for the previous levels
+        assert_eq!(c.max_hp, 8 + 5 * (10 + 3 + 1));
+        assert_eq!(c.proficiencies.martial_weapons, Proficiency::Master);
+    }
+
+    #[test]
+    fn feats() {
+        let options = CharacterOptions::core();
+        let build = |feats: &[&str]| fighter(feats).build(&options).map(|c| c.speed);
+        assert_eq!(build(&["natural-ambition", "power-attack"]), Ok(25));
+        assert_eq!(
+            build(&["toughness"]).unwrap_err().to_string(),
+            "invalid feats: no General feat slot left for Toughness at level 1"
+        );
+        assert_eq!(
+            build(&["power-attack", "power-attack"])
+                .unwrap_err()
+                .to_string(),
+            "invalid feats: Power Attack is already taken"
+        );
+        assert_eq!(
+            build(&["unburdened-iron"]).unwrap_err().to_string(),
+            "invalid feats: Unburdened Iron is for [\"dwarf\"] only"
+        );
+        assert_eq!(
+            build(&["sudden-leap"]).unwrap_err().to_string(),
+            "unknown feat \"sudden-leap\""
+        );
+    }
+}
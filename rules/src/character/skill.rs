@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+use super::{abilities::Ability, proficiency::Proficiency, Character};
+
+// p233 the core skills. Lore skills are not modelled.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Skill {
+    Acrobatics,
+    Arcana,
+    Athletics,
+    Crafting,
+    Deception,
+    Diplomacy,
+    Intimidation,
+    Medicine,
+    Nature,
+    Occultism,
+    Performance,
+    Religion,
+    Society,
+    Stealth,
+    Survival,
+    Thievery,
+}
+
+impl Skill {
+    // p234 table 4-1 key ability of each skill
+    pub fn get_ability(&self) -> Ability {
+        match self {
+            Skill::Acrobatics | Skill::Stealth | Skill::Thievery => Ability::Dexterity,
+            Skill::Athletics => Ability::Strength,
+            Skill::Arcana | Skill::Crafting | Skill::Occultism | Skill::Society => {
+                Ability::Intelligence
+            }
+            Skill::Medicine | Skill::Nature | Skill::Religion | Skill::Survival => Ability::Wisdom,
+            Skill::Deception | Skill::Diplomacy | Skill::Intimidation | Skill::Performance => {
+                Ability::Charisma
+            }
+        }
+    }
+}
+
+impl Character {
+    // creatures are trained in the skills of their stat block.
+    pub fn is_trained(&self, skill: Skill) -> bool {
+        match &self.creature {
+            Some(creature) => creature.skills.contains_key(&format!("{:?}", skill)),
+            None => self.proficiencies.get_skill(skill) >= Proficiency::Trained,
+        }
+    }
+
+    // p446 ability modifier + proficiency bonus, creatures use their stat block.
+    pub fn get_skill_modifier(&self, skill: Skill) -> i64 {
+        let ability = self.ability_score.get_modifier(skill.get_ability());
+        let modifier = match &self.creature {
+            Some(creature) => creature
+                .skills
+                .get(&format!("{:?}", skill))
+                .copied()
+                .unwrap_or(ability),
+            None => ability + self.proficiencies.get_skill(skill).bonus(self.level),
+        };
+        modifier + self.get_ability_status_penalty(skill.get_ability()).roll()
+    }
+}
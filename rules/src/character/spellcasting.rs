@@ -0,0 +1,310 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use dice::Roll;
+
+use crate::spell::{SpellError, Spellbook, Tradition};
+
+use super::{abilities::Ability, proficiency::Proficiency, Character};
+
+// p299 prepared casters choose their spells every day, spontaneous ones cast from a repertoire.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CastingType {
+    Prepared,
+    Spontaneous,
+}
+
+// a spell prepared in a slot of `rank`, heightened if the rank is higher than the spell's.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PreparedSpell {
+    pub spell: String,
+    pub rank: i64,
+    pub expended: bool,
+}
+
+// what casting a spell spends.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SpellSlot {
+    Cantrip,
+    // index in the prepared spells
+    Prepared(usize),
+    // a slot of that rank
+    Spontaneous(i64),
+    // p564 the spell comes from a held scroll, the entry only gives its statistics.
+    Scroll,
+}
+
+// a spell that can be cast right now, with the slot it would spend.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CastableSpell {
+    pub spell: String,
+    pub rank: i64,
+    pub slot: SpellSlot,
+}
+
+/**
+p298 where the spells of a character come from, like a wizard's spellbook or a sorcerer's bloodline.
+Spell keys refer to the spell list of the world, see `Spellbook`.
+*/
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SpellcastingEntry {
+    pub name: String,
+    pub tradition: Tradition,
+    pub casting: CastingType,
+    pub ability: Ability,
+    pub proficiency: Proficiency,
+    // spell slots per rank
+    pub slots: BTreeMap<i64, i64>,
+    pub cantrips: Vec<String>,
+    pub prepared: Vec<PreparedSpell>,
+    pub repertoire: Vec<String>,
+    // spontaneous slots used per rank since the last daily preparations.
+    pub spent: BTreeMap<i64, i64>,
+}
+
+// p300 cantrips are heightened to half the level, rounded up.
+pub fn get_cantrip_rank(level: i64) -> i64 {
+    ((level + 1) / 2).max(1)
+}
+
+impl SpellcastingEntry {
+    pub fn new(name: &str, tradition: Tradition, casting: CastingType, ability: Ability) -> Self {
+        SpellcastingEntry {
+            name: String::from(name),
+            tradition,
+            casting,
+            ability,
+            proficiency: Proficiency::Trained,
+            slots: BTreeMap::new(),
+            cantrips: vec![],
+            prepared: vec![],
+            repertoire: vec![],
+            spent: BTreeMap::new(),
+        }
+    }
+
+    pub fn slots(mut self, rank: i64, count: i64) -> Self {
+        self.slots.insert(rank, count);
+        self
+    }
+
+    fn check_spell(
+        &self,
+        spellbook: &Spellbook,
+        key: &str,
+        cantrip: bool,
+    ) -> Result<(), SpellError> {
+        let spell = spellbook.get(key)?;
+        let unavailable = |message: String| SpellError::Unavailable {
+            key: String::from(key),
+            message,
+        };
+        if !spell.traditions.contains(&self.tradition) {
+            return Err(unavailable(format!(
+                "{} is not on the {:?} spell list",
+                spell.name, self.tradition
+            )));
+        }
+        if spell.is_cantrip() != cantrip {
+            return Err(unavailable(format!(
+                "{} {} a cantrip",
+                spell.name,
+                if cantrip { "is not" } else { "is" }
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn add_cantrip(&mut self, spellbook: &Spellbook, key: &str) -> Result<(), SpellError> {
+        self.check_spell(spellbook, key, true)?;
+        self.cantrips.push(String::from(key));
+        Ok(())
+    }
+
+    // p298 prepares a spell in a free slot of `rank`, which heightens it.
+    pub fn prepare(
+        &mut self,
+        spellbook: &Spellbook,
+        key: &str,
+        rank: i64,
+    ) -> Result<(), SpellError> {
+        self.check_spell(spellbook, key, false)?;
+        let unavailable = |message: String| SpellError::Unavailable {
+            key: String::from(key),
+            message,
+        };
+        if self.casting != CastingType::Prepared {
+            return Err(unavailable(format!(
+                "{} is not a prepared entry",
+                self.name
+            )));
+        }
+        let spell = spellbook.get(key)?;
+        if rank < spell.rank {
+            return Err(unavailable(format!(
+                "{} cannot be prepared below rank {}",
+                spell.name, spell.rank
+            )));
+        }
+        let used = self.prepared.iter().filter(|p| p.rank == rank).count() as i64;
+        if used >= self.slots.get(&rank).copied().unwrap_or(0) {
+            return Err(unavailable(format!("no rank {} slot left", rank)));
+        }
+        self.prepared.push(PreparedSpell {
+            spell: String::from(key),
+            rank,
+            expended: false,
+        });
+        Ok(())
+    }
+
+    // p300 adds a spell to the repertoire, it is cast with the slots of its rank.
+    pub fn learn(&mut self, spellbook: &Spellbook, key: &str) -> Result<(), SpellError> {
+        self.check_spell(spellbook, key, false)?;
+        if self.casting != CastingType::Spontaneous {
+            return Err(SpellError::Unavailable {
+                key: String::from(key),
+                message: format!("{} is not a spontaneous entry", self.name),
+            });
+        }
+        self.repertoire.push(String::from(key));
+        Ok(())
+    }
+
+    // p483 daily preparations give back every slot.
+    pub fn refresh(&mut self) {
+        self.prepared.iter_mut().for_each(|p| p.expended = false);
+        self.spent.clear();
+    }
+
+    pub fn get_castable(&self, spellbook: &Spellbook, level: i64) -> Vec<CastableSpell> {
+        let mut castable: Vec<CastableSpell> = self
+            .cantrips
+            .iter()
+            .map(|key| CastableSpell {
+                spell: key.clone(),
+                rank: get_cantrip_rank(level),
+                slot: SpellSlot::Cantrip,
+            })
+            .collect();
+        for (index, prepared) in self.prepared.iter().enumerate() {
+            let known = castable
+                .iter()
+                .any(|c| c.spell == prepared.spell && c.rank == prepared.rank);
+            if !prepared.expended && !known {
+                castable.push(CastableSpell {
+                    spell: prepared.spell.clone(),
+                    rank: prepared.rank,
+                    slot: SpellSlot::Prepared(index),
+                });
+            }
+        }
+        for key in self.repertoire.iter() {
+            let rank = match spellbook.get(key) {
+                Ok(spell) => spell.rank,
+                Err(_) => continue,
+            };
+            let spent = self.spent.get(&rank).copied().unwrap_or(0);
+            if spent < self.slots.get(&rank).copied().unwrap_or(0) {
+                castable.push(CastableSpell {
+                    spell: key.clone(),
+                    rank,
+                    slot: SpellSlot::Spontaneous(rank),
+                });
+            }
+        }
+        castable
+    }
+
+    pub fn expend(&mut self, slot: SpellSlot) {
+        match slot {
+            SpellSlot::Cantrip | SpellSlot::Scroll => {}
+            SpellSlot::Prepared(index) => self.prepared[index].expended = true,
+            SpellSlot::Spontaneous(rank) => *self.spent.entry(rank).or_insert(0) += 1,
+        }
+    }
+
+    // p298 spell attack modifier: key ability modifier + proficiency bonus.
+    pub fn get_spell_attack(&self, character: &Character) -> Roll {
+        Roll::flat(
+            "ability",
+            character.ability_score.get_modifier(self.ability),
+        ) + Roll::flat("proficiency", self.proficiency.bonus(character.level))
+            + character.get_status_penalty()
+    }
+
+    // p298 spell DC: 10 + the same modifiers.
+    pub fn get_spell_dc(&self, character: &Character) -> i64 {
+        10 + self.get_spell_attack(character).roll()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slots() {
+        let spellbook = Spellbook::core();
+        let mut wizard = SpellcastingEntry::new(
+            "Arcane Prepared Spells",
+            Tradition::Arcane,
+            CastingType::Prepared,
+            Ability::Intelligence,
+        )
+        .slots(1, 2)
+        .slots(3, 1);
+        wizard.add_cantrip(&spellbook, "ray-of-frost").unwrap();
+        wizard.prepare(&spellbook, "magic-missile", 1).unwrap();
+        wizard.prepare(&spellbook, "magic-missile", 1).unwrap();
+        wizard.prepare(&spellbook, "fear", 3).unwrap();
+        assert_eq!(
+            wizard
+                .prepare(&spellbook, "fear", 1)
+                .unwrap_err()
+                .to_string(),
+            "cannot add \"fear\": no rank 1 slot left"
+        );
+        assert_eq!(
+            wizard
+                .prepare(&spellbook, "bless", 3)
+                .unwrap_err()
+                .to_string(),
+            "cannot add \"bless\": Bless is not on the Arcane spell list"
+        );
+        assert!(wizard.add_cantrip(&spellbook, "fireball").is_err());
+        assert!(wizard.learn(&spellbook, "fireball").is_err());
+
+        let castable = wizard.get_castable(&spellbook, 5);
+        // the cantrip heightened to rank 3, the two missiles only once
+        assert_eq!(
+            castable.iter().map(|c| c.rank).collect::<Vec<_>>(),
+            vec![3, 1, 3]
+        );
+        wizard.expend(castable[1].slot);
+        assert_eq!(wizard.get_castable(&spellbook, 5).len(), 3);
+        wizard.expend(SpellSlot::Prepared(1));
+        assert_eq!(wizard.get_castable(&spellbook, 5).len(), 2);
+        wizard.refresh();
+        assert_eq!(wizard.get_castable(&spellbook, 5).len(), 3);
+
+        let mut sorcerer = SpellcastingEntry::new(
+            "Occult Spontaneous Spells",
+            Tradition::Occult,
+            CastingType::Spontaneous,
+            Ability::Charisma,
+        )
+        .slots(1, 1);
+        sorcerer.learn(&spellbook, "bless").unwrap();
+        sorcerer.learn(&spellbook, "fear").unwrap();
+        assert_eq!(sorcerer.get_castable(&spellbook, 1).len(), 2);
+        sorcerer.expend(SpellSlot::Spontaneous(1));
+        assert!(sorcerer.get_castable(&spellbook, 1).is_empty());
+
+        let mut character = Character::new(String::from("Ezren"), String::from("heroes"), 16);
+        character.ability_score.charisma = 18;
+        // 4 cha + 3 trained
+        assert_eq!(sorcerer.get_spell_attack(&character).roll(), 7);
+        assert_eq!(sorcerer.get_spell_dc(&character), 17);
+    }
+}
@@ -0,0 +1,114 @@
+use dice::Roll;
+use serde::{Deserialize, Serialize};
+
+use crate::fact::{Fact, Facts};
+
+// p450 a flat check: a d20 without modifiers, it succeeds at or above the DC.
+pub fn flat_check(character_id: &str, dc: i64, facts: &mut Facts) -> bool {
+    let total = Roll::d("", 1, 20).roll();
+    let success = total >= dc;
+    facts.push(Fact::Check {
+        character_id: String::from(character_id),
+        check: String::from("attempts a flat check"),
+        total,
+        dc,
+        degree: if success {
+            DegreeOfSuccess::Success
+        } else {
+            DegreeOfSuccess::Failure
+        },
+    });
+    success
+}
+
+// p445 the four degrees of success of a check.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum DegreeOfSuccess {
+    CriticalFailure,
+    Failure,
+    Success,
+    CriticalSuccess,
+}
+
+impl DegreeOfSuccess {
+    /**
+    Beating the DC by 10 or more is a critical success, failing by 10 or more a critical failure.
+    A natural 20 improves the degree by one step, a natural 1 worsens it by one step.
+    */
+    pub fn from_check(total: i64, natural: i64, dc: i64) -> Self {
+        let degree = match total - dc {
+            d if d >= 10 => DegreeOfSuccess::CriticalSuccess,
+            d if d >= 0 => DegreeOfSuccess::Success,
+            d if d > -10 => DegreeOfSuccess::Failure,
+            _ => DegreeOfSuccess::CriticalFailure,
+        };
+        match natural {
+            20 => degree.improve(),
+            1 => degree.worsen(),
+            _ => degree,
+        }
+    }
+
+    fn improve(self) -> Self {
+        match self {
+            DegreeOfSuccess::CriticalFailure => DegreeOfSuccess::Failure,
+            DegreeOfSuccess::Failure => DegreeOfSuccess::Success,
+            _ => DegreeOfSuccess::CriticalSuccess,
+        }
+    }
+
+    fn worsen(self) -> Self {
+        match self {
+            DegreeOfSuccess::CriticalSuccess => DegreeOfSuccess::Success,
+            DegreeOfSuccess::Success => DegreeOfSuccess::Failure,
+            _ => DegreeOfSuccess::CriticalFailure,
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        *self >= DegreeOfSuccess::Success
+    }
+}
+
+// the chance of every degree of success of a d20 check, indexed by degree.
+pub fn get_chances(modifier: i64, dc: i64) -> [f64; 4] {
+    let mut chances = [0.0; 4];
+    for natural in 1..=20 {
+        chances[DegreeOfSuccess::from_check(natural + modifier, natural, dc) as usize] += 0.05;
+    }
+    chances
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DegreeOfSuccess::*;
+    use super::*;
+
+    #[test]
+    fn degrees() {
+        assert_eq!(DegreeOfSuccess::from_check(25, 15, 15), CriticalSuccess);
+        assert_eq!(DegreeOfSuccess::from_check(15, 5, 15), Success);
+        assert_eq!(DegreeOfSuccess::from_check(14, 5, 15), Failure);
+        assert_eq!(DegreeOfSuccess::from_check(5, 2, 15), CriticalFailure);
+        assert_eq!(DegreeOfSuccess::from_check(14, 20, 15), Success);
+        assert_eq!(DegreeOfSuccess::from_check(30, 20, 15), CriticalSuccess);
+        assert_eq!(DegreeOfSuccess::from_check(16, 1, 15), Failure);
+        assert!(!CriticalFailure.is_success());
+    }
+
+    #[test]
+    fn chances() {
+        let chances = get_chances(5, 15);
+        assert_eq!(
+            chances
+                .iter()
+                .map(|c| (c * 20.0).round() as i64)
+                .collect::<Vec<_>>(),
+            vec![1, 8, 10, 1]
+        );
+        // only a natural 20 succeeds.
+        let chances = get_chances(0, 25);
+        assert!((chances[Success as usize] - 0.05).abs() < 1e-9);
+        assert!((chances[CriticalFailure as usize] - 0.75).abs() < 1e-9);
+    }
+}
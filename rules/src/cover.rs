@@ -0,0 +1,146 @@
+use crate::{character::Character, position::Position, status::StatusType, world::World};
+
+// p477 the degrees of cover, from the point of view of an attacker.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Cover {
+    None,
+    Lesser,
+    Standard,
+    Greater,
+}
+
+impl Cover {
+    // circumstance bonus to AC
+    pub fn get_ac_bonus(&self) -> i64 {
+        match self {
+            Cover::None => 0,
+            Cover::Lesser => 1,
+            Cover::Standard => 2,
+            Cover::Greater => 4,
+        }
+    }
+
+    // circumstance bonus to Reflex saves against area effects, lesser cover gives none.
+    pub fn get_reflex_bonus(&self) -> i64 {
+        match self {
+            Cover::Lesser => 0,
+            c => c.get_ac_bonus(),
+        }
+    }
+}
+
+/**
+p477 draw a line from the center of the attacker's square to the center of the target's square:
+a wall on the way gives standard cover, another creature gives lesser cover.
+p471 Take Cover turns standard cover into greater cover, and prone creatures get standard cover
+against ranged attacks.
+*/
+pub fn get_cover(attacker: &Character, target: &Character, ranged: bool, world: &World) -> Cover {
+    get_cover_from(&attacker.position, target, ranged, world)
+}
+
+// the cover of a target from a square, like the origin of an area for a Reflex save. p477
+pub fn get_cover_from(origin: &Position, target: &Character, ranged: bool, world: &World) -> Cover {
+    let line = world.map.get_center_line(origin, &target.position);
+    let mut cover = if line.iter().any(|s| world.map.is_wall(s)) {
+        Cover::Standard
+    } else if world.get_characters().iter().any(|c| {
+        c.hp > 0 && c.position != *origin && c.id != target.id && line.contains(&c.position)
+    }) {
+        Cover::Lesser
+    } else {
+        Cover::None
+    };
+    if target.has_status(StatusType::TakingCover) {
+        if cover == Cover::Standard {
+            cover = Cover::Greater;
+        } else if ranged && target.has_status(StatusType::Prone) {
+            cover = Cover::Standard;
+        }
+    }
+    cover
+}
+
+/**
+p618 the flat check needed to target a creature: DC 11 when it is hidden, because it is out of
+sight or has the hidden condition, DC 5 when it is concealed, in smoke or with the condition.
+*/
+pub fn get_flat_check_dc(attacker: &Character, target: &Character, world: &World) -> Option<i64> {
+    if target.has_status(StatusType::Hidden)
+        || !world
+            .map
+            .has_line_of_sight(&attacker.position, &target.position)
+    {
+        Some(11)
+    } else if target.has_status(StatusType::Concealed) || world.map.smoke.contains(&target.position)
+    {
+        Some(5)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        map::Map,
+        status::{Duration, StatusEffect},
+    };
+
+    #[test]
+    fn cover() {
+        let mut world = World::new();
+        world.map = Map::new()
+            .add_wall(Position::new(2, 0), Position::new(2, 0))
+            .add_smoke(Position::new(0, 4), Position::new(0, 4));
+        let archer = world.add_character_at("Archer", "Archer", 10, Position::new(0, 0));
+        let behind_wall = world.add_character_at("Goblin", "Goblin", 10, Position::new(4, 1));
+        let behind_goblin = world.add_character_at("Kobold", "Kobold", 10, Position::new(0, 2));
+        world.add_character_at("Orc", "Orc", 10, Position::new(0, 1));
+        let in_smoke = world.add_character_at("Wolf", "Wolf", 10, Position::new(0, 4));
+
+        let get = |world: &World, id: &str| {
+            let archer = world.get_character(&archer);
+            let target = world.get_character(id);
+            (
+                get_cover(archer, target, true, world),
+                get_flat_check_dc(archer, target, world),
+            )
+        };
+        assert_eq!(get(&world, &behind_wall), (Cover::Standard, None));
+        assert_eq!(get(&world, &behind_goblin), (Cover::Lesser, None));
+        assert_eq!(get(&world, &in_smoke), (Cover::Lesser, Some(5)));
+
+        // an area bursting past the wall, or from the square of the orc.
+        let target = world.get_character(&behind_wall);
+        assert_eq!(
+            get_cover_from(&Position::new(4, 0), target, true, &world),
+            Cover::None
+        );
+        let target = world.get_character(&behind_goblin);
+        assert_eq!(
+            get_cover_from(&Position::new(0, 1), target, true, &world),
+            Cover::None
+        );
+
+        let take_cover = StatusEffect {
+            status_type: StatusType::TakingCover,
+            duration: Duration::UntilRemoved,
+        };
+        world
+            .get_mut_character(&behind_wall)
+            .add_status(take_cover.clone());
+        assert_eq!(get(&world, &behind_wall).0, Cover::Greater);
+        assert_eq!(Cover::Greater.get_ac_bonus(), 4);
+        assert_eq!(Cover::Lesser.get_reflex_bonus(), 0);
+
+        world
+            .get_mut_character(&behind_goblin)
+            .add_status(StatusEffect {
+                status_type: StatusType::Hidden,
+                duration: Duration::Round(1),
+            });
+        assert_eq!(get(&world, &behind_goblin).1, Some(11));
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{
+    character::Character,
+    fact::{self, Fact},
+    item::weapon::DamageType,
+    reaction::{offer_reactions, Trigger},
+    rules::Rule,
+    world::World,
+};
+
+// damage about to be dealt, with its type and how it was rolled.
+#[derive(Clone, Debug, Default)]
+pub struct Damage {
+    pub amount: i64,
+    pub damage_type: Option<DamageType>,
+    pub details: String,
+}
+
+impl Damage {
+    pub fn new(amount: i64) -> Self {
+        Self {
+            amount,
+            ..Default::default()
+        }
+    }
+
+    pub fn of_type(mut self, damage_type: DamageType) -> Self {
+        self.damage_type = Some(damage_type);
+        self
+    }
+
+    pub fn details(mut self, details: &str) -> Self {
+        self.details = String::from(details);
+        self
+    }
+}
+
+/**
+Every source of damage goes through here, so that "you would take damage" reactions
+like Shield Block get a chance to reduce it before it is applied.
+Returns the damage actually dealt.
+*/
+pub fn apply_damage(
+    source_id: &str,
+    target_id: &str,
+    damage: Damage,
+    world: &mut World,
+    facts: &mut fact::Facts,
+) -> i64 {
+    let amount = damage.amount;
+    let mut trigger = Trigger::Damage {
+        source: String::from(source_id),
+        target: String::from(target_id),
+        amount,
+    };
+    if amount > 0 {
+        offer_reactions(&mut trigger, world, facts);
+    }
+    let dealt = match trigger {
+        Trigger::Damage { amount, .. } => amount.max(0),
+        _ => amount,
+    };
+    let target = world.get_mut_character(target_id);
+    let conscious = target.hp > 0;
+    target.sub_hp(dealt);
+    let knocked_out = conscious && target.hp <= 0;
+    facts.push(Fact::Damage {
+        source_id: String::from(source_id),
+        target_id: String::from(target_id),
+        amount: dealt,
+        damage_type: damage.damage_type,
+        details: damage.details,
+    });
+    if knocked_out {
+        facts.push(Fact::Unconscious(String::from(target_id)));
+    }
+    dealt
+}
+
+// p453 weaknesses add to the damage of their type, resistances reduce it.
+pub fn adjust_damage(target: &Character, damage_type: DamageType, amount: i64) -> i64 {
+    adjust_strike_damage(target, damage_type, amount, false)
+}
+
+/**
+The damage of a Strike, the ones of a ghost touch weapon ignore the resistance to all damage of
+incorporeal creatures. p581
+*/
+pub fn adjust_strike_damage(
+    target: &Character,
+    damage_type: DamageType,
+    amount: i64,
+    ghost_touch: bool,
+) -> i64 {
+    let creature = match &target.creature {
+        Some(c) => c,
+        None => return amount,
+    };
+    let find = |list: &Vec<(DamageType, i64)>| {
+        list.iter()
+            .filter(|(t, _)| *t == damage_type)
+            .map(|(_, v)| *v)
+            .max()
+            .unwrap_or(0)
+    };
+    let mut resistance = find(&creature.resistances);
+    let ignored = ghost_touch && target.traits.contains(&Rule::Incorporeal);
+    if damage_type != DamageType::Force && !ignored {
+        resistance = resistance.max(creature.resist_all);
+    }
+    (amount + find(&creature.weaknesses) - resistance).max(0)
+}
@@ -0,0 +1,202 @@
+use dice::Roll;
+use serde::Deserialize;
+
+use crate::{
+    character::{abilities::Ability, proficiency::Proficiency, Character},
+    item::armor::ArmorCategory,
+    status::StatusType,
+    timeline::get_modifier,
+    utils::{get_armor, get_shield},
+    world::World,
+};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+pub enum Save {
+    Fortitude,
+    Reflex,
+    Will,
+}
+
+/**
+p274 Armor Class: 10 + Dex modifier (up to the armor's Dex cap) + proficiency bonus
++ armor item bonus (with its potency rune) + circumstance bonuses and penalties.
+
+It is returned as a roll of flat bonuses, so the details can be displayed next to the total.
+*/
+pub fn compute_ac(character: &Character, world: &World) -> Roll {
+    let proficiencies = &character.proficiencies;
+    let mut ac = Roll::flat("base", 10);
+    ac = match (&character.creature, get_armor(character, world)) {
+        (Some(creature), _) => Roll::flat("ac", creature.ac),
+        (None, Some(armor)) => {
+            let proficiency = match armor.category {
+                ArmorCategory::Unarmored => proficiencies.unarmored,
+                ArmorCategory::Light => proficiencies.light_armor,
+                ArmorCategory::Medium => proficiencies.medium_armor,
+                ArmorCategory::Heavy => proficiencies.heavy_armor,
+            };
+            ac + Roll::flat("dex", armor.get_dex_bonus(character))
+                + Roll::flat("proficiency", proficiency.bonus(character.level))
+                + Roll::flat("armor", armor.ac_bonus)
+                + Roll::flat("potency", armor.get_potency())
+        }
+        (None, None) => {
+            ac + Roll::flat("dex", get_modifier(character.ability_score.dexterity))
+                + Roll::flat(
+                    "proficiency",
+                    proficiencies.unarmored.bonus(character.level),
+                )
+        }
+    };
+
+    // p277 the shield bonus only applies while the shield is raised.
+    if character.has_status(StatusType::ShieldRaised) {
+        if let Some(shield) = get_shield(character, world) {
+            if !shield.is_broken() {
+                ac = ac + Roll::flat("shield", shield.ac_bonus);
+            }
+        }
+    }
+    if is_flat_footed(character) {
+        ac = ac + Roll::flat("flat-footed", -2);
+    }
+    ac + character.get_ability_status_penalty(Ability::Dexterity)
+}
+
+// p620 flat-footed: -2 circumstance penalty to AC, prone creatures are flat-footed.
+pub fn is_flat_footed(character: &Character) -> bool {
+    character.has_status(StatusType::FlatFooted) || character.has_status(StatusType::Prone)
+}
+
+// p449 saving throw modifier: ability modifier + proficiency + resilient rune.
+pub fn compute_save(character: &Character, save: Save, world: &World) -> Roll {
+    let penalty = character.get_ability_status_penalty(match save {
+        Save::Fortitude => Ability::Constitution,
+        Save::Reflex => Ability::Dexterity,
+        Save::Will => Ability::Wisdom,
+    });
+    if let Some(creature) = &character.creature {
+        let bonus = match save {
+            Save::Fortitude => creature.fortitude,
+            Save::Reflex => creature.reflex,
+            Save::Will => creature.will,
+        };
+        return Roll::flat("save", bonus) + penalty;
+    }
+    let proficiencies = &character.proficiencies;
+    let (tag, score, proficiency): (&str, i64, Proficiency) = match save {
+        Save::Fortitude => (
+            "con",
+            character.ability_score.constitution,
+            proficiencies.fortitude,
+        ),
+        Save::Reflex => (
+            "dex",
+            character.ability_score.dexterity,
+            proficiencies.reflex,
+        ),
+        Save::Will => ("wis", character.ability_score.wisdom, proficiencies.will),
+    };
+    let resilient = get_armor(character, world).map_or(0, |a| a.get_resilient());
+    Roll::flat(tag, get_modifier(score))
+        + Roll::flat("proficiency", proficiency.bonus(character.level))
+        + Roll::flat("resilient", resilient)
+        + penalty
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        character::proficiency::Proficiencies,
+        item::rune::Runes,
+        status::{Duration, StatusEffect},
+        utils::get_speed,
+    };
+
+    fn trained_character() -> Character {
+        let mut c = Character::new(String::from("Valeros"), String::from("heroes"), 20);
+        c.proficiencies = Proficiencies::trained();
+        c
+    }
+
+    #[test]
+    fn unarmored() {
+        let world = World::new();
+        let mut c = trained_character();
+        c.ability_score.dexterity = 16;
+        // 10 + 3 dex + 3 trained at level 1
+        assert_eq!(compute_ac(&c, &world).roll(), 16);
+    }
+
+    #[test]
+    fn dex_cap_and_potency() {
+        let mut world = World::new();
+        let mut c = trained_character();
+        c.ability_score.dexterity = 18;
+        c.loadout.armor = world.spawn_armor(
+            world
+                .catalog
+                .get_armor("full-plate")
+                .unwrap()
+                .with_runes(Runes {
+                    potency: 1,
+                    ..Default::default()
+                })
+                .unwrap(),
+        );
+        // 10 + 0 dex (capped) + 3 trained + 6 armor + 1 potency
+        assert_eq!(compute_ac(&c, &world).roll(), 20);
+    }
+
+    #[test]
+    fn circumstance() {
+        let mut world = World::new();
+        let mut c = trained_character();
+        c.loadout.armor = world.spawn_item("leather").ok();
+        c.loadout.left_hand = world.spawn_item("steel-shield").ok();
+        assert_eq!(compute_ac(&c, &world).roll(), 14);
+        c.add_status(StatusEffect {
+            duration: Duration::StartOfNextTurn,
+            status_type: StatusType::ShieldRaised,
+        });
+        assert_eq!(compute_ac(&c, &world).roll(), 16);
+        c.add_status(StatusEffect {
+            duration: Duration::Round(1),
+            status_type: StatusType::FlatFooted,
+        });
+        assert_eq!(compute_ac(&c, &world).roll(), 14);
+    }
+
+    #[test]
+    fn strength_requirement() {
+        let mut world = World::new();
+        let mut c = trained_character();
+        c.loadout.armor = world.spawn_item("full-plate").ok();
+        assert_eq!(get_speed(&c, &world), 15);
+        assert_eq!(get_armor(&c, &world).unwrap().get_check_penalty(&c), -3);
+        c.ability_score.strength = 18;
+        assert_eq!(get_speed(&c, &world), 20);
+        assert_eq!(get_armor(&c, &world).unwrap().get_check_penalty(&c), 0);
+    }
+
+    #[test]
+    fn resilient() {
+        let mut world = World::new();
+        let mut c = trained_character();
+        c.ability_score.constitution = 14;
+        assert_eq!(compute_save(&c, Save::Fortitude, &world).roll(), 5);
+        c.loadout.armor = world.spawn_armor(
+            world
+                .catalog
+                .get_armor("leather")
+                .unwrap()
+                .with_runes(Runes {
+                    resilient: 2,
+                    ..Default::default()
+                })
+                .unwrap(),
+        );
+        assert_eq!(compute_save(&c, Save::Fortitude, &world).roll(), 7);
+    }
+}
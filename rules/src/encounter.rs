@@ -0,0 +1,447 @@
+use std::{fmt, fs, path::Path};
+
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::{
+    ai::{Personality, Planner},
+    bestiary::{Bestiary, BestiaryError},
+    map::Map,
+    position::Position,
+    timeline::CharacterId,
+    world::World,
+};
+
+// p489 threat levels, from the easiest to the deadliest.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Threat {
+    Trivial,
+    Low,
+    Moderate,
+    Severe,
+    Extreme,
+}
+
+impl Threat {
+    pub const ALL: [Threat; 5] = [
+        Threat::Trivial,
+        Threat::Low,
+        Threat::Moderate,
+        Threat::Severe,
+        Threat::Extreme,
+    ];
+
+    // p489 table 10-1: (XP budget for 4 characters, character adjustment)
+    fn budget_table(&self) -> (i64, i64) {
+        match self {
+            Threat::Trivial => (40, 10),
+            Threat::Low => (60, 15),
+            Threat::Moderate => (80, 20),
+            Threat::Severe => (120, 30),
+            Threat::Extreme => (160, 40),
+        }
+    }
+}
+
+impl fmt::Display for Threat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Threat::Trivial => "trivial",
+            Threat::Low => "low",
+            Threat::Moderate => "moderate",
+            Threat::Severe => "severe",
+            Threat::Extreme => "extreme",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EncounterError {
+    Io(String),
+    Parse(String),
+    Bestiary(BestiaryError),
+    // more than 4 levels above the party, too dangerous to be in the XP table.
+    TooPowerful {
+        key: String,
+        level: i64,
+    },
+    OverBudget {
+        xp: i64,
+        budget: i64,
+        threat: Threat,
+    },
+    NoEncounter(Threat),
+}
+
+impl fmt::Display for EncounterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncounterError::Io(e) => write!(f, "cannot read the encounter: {}", e),
+            EncounterError::Parse(e) => write!(f, "malformed encounter: {}", e),
+            EncounterError::Bestiary(e) => write!(f, "{}", e),
+            EncounterError::TooPowerful { key, level } => write!(
+                f,
+                "\"{}\" is level {}, more than 4 levels above the party",
+                key, level
+            ),
+            EncounterError::OverBudget { xp, budget, threat } => write!(
+                f,
+                "the encounter is worth {} XP, over the {} XP budget of a {} threat",
+                xp, budget, threat
+            ),
+            EncounterError::NoEncounter(threat) => {
+                write!(f, "cannot build a {} encounter from this pool", threat)
+            }
+        }
+    }
+}
+
+impl From<BestiaryError> for EncounterError {
+    fn from(e: BestiaryError) -> Self {
+        EncounterError::Bestiary(e)
+    }
+}
+
+/**
+p489 Building encounters: the party level and size give an XP budget for each threat,
+each creature costs XP depending on its level compared to the party level.
+*/
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct EncounterBudget {
+    pub party_level: i64,
+    pub party_size: i64,
+}
+
+impl EncounterBudget {
+    pub fn new(party_level: i64, party_size: i64) -> Self {
+        EncounterBudget {
+            party_level,
+            party_size,
+        }
+    }
+
+    // p489 each character above or below 4 adjusts the budget.
+    pub fn budget(&self, threat: Threat) -> i64 {
+        let (base, adjustment) = threat.budget_table();
+        base + (self.party_size - 4) * adjustment
+    }
+
+    /**
+    p489 table 10-2: creature XP by level difference with the party.
+    Creatures 5 or more levels below are worth nothing, 5 or more above are off the table.
+    */
+    pub fn creature_xp(&self, creature_level: i64) -> Option<i64> {
+        match creature_level - self.party_level {
+            d if d < -4 => Some(0),
+            -4 => Some(10),
+            -3 => Some(15),
+            -2 => Some(20),
+            -1 => Some(30),
+            0 => Some(40),
+            1 => Some(60),
+            2 => Some(80),
+            3 => Some(120),
+            4 => Some(160),
+            _ => None,
+        }
+    }
+
+    pub fn xp<S: AsRef<str>>(
+        &self,
+        creatures: &[S],
+        bestiary: &Bestiary,
+    ) -> Result<i64, EncounterError> {
+        let mut total = 0;
+        for key in creatures {
+            let level = bestiary.get(key.as_ref())?.level;
+            total += self
+                .creature_xp(level)
+                .ok_or_else(|| EncounterError::TooPowerful {
+                    key: String::from(key.as_ref()),
+                    level,
+                })?;
+        }
+        Ok(total)
+    }
+
+    // the highest threat whose budget the XP reaches, trivial below that.
+    pub fn rate(&self, xp: i64) -> Threat {
+        Threat::ALL
+            .iter()
+            .rev()
+            .find(|t| xp >= self.budget(**t))
+            .copied()
+            .unwrap_or(Threat::Trivial)
+    }
+
+    // checks the creatures fit in the budget of the threat, returns their XP.
+    pub fn validate<S: AsRef<str>>(
+        &self,
+        creatures: &[S],
+        bestiary: &Bestiary,
+        threat: Threat,
+    ) -> Result<i64, EncounterError> {
+        let xp = self.xp(creatures, bestiary)?;
+        let budget = self.budget(threat);
+        if xp > budget {
+            return Err(EncounterError::OverBudget { xp, budget, threat });
+        }
+        Ok(xp)
+    }
+
+    /**
+    Picks creatures from the pool at random until the budget of the threat is spent.
+    A few attempts are made to land exactly on the requested threat.
+    */
+    pub fn random<S: AsRef<str>>(
+        &self,
+        pool: &[S],
+        bestiary: &Bestiary,
+        threat: Threat,
+        rng: &mut impl Rng,
+    ) -> Result<Vec<String>, EncounterError> {
+        let mut candidates = vec![];
+        for key in pool {
+            let level = bestiary.get(key.as_ref())?.level;
+            // creatures worth no XP would never spend the budget.
+            if let Some(xp) = self.creature_xp(level).filter(|xp| *xp > 0) {
+                candidates.push((String::from(key.as_ref()), xp));
+            }
+        }
+        let budget = self.budget(threat);
+        for _attempt in 0..20 {
+            let mut creatures = vec![];
+            let mut remaining = budget;
+            loop {
+                let fitting: Vec<&(String, i64)> = candidates
+                    .iter()
+                    .filter(|(_, xp)| *xp <= remaining)
+                    .collect();
+                if fitting.is_empty() {
+                    break;
+                }
+                let (key, xp) = fitting[rng.gen_range(0..fitting.len())];
+                creatures.push(key.clone());
+                remaining -= xp;
+            }
+            if !creatures.is_empty() && self.rate(budget - remaining) == threat {
+                return Ok(creatures);
+            }
+        }
+        Err(EncounterError::NoEncounter(threat))
+    }
+}
+
+// spawns the creatures side by side, from `origin` going right.
+pub fn spawn_encounter<S: AsRef<str>>(
+    world: &mut World,
+    creatures: &[S],
+    party: &str,
+    origin: Position,
+) -> Result<Vec<CharacterId>, EncounterError> {
+    let mut ids = vec![];
+    for (i, key) in creatures.iter().enumerate() {
+        let position = Position::new(origin.x + i as i64, origin.y);
+        ids.push(world.spawn_creature(key.as_ref(), party, position)?);
+    }
+    Ok(ids)
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UnitDefinition {
+    // key in the bestiary
+    pub creature: String,
+    pub party: String,
+    pub position: Position,
+    // replaces the personality of the stat block
+    #[serde(default)]
+    pub personality: Option<Personality>,
+    // replaces the planner of the stat block, a boss of an encounter can search its turns.
+    #[serde(default)]
+    pub planner: Option<Planner>,
+}
+
+/**
+An encounter as written in a file: the creatures of every party, and the terrain.
+Walls and smoke are rectangles, given by two opposite corners.
+*/
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EncounterDefinition {
+    pub units: Vec<UnitDefinition>,
+    #[serde(default)]
+    pub walls: Vec<(Position, Position)>,
+    #[serde(default)]
+    pub smoke: Vec<(Position, Position)>,
+}
+
+impl EncounterDefinition {
+    pub fn load(path: &Path) -> Result<Self, EncounterError> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| EncounterError::Io(format!("{}: {}", path.display(), e)))?;
+        EncounterDefinition::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Self, EncounterError> {
+        ron::from_str(content).map_err(|e| EncounterError::Parse(e.to_string()))
+    }
+
+    // builds the terrain and spawns the units, in the order of the file.
+    pub fn spawn(&self, world: &mut World) -> Result<Vec<CharacterId>, EncounterError> {
+        let mut map = Map::new();
+        for (from, to) in &self.walls {
+            map = map.add_wall(*from, *to);
+        }
+        for (from, to) in &self.smoke {
+            map = map.add_smoke(*from, *to);
+        }
+        world.map = map;
+        let mut ids = vec![];
+        for unit in &self.units {
+            let id = world.spawn_creature(&unit.creature, &unit.party, unit.position)?;
+            if let Some(personality) = unit.personality {
+                world.get_mut_character(&id).personality = personality;
+            }
+            if let Some(planner) = unit.planner {
+                world.get_mut_character(&id).planner = planner;
+            }
+            ids.push(id);
+        }
+        Ok(ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::mcts::Budget;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn budgets() {
+        let party = EncounterBudget::new(1, 4);
+        let budgets: Vec<i64> = Threat::ALL.iter().map(|t| party.budget(*t)).collect();
+        assert_eq!(budgets, vec![40, 60, 80, 120, 160]);
+        assert_eq!(EncounterBudget::new(1, 5).budget(Threat::Moderate), 100);
+        assert_eq!(EncounterBudget::new(1, 3).budget(Threat::Severe), 90);
+        assert_eq!(EncounterBudget::new(1, 6).budget(Threat::Extreme), 240);
+    }
+
+    #[test]
+    fn creature_xp() {
+        let party = EncounterBudget::new(3, 4);
+        assert_eq!(party.creature_xp(-2), Some(0));
+        assert_eq!(party.creature_xp(-1), Some(10));
+        assert_eq!(party.creature_xp(2), Some(30));
+        assert_eq!(party.creature_xp(3), Some(40));
+        assert_eq!(party.creature_xp(5), Some(80));
+        assert_eq!(party.creature_xp(7), Some(160));
+        assert_eq!(party.creature_xp(8), None);
+    }
+
+    #[test]
+    fn validate() {
+        let bestiary = Bestiary::core();
+        let party = EncounterBudget::new(1, 4);
+        // 2 level 1 (40 each)
+        let xp = party
+            .validate(&["wolf", "kobold-scout"], &bestiary, Threat::Moderate)
+            .unwrap();
+        assert_eq!(xp, 80);
+        assert_eq!(party.rate(xp), Threat::Moderate);
+        // 4 level -1 (20 each) and 1 level 1
+        let creatures = [
+            "kobold-warrior",
+            "kobold-warrior",
+            "goblin-warrior",
+            "goblin-warrior",
+            "wolf",
+        ];
+        assert_eq!(party.xp(&creatures, &bestiary), Ok(120));
+        assert_eq!(
+            party.validate(&creatures, &bestiary, Threat::Moderate),
+            Err(EncounterError::OverBudget {
+                xp: 120,
+                budget: 80,
+                threat: Threat::Moderate
+            })
+        );
+        assert!(matches!(
+            EncounterBudget::new(-4, 4).xp(&["wolf"], &bestiary),
+            Err(EncounterError::TooPowerful { .. })
+        ));
+    }
+
+    #[test]
+    fn random() {
+        let bestiary = Bestiary::core();
+        let party = EncounterBudget::new(1, 4);
+        let pool = ["kobold-warrior", "kobold-scout", "orc-brute", "wolf"];
+        let mut rng = StdRng::seed_from_u64(42);
+        for threat in Threat::ALL.iter() {
+            let creatures = party.random(&pool, &bestiary, *threat, &mut rng).unwrap();
+            let xp = party.validate(&creatures, &bestiary, *threat).unwrap();
+            assert_eq!(party.rate(xp), *threat);
+        }
+
+        let mut world = World::new();
+        let creatures = party
+            .random(&pool, &bestiary, Threat::Low, &mut rng)
+            .unwrap();
+        let ids = spawn_encounter(&mut world, &creatures, "monsters", Position::new(0, 0)).unwrap();
+        assert_eq!(ids.len(), creatures.len());
+    }
+
+    #[test]
+    fn definition() {
+        let definition = EncounterDefinition::parse(
+            r#"(
+                units: [
+                    (creature: "wolf", party: "wolves", position: (x: 0, y: 0),
+                        personality: Some((kill: 2.0))),
+                    (creature: "orc-brute", party: "orcs", position: (x: 4, y: 0),
+                        planner: Some(Search(Iterations(100)))),
+                ],
+                walls: [((x: 2, y: -1), (x: 2, y: 1))],
+            )"#,
+        )
+        .unwrap();
+        let mut world = World::new();
+        let ids = definition.spawn(&mut world).unwrap();
+        assert_eq!(world.get_character(&ids[1]).party, "orcs");
+        assert_eq!(world.get_character(&ids[1]).position, Position::new(4, 0));
+        assert_eq!(world.map.walls.len(), 3);
+        // the stat block personality, unless the encounter gives one
+        assert_eq!(world.get_character(&ids[1]).personality.kill, 8.0);
+        assert_eq!(world.get_character(&ids[0]).personality.kill, 2.0);
+        assert_eq!(world.get_character(&ids[0]).personality.damage, 1.0);
+        assert_eq!(
+            world.get_character(&ids[1]).planner,
+            Planner::Search(Budget::Iterations(100))
+        );
+        assert_eq!(world.get_character(&ids[0]).planner, Planner::Utility);
+
+        let unknown = EncounterDefinition {
+            units: vec![UnitDefinition {
+                creature: String::from("dragon"),
+                party: String::from("dragons"),
+                position: Position::new(0, 0),
+                personality: None,
+                planner: None,
+            }],
+            ..Default::default()
+        };
+        assert_eq!(
+            unknown.spawn(&mut World::new()),
+            Err(EncounterError::Bestiary(BestiaryError::UnknownCreature(
+                String::from("dragon")
+            )))
+        );
+        assert!(matches!(
+            EncounterDefinition::parse("(units: [], lair: true)"),
+            Err(EncounterError::Parse(_))
+        ));
+    }
+}
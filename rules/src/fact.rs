@@ -1,30 +1,123 @@
-use crate::timeline::CharacterId;
+mod text;
 
-// Facts are things that just happened in the world.
-// They serve as the platform to communicate between the rule engine and the view
-// Mostly for animation purposes, so they are less detailed than the info log for now.
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
+use crate::{
+    check::DegreeOfSuccess, item::weapon::DamageType, position::Position, status::StatusType,
+    timeline::CharacterId,
+};
+
+pub use text::{TextFormatter, Verbosity};
+
+/**
+Facts are things that just happened in the world.
+They serve as the platform to communicate between the rule engine and the view: the app
+animates them, the text formatter turns them into a log.
+*/
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Fact {
+    // free text, for what has no event of its own.
     InfoFact(String),
+    RoundStart(i64),
+    TurnStart(CharacterId),
+    TurnEnd(CharacterId),
+    EncounterOver,
+    // an activity about to be resolved, with the actions it costs.
+    Activity {
+        character_id: CharacterId,
+        name: String,
+        cost: i64,
+    },
+    Reaction {
+        character_id: CharacterId,
+        name: String,
+        trigger_id: CharacterId,
+    },
+    // every square entered, in order.
     Move {
         character_id: CharacterId,
-        x: u32,
-        y: u32,
+        path: Vec<Position>,
     },
     CastSpell {
         character_id: CharacterId,
-        spell_id: u32,
+        spell: String,
+        rank: i64,
     },
+    // p278 an attack roll against the AC, with a weapon or a spell.
     Attack {
         character_id: CharacterId,
         target_id: CharacterId,
-        attack_type: u32,
-        dmg: u32,
+        weapon: String,
+        total: i64,
+        details: String,
+        ac: i64,
+        degree: DegreeOfSuccess,
+    },
+    // p445 any other check against a DC: skill actions, saves and flat checks.
+    Check {
+        character_id: CharacterId,
+        check: String,
+        total: i64,
+        dc: i64,
+        degree: DegreeOfSuccess,
+    },
+    Damage {
+        source_id: CharacterId,
+        target_id: CharacterId,
+        amount: i64,
+        damage_type: Option<DamageType>,
+        details: String,
+    },
+    Heal {
+        target_id: CharacterId,
+        amount: i64,
+    },
+    TemporaryHp {
+        target_id: CharacterId,
+        amount: i64,
+    },
+    StatusApplied {
+        character_id: CharacterId,
+        status: StatusType,
+    },
+    StatusRemoved {
+        character_id: CharacterId,
+        status: StatusType,
     },
     Unconscious(CharacterId),
+    Conscious(CharacterId),
+    // an emanation that grows when sustained.
+    Sustain {
+        character_id: CharacterId,
+        spell: String,
+        radius: i64,
+    },
+    // p266 the damage taken by the shield, after its hardness.
+    ShieldBlock {
+        character_id: CharacterId,
+        shield: String,
+        damage: i64,
+        broken: bool,
+    },
 }
-#[derive(Debug)]
+
+impl Fact {
+    // the facts that show the results of dice, they cannot be taken back.
+    pub fn reveals_roll(&self) -> bool {
+        matches!(
+            self,
+            Fact::Attack { .. }
+                | Fact::Check { .. }
+                | Fact::Damage { .. }
+                | Fact::Heal { .. }
+                | Fact::TemporaryHp { .. }
+        )
+    }
+}
+
+// The facts of a tick, in the order they happened.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct Facts {
     facts: Vec<Fact>,
 }
@@ -34,7 +127,45 @@ impl Facts {
         self.facts.push(Fact::InfoFact(String::from(info)));
     }
 
+    pub fn push(&mut self, fact: Fact) {
+        self.facts.push(fact);
+    }
+
     pub fn new() -> Self {
         Self { facts: vec![] }
     }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Fact> {
+        self.facts.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.facts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.facts.is_empty()
+    }
+
+    pub fn extend(&mut self, other: Facts) {
+        self.facts.extend(other.facts);
+    }
+}
+
+impl IntoIterator for Facts {
+    type Item = Fact;
+    type IntoIter = std::vec::IntoIter<Fact>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.facts.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Facts {
+    type Item = &'a Fact;
+    type IntoIter = std::slice::Iter<'a, Fact>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.facts.iter()
+    }
 }
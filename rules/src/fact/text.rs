@@ -0,0 +1,304 @@
+use crate::{check::DegreeOfSuccess, item::weapon::DamageType, world::World};
+
+use super::{Fact, Facts};
+
+fn get_damage_verb(damage_type: DamageType) -> &'static str {
+    match damage_type {
+        DamageType::Bludgeoning => "was bludgeoned for",
+        DamageType::Piercing => "was pierced for",
+        DamageType::Slashing => "was slashed for",
+        DamageType::Fire => "was burned for",
+        DamageType::Cold => "was frozen for",
+        DamageType::Electricity => "was shocked for",
+        DamageType::Good => "was smitten for",
+        DamageType::Force => "was struck for",
+    }
+}
+
+// How much of the encounter the log tells.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    // who did what, and who went down.
+    Summary,
+    // every roll against its AC or DC, movement and conditions.
+    Normal,
+    // the math behind the attack and damage rolls.
+    Full,
+}
+
+// The lowest verbosity at which a fact is told.
+fn get_verbosity(fact: &Fact) -> Verbosity {
+    match fact {
+        Fact::RoundStart(_)
+        | Fact::EncounterOver
+        | Fact::Activity { .. }
+        | Fact::Reaction { .. }
+        | Fact::Damage { .. }
+        | Fact::Heal { .. }
+        | Fact::Unconscious(_)
+        | Fact::Conscious(_) => Verbosity::Summary,
+        _ => Verbosity::Normal,
+    }
+}
+
+/**
+Renders the facts as the lines of a combat log. The characters are named after the world,
+characters are never removed from it so every fact of the encounter can be rendered.
+*/
+pub struct TextFormatter<'a> {
+    world: &'a World,
+    verbosity: Verbosity,
+}
+
+impl<'a> TextFormatter<'a> {
+    pub fn new(world: &'a World) -> Self {
+        Self {
+            world,
+            verbosity: Verbosity::Normal,
+        }
+    }
+
+    pub fn verbosity(mut self, verbosity: Verbosity) -> Self {
+        self.verbosity = verbosity;
+        self
+    }
+
+    fn name(&self, id: &str) -> &'a str {
+        match self.world.characters.get(id) {
+            Some(character) => &character.name,
+            None => "someone",
+        }
+    }
+
+    // The log line of the fact, none when it is not told at this verbosity.
+    pub fn format(&self, fact: &Fact) -> Option<String> {
+        if get_verbosity(fact) > self.verbosity {
+            return None;
+        }
+        let full = self.verbosity == Verbosity::Full;
+        let line = match fact {
+            Fact::InfoFact(info) => info.clone(),
+            Fact::RoundStart(round) => format!("Start of Round {}", round),
+            Fact::TurnStart(id) => format!("{}'s turn", self.name(id)),
+            Fact::TurnEnd(id) => format!("End of {}'s turn", self.name(id)),
+            Fact::EncounterOver => String::from("The encounter is over"),
+            Fact::Activity {
+                character_id,
+                name,
+                cost,
+            } => {
+                let cost = match cost {
+                    0 => String::from("free action"),
+                    1 => String::from("1 action"),
+                    n => format!("{} actions", n),
+                };
+                format!("{} uses {} ({})", self.name(character_id), name, cost)
+            }
+            Fact::Reaction {
+                character_id,
+                name,
+                trigger_id,
+            } => format!(
+                "\t{} reacts to {} with {}",
+                self.name(character_id),
+                self.name(trigger_id),
+                name
+            ),
+            Fact::Move { character_id, path } => match path.last() {
+                Some(p) => format!("\t{} moves to ({}, {})", self.name(character_id), p.x, p.y),
+                None => format!("\t{} does not move", self.name(character_id)),
+            },
+            Fact::CastSpell {
+                character_id,
+                spell,
+                rank,
+            } => format!(
+                "\t{} casts {} at rank {}",
+                self.name(character_id),
+                spell,
+                rank
+            ),
+            Fact::Attack {
+                character_id,
+                target_id,
+                weapon,
+                total,
+                details,
+                ac,
+                degree,
+            } => {
+                let verb = match degree {
+                    DegreeOfSuccess::CriticalSuccess => "critically hits",
+                    DegreeOfSuccess::Success => "hits",
+                    DegreeOfSuccess::Failure => "misses",
+                    DegreeOfSuccess::CriticalFailure => "critically misses",
+                };
+                let roll = if full && !details.is_empty() {
+                    format!("{} = {}", details, total)
+                } else {
+                    total.to_string()
+                };
+                format!(
+                    "\t{} {} {} with {} ({} vs {} AC)",
+                    self.name(character_id),
+                    verb,
+                    self.name(target_id),
+                    weapon,
+                    roll,
+                    ac
+                )
+            }
+            Fact::Check {
+                character_id,
+                check,
+                total,
+                dc,
+                degree,
+            } => format!(
+                "\t{} {} ({} vs DC {}): {:?}",
+                self.name(character_id),
+                check,
+                total,
+                dc,
+                degree
+            ),
+            Fact::Damage {
+                target_id,
+                amount,
+                damage_type,
+                details,
+                ..
+            } => {
+                let verb = damage_type.map_or("takes", get_damage_verb);
+                if !full || details.is_empty() {
+                    format!("\t{} {} {} damage", self.name(target_id), verb, amount)
+                } else {
+                    format!(
+                        "\t{} {} {} damage ({})",
+                        self.name(target_id),
+                        verb,
+                        amount,
+                        details
+                    )
+                }
+            }
+            Fact::Heal { target_id, amount } => {
+                format!("\t{} regains {} HP", self.name(target_id), amount)
+            }
+            Fact::TemporaryHp { target_id, amount } => {
+                format!("\t{} gains {} temporary HP", self.name(target_id), amount)
+            }
+            Fact::StatusApplied {
+                character_id,
+                status,
+            } => format!("\t{} is {:?}", self.name(character_id), status),
+            Fact::StatusRemoved {
+                character_id,
+                status,
+            } => format!("\t{} is no longer {:?}", self.name(character_id), status),
+            Fact::Unconscious(id) => format!("\t{} is unconscious!", self.name(id)),
+            Fact::Conscious(id) => format!("\t{} regains consciousness", self.name(id)),
+            Fact::Sustain {
+                character_id,
+                spell,
+                radius,
+            } => format!(
+                "\t{} sustains {}, the emanation grows to {} feet",
+                self.name(character_id),
+                spell,
+                radius
+            ),
+            Fact::ShieldBlock {
+                character_id,
+                shield,
+                damage,
+                broken,
+            } => format!(
+                "\t{} blocks with {}, which takes {} damage{}",
+                self.name(character_id),
+                shield,
+                damage,
+                if *broken { " and breaks" } else { "" }
+            ),
+        };
+        Some(line)
+    }
+
+    pub fn format_all(&self, facts: &Facts) -> Vec<String> {
+        facts.iter().filter_map(|fact| self.format(fact)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{character::Character, status::StatusType};
+
+    #[test]
+    fn format() {
+        let mut world = World::new();
+        let valeros = Character::new(String::from("Valeros"), String::from("heroes"), 20);
+        let goblin = Character::new(String::from("Goblin"), String::from("goblins"), 6);
+        let (valeros_id, goblin_id) = (valeros.id.clone(), goblin.id.clone());
+        world.characters.insert(valeros_id.clone(), valeros);
+        world.characters.insert(goblin_id.clone(), goblin);
+
+        let mut facts = Facts::new();
+        facts.push(Fact::Activity {
+            character_id: valeros_id.clone(),
+            name: String::from("Strike (Longsword)"),
+            cost: 1,
+        });
+        facts.push(Fact::Attack {
+            character_id: valeros_id.clone(),
+            target_id: goblin_id.clone(),
+            weapon: String::from("Longsword"),
+            total: 21,
+            details: String::from("1d20 + 3"),
+            ac: 16,
+            degree: DegreeOfSuccess::Success,
+        });
+        facts.push(Fact::Damage {
+            source_id: valeros_id.clone(),
+            target_id: goblin_id.clone(),
+            amount: 9,
+            damage_type: Some(DamageType::Slashing),
+            details: String::from("1d8 + 3"),
+        });
+        facts.push(Fact::Unconscious(goblin_id.clone()));
+        facts.push(Fact::StatusApplied {
+            character_id: valeros_id,
+            status: StatusType::Prone,
+        });
+        let format = |verbosity| {
+            TextFormatter::new(&world)
+                .verbosity(verbosity)
+                .format_all(&facts)
+        };
+        assert_eq!(
+            format(Verbosity::Full),
+            vec![
+                "Valeros uses Strike (Longsword) (1 action)",
+                "\tValeros hits Goblin with Longsword (1d20 + 3 = 21 vs 16 AC)",
+                "\tGoblin was slashed for 9 damage (1d8 + 3)",
+                "\tGoblin is unconscious!",
+                "\tValeros is Prone",
+            ]
+        );
+        assert_eq!(
+            format(Verbosity::Normal)[1..3],
+            [
+                "\tValeros hits Goblin with Longsword (21 vs 16 AC)",
+                "\tGoblin was slashed for 9 damage",
+            ]
+        );
+        assert_eq!(
+            format(Verbosity::Summary),
+            vec![
+                "Valeros uses Strike (Longsword) (1 action)",
+                "\tGoblin was slashed for 9 damage",
+                "\tGoblin is unconscious!",
+            ]
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap},
+};
+
+use crate::{character::Character, position::Position, utils::get_active_weapon, world::World};
+
+/**
+p476 the line between the centers of both squares passes through opposite sides, or opposite
+corners, of the target's square.
+*/
+fn is_opposite(a: &Position, b: &Position, target: &Position) -> bool {
+    let (ax, ay) = ((a.x - target.x) as f64, (a.y - target.y) as f64);
+    let (bx, by) = ((b.x - target.x) as f64, (b.y - target.y) as f64);
+    // where the line crosses the sides, it has to be within the square
+    let crosses = |ax: f64, ay: f64, bx: f64, by: f64| {
+        if ax.min(bx) > -1.0 || ax.max(bx) < 1.0 {
+            return false;
+        }
+        let at = |x: f64| ay + (by - ay) * (x - ax) / (bx - ax);
+        at(-0.5).abs() <= 0.5 && at(0.5).abs() <= 0.5
+    };
+    crosses(ax, ay, bx, by) || crosses(ay, ax, by, bx)
+}
+
+// the creature can act and its melee weapon reaches the target from `position`.
+fn threatens(
+    character: &Character,
+    position: &Position,
+    target: &Character,
+    world: &World,
+) -> bool {
+    let weapon = get_active_weapon(character, world);
+    character.hp > 0
+        && !weapon.is_ranged
+        && position.is_within_reach(&target.position, weapon.get_reach())
+}
+
+/**
+p476 flanking: the attacker and one of its allies both threaten the target, from opposite sides.
+The target is then flat-footed to the melee attacks of the flanking creatures.
+*/
+pub fn is_flanking(attacker: &Character, target: &Character, world: &World) -> bool {
+    is_flanking_from(attacker, &attacker.position, target, world)
+}
+
+fn is_flanking_from(
+    attacker: &Character,
+    position: &Position,
+    target: &Character,
+    world: &World,
+) -> bool {
+    threatens(attacker, position, target, world)
+        && world.get_characters().iter().any(|ally| {
+            ally.id != attacker.id
+                && ally.party == attacker.party
+                && threatens(ally, &ally.position, target, world)
+                && is_opposite(position, &ally.position, &target.position)
+        })
+}
+
+/**
+The closest free square where the character would flank the target, reachable within `distance`
+feet without going through walls or creatures.
+*/
+pub fn find_flanking_position(
+    character: &Character,
+    target: &Character,
+    distance: i64,
+    world: &World,
+) -> Option<Position> {
+    find_flanking_path(character, target, distance, world).and_then(|path| path.last().copied())
+}
+
+/**
+The squares entered on the way to the flanking position, the starting square excluded.
+The path costs at most `distance` feet, every second diagonal counting as 10 feet.
+*/
+pub fn find_flanking_path(
+    character: &Character,
+    target: &Character,
+    distance: i64,
+    world: &World,
+) -> Option<Vec<Position>> {
+    // a square, and whether an odd number of diagonals was taken to reach it
+    let start = (character.position, false);
+    // the cost of every reachable step, with the step it was reached from
+    let mut reachable = BTreeMap::from([(start, (0, start))]);
+    let mut queue = BinaryHeap::from([Reverse((0, start))]);
+    while let Some(Reverse((cost, step))) = queue.pop() {
+        if reachable[&step].0 < cost {
+            continue;
+        }
+        let (position, odd) = step;
+        for next in position.neighbours() {
+            let diagonal = next.x != position.x && next.y != position.y;
+            let next_cost = cost + if diagonal && odd { 10 } else { 5 };
+            let next_step = (next, odd != diagonal);
+            if next_cost <= distance
+                && !world.is_occupied(&next)
+                && reachable
+                    .get(&next_step)
+                    .is_none_or(|(known, _)| next_cost < *known)
+            {
+                reachable.insert(next_step, (next_cost, step));
+                queue.push(Reverse((next_cost, next_step)));
+            }
+        }
+    }
+    let (mut step, _) = reachable
+        .iter()
+        .filter(|((p, _), _)| is_flanking_from(character, p, target, world))
+        .min_by_key(|((p, _), (cost, _))| (*cost, *p))?;
+    let mut path = vec![];
+    while *step != start {
+        path.push(step.0);
+        step = &reachable[step].1;
+    }
+    path.reverse();
+    Some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::Map;
+
+    #[test]
+    fn flanking() {
+        let target = Position::new(0, 0);
+        assert!(is_opposite(
+            &Position::new(-1, 0),
+            &Position::new(1, 0),
+            &target
+        ));
+        assert!(is_opposite(
+            &Position::new(-1, -1),
+            &Position::new(1, 1),
+            &target
+        ));
+        assert!(is_opposite(
+            &Position::new(0, 2),
+            &Position::new(0, -1),
+            &target
+        ));
+        assert!(!is_opposite(
+            &Position::new(-1, 0),
+            &Position::new(1, 1),
+            &target
+        ));
+        assert!(!is_opposite(
+            &Position::new(-1, -1),
+            &Position::new(1, 0),
+            &target
+        ));
+        assert!(!is_opposite(
+            &Position::new(-1, 0),
+            &Position::new(0, 1),
+            &target
+        ));
+
+        let mut world = World::new();
+        let goblin = world.add_character_at("Goblin", "goblins", 10, target);
+        let fighter = world.add_character_at("Valeros", "heroes", 10, Position::new(-1, -1));
+        let rogue = world.add_character_at("Merisiel", "heroes", 10, Position::new(3, 1));
+        let get = |id: &str| world.get_character(id);
+        assert!(!is_flanking(get(&fighter), get(&goblin), &world));
+        // (1, 1) is the only square opposite to the fighter within reach
+        assert_eq!(
+            find_flanking_position(get(&rogue), get(&goblin), 25, &world),
+            Some(Position::new(1, 1))
+        );
+        assert_eq!(
+            find_flanking_position(get(&rogue), get(&goblin), 5, &world),
+            None
+        );
+
+        // around a wall, the path to (1, 1) costs 25 feet
+        world.map = Map::new().add_wall(Position::new(2, 0), Position::new(2, 2));
+        let get = |id: &str| world.get_character(id);
+        assert_eq!(
+            find_flanking_position(get(&rogue), get(&goblin), 20, &world),
+            None
+        );
+        let path = find_flanking_path(get(&rogue), get(&goblin), 25, &world).unwrap();
+        assert_eq!(path.len(), 4);
+        assert_eq!(path.last(), Some(&Position::new(1, 1)));
+        world.map = Map::new();
+
+        world.get_mut_character(&rogue).position = Position::new(1, 1);
+        let get = |id: &str| world.get_character(id);
+        assert!(is_flanking(get(&fighter), get(&goblin), &world));
+        assert!(is_flanking(get(&rogue), get(&goblin), &world));
+        assert!(!is_flanking(get(&goblin), get(&fighter), &world));
+    }
+}
@@ -0,0 +1,203 @@
+use dice::Roll;
+
+use crate::{
+    character::{proficiency::Proficiency, skill::Skill, Character},
+    check::{get_chances, DegreeOfSuccess},
+    damage::{apply_damage, Damage},
+    fact::{self, Fact},
+    status::{apply_status, Duration, StatusEffect, StatusType},
+    world::World,
+};
+
+// p498 an hour and a day, in rounds of 6 seconds.
+const HOUR: i64 = 600;
+const DAY: i64 = 24 * HOUR;
+
+/**
+Every source of healing goes through here, so that the creatures brought above 0 HP
+are reported as conscious again. Returns the HP regained.
+*/
+pub fn apply_healing(
+    target_id: &str,
+    amount: i64,
+    world: &mut World,
+    facts: &mut fact::Facts,
+) -> i64 {
+    let target = world.get_mut_character(target_id);
+    let unconscious = target.hp <= 0;
+    let healed = target.heal(amount);
+    let conscious = unconscious && target.hp > 0;
+    facts.push(Fact::Heal {
+        target_id: String::from(target_id),
+        amount: healed,
+    });
+    if conscious {
+        facts.push(Fact::Conscious(String::from(target_id)));
+    }
+    healed
+}
+
+// creatures are trained in the skills of their stat block.
+fn get_medicine(character: &Character) -> Proficiency {
+    match &character.creature {
+        Some(_) if character.is_trained(Skill::Medicine) => Proficiency::Trained,
+        Some(_) => Proficiency::Untrained,
+        None => character.proficiencies.get_skill(Skill::Medicine),
+    }
+}
+
+/**
+p249 the DC of Treat Wounds and the HP added on a success, the healer uses the highest DC
+of its proficiency rank. Untrained healers cannot treat wounds.
+*/
+pub fn get_treatment(proficiency: Proficiency) -> Option<(i64, i64)> {
+    match proficiency {
+        Proficiency::Untrained => None,
+        Proficiency::Trained => Some((15, 0)),
+        Proficiency::Expert => Some((20, 10)),
+        Proficiency::Master => Some((30, 30)),
+        Proficiency::Legendary => Some((40, 50)),
+    }
+}
+
+/**
+A Medicine check to heal a patient, shared by Treat Wounds and Battle Medicine: 2d8 HP on a
+success, 4d8 on a critical success and 1d8 damage on a critical failure. The patient is then
+immune to the same treatment for a while.
+Returns None when the healer is untrained or the patient is immune.
+*/
+pub(crate) fn treat(
+    healer: &Character,
+    patient_id: &str,
+    immunity: StatusEffect,
+    world: &mut World,
+    facts: &mut fact::Facts,
+) -> Option<DegreeOfSuccess> {
+    let (dc, bonus) = get_treatment(get_medicine(healer))?;
+    let patient = world.get_character(patient_id);
+    if patient.has_status(immunity.status_type) {
+        return None;
+    }
+    let patient_name = patient.name.clone();
+    let natural = Roll::d("", 1, 20).roll();
+    let total = natural + healer.get_skill_modifier(Skill::Medicine);
+    let degree = DegreeOfSuccess::from_check(total, natural, dc);
+    facts.push(Fact::Check {
+        character_id: healer.id.clone(),
+        check: format!("treats {}", patient_name),
+        total,
+        dc,
+        degree,
+    });
+    let healing = match degree {
+        DegreeOfSuccess::CriticalSuccess => Roll::d("heal", 4, 8) + Roll::flat("bonus", bonus),
+        DegreeOfSuccess::Success => Roll::d("heal", 2, 8) + Roll::flat("bonus", bonus),
+        DegreeOfSuccess::Failure => Roll::flat("heal", 0),
+        DegreeOfSuccess::CriticalFailure => {
+            let mut damage = Roll::d("damage", 1, 8);
+            let amount = damage.resolve();
+            let damage = Damage::new(amount).details(&damage.to_string());
+            apply_damage(&healer.id, patient_id, damage, world, facts);
+            Roll::flat("heal", 0)
+        }
+    };
+    let amount = healing.roll();
+    if amount > 0 {
+        apply_healing(patient_id, amount, world, facts);
+    }
+    apply_status(patient_id, immunity, world, facts);
+    Some(degree)
+}
+
+// the HP a treatment is expected to restore, less the damage of a critical failure.
+pub(crate) fn expect_treatment(healer: &Character) -> f64 {
+    let (dc, bonus) = match get_treatment(get_medicine(healer)) {
+        Some(treatment) => treatment,
+        None => return 0.0,
+    };
+    let chances = get_chances(healer.get_skill_modifier(Skill::Medicine), dc);
+    let critical = (Roll::d("heal", 4, 8) + Roll::flat("bonus", bonus)).average();
+    let success = (Roll::d("heal", 2, 8) + Roll::flat("bonus", bonus)).average();
+    chances[DegreeOfSuccess::CriticalSuccess as usize] * critical
+        + chances[DegreeOfSuccess::Success as usize] * success
+        - chances[DegreeOfSuccess::CriticalFailure as usize] * Roll::d("damage", 1, 8).average()
+}
+
+/**
+p249 Treat Wounds, the 10-minute exploration activity, outside of encounters.
+The patient is immune to it for an hour.
+*/
+pub fn treat_wounds(
+    healer_id: &str,
+    patient_id: &str,
+    world: &mut World,
+    facts: &mut fact::Facts,
+) -> Option<DegreeOfSuccess> {
+    let healer = world.get_character(healer_id).clone();
+    let immunity = StatusEffect {
+        status_type: StatusType::TreatWoundsImmunity,
+        duration: Duration::Round(HOUR),
+    };
+    treat(&healer, patient_id, immunity, world, facts)
+}
+
+// p258 Battle Medicine, the patient is immune to it for a day.
+pub(crate) fn battle_medicine(
+    healer: &Character,
+    patient_id: &str,
+    world: &mut World,
+    facts: &mut fact::Facts,
+) -> Option<DegreeOfSuccess> {
+    let immunity = StatusEffect {
+        status_type: StatusType::BattleMedicineImmunity,
+        duration: Duration::Round(DAY),
+    };
+    treat(healer, patient_id, immunity, world, facts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn treatment() {
+        let mut world = World::new();
+        let mut healer = Character::new(String::from("Kyra"), String::from("heroes"), 20);
+        let healer_id = healer.id.clone();
+        world.characters.insert(healer_id.clone(), healer.clone());
+        let mut patient = Character::new(String::from("Valeros"), String::from("heroes"), 30);
+        patient.hp = -2;
+        let patient_id = patient.id.clone();
+        world.characters.insert(patient_id.clone(), patient);
+        let mut facts = fact::Facts::new();
+
+        // untrained
+        assert_eq!(
+            treat_wounds(&healer_id, &patient_id, &mut world, &mut facts),
+            None
+        );
+        healer
+            .proficiencies
+            .skills
+            .insert(Skill::Medicine, Proficiency::Trained);
+        world.characters.insert(healer_id.clone(), healer);
+        // 18 + 3 vs DC 15, healing starts from 0 HP
+        assert_eq!(
+            treat_wounds(&healer_id, &patient_id, &mut world, &mut facts),
+            Some(DegreeOfSuccess::Success)
+        );
+        assert_eq!(world.get_character(&patient_id).hp, 2 * 6);
+        // immune for an hour
+        assert_eq!(
+            treat_wounds(&healer_id, &patient_id, &mut world, &mut facts),
+            None
+        );
+
+        // temporary HP are lost first
+        let patient = world.get_mut_character(&patient_id);
+        patient.add_temp_hp(5);
+        patient.add_temp_hp(3);
+        patient.sub_hp(7);
+        assert_eq!((patient.hp, patient.temp_hp), (10, 0));
+    }
+}
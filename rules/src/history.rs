@@ -0,0 +1,211 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{replay::Decision, world::WorldSnapshot, GameState, Turn};
+
+// Rules a table agrees on, that the Core Rulebook does not cover.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HouseRules {
+    // take back activities even after their dice were rolled, like an attack that missed.
+    pub undo_after_rolls: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum UndoError {
+    NothingToUndo,
+    NothingToRedo,
+    // the results of the dice are known, taking the activity back would be cheating.
+    RollsRevealed,
+}
+
+impl fmt::Display for UndoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UndoError::NothingToUndo => write!(f, "nothing to undo in this turn"),
+            UndoError::NothingToRedo => write!(f, "nothing to redo"),
+            UndoError::RollsRevealed => write!(f, "the dice have been rolled"),
+        }
+    }
+}
+
+/**
+The state of a game before an activity, to take the activity back.
+The dice are rewound too: taking the same activity again rolls the same results.
+*/
+#[derive(Clone)]
+pub struct Snapshot {
+    world: WorldSnapshot,
+    turn: Option<Turn>,
+    // the decisions are only ever added, the snapshot keeps how many there were.
+    decisions: usize,
+    dice: Option<u64>,
+}
+
+#[derive(Clone)]
+struct Undo {
+    before: Snapshot,
+    revealed: bool,
+}
+
+#[derive(Clone)]
+struct Redo {
+    after: Snapshot,
+    decisions: Vec<Decision>,
+    revealed: bool,
+}
+
+// The activities of the current turn, the ones taken back can be played again.
+#[derive(Clone, Default)]
+pub(crate) struct History {
+    undo: Vec<Undo>,
+    redo: Vec<Redo>,
+}
+
+impl History {
+    // a new activity, the ones taken back are lost.
+    pub(crate) fn push(&mut self, before: Snapshot, revealed: bool) {
+        self.undo.push(Undo { before, revealed });
+        self.redo.clear();
+    }
+}
+
+impl GameState {
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            world: self.world.snapshot(),
+            turn: self.turn.clone(),
+            decisions: self.decisions.len(),
+            dice: dice::get_state(),
+        }
+    }
+
+    /**
+    Goes back to a snapshot taken earlier in the same game, with the dice of the current thread.
+    The decisions planned by the players are kept, they are theirs to change.
+    */
+    pub fn restore(&mut self, snapshot: Snapshot) {
+        self.world.restore(snapshot.world);
+        self.turn = snapshot.turn;
+        self.decisions.truncate(snapshot.decisions);
+        dice::set_state(snapshot.dice);
+    }
+
+    pub fn can_undo(&self) -> Result<(), UndoError> {
+        match self.history.undo.last() {
+            None => Err(UndoError::NothingToUndo),
+            Some(undo) if undo.revealed && !self.house_rules.undo_after_rolls => {
+                Err(UndoError::RollsRevealed)
+            }
+            Some(_) => Ok(()),
+        }
+    }
+
+    // takes back the last activity of the current turn.
+    pub fn undo(&mut self) -> Result<(), UndoError> {
+        self.can_undo()?;
+        let undo = self.history.undo.pop().ok_or(UndoError::NothingToUndo)?;
+        let after = self.snapshot();
+        let decisions = self.decisions.split_off(undo.before.decisions);
+        self.history.redo.push(Redo {
+            after,
+            decisions,
+            revealed: undo.revealed,
+        });
+        self.restore(undo.before);
+        Ok(())
+    }
+
+    // plays again the last activity taken back, as it was.
+    pub fn redo(&mut self) -> Result<(), UndoError> {
+        let redo = self.history.redo.pop().ok_or(UndoError::NothingToRedo)?;
+        self.history.undo.push(Undo {
+            before: self.snapshot(),
+            revealed: redo.revealed,
+        });
+        self.decisions.extend(redo.decisions);
+        self.restore(redo.after);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fact::{Fact, Facts},
+        position::Position,
+        world::World,
+    };
+
+    fn duel(distance: i64) -> (GameState, String) {
+        let (world, _, _) =
+            World::orc_duel("kobold-warrior", "kobolds", Position::new(0, distance));
+        let mut game_state = GameState::from_world(world);
+        let id = match game_state.step().iter().next() {
+            Some(Fact::TurnStart(id)) => id.clone(),
+            _ => panic!("the encounter starts with a turn"),
+        };
+        (game_state, id)
+    }
+
+    #[test]
+    fn undo_redo() {
+        let (mut game_state, id) = duel(12);
+        let stride = game_state
+            .get_activities(&id)
+            .iter()
+            .position(|name| name == "Stride");
+        game_state.decide(Decision {
+            character_id: id.clone(),
+            activity: stride,
+        });
+        let start = game_state.get_world().get_character(&id).position;
+        let facts = game_state.step();
+        assert!(matches!(facts.iter().nth(1), Some(Fact::Move { .. })));
+        let moved = game_state.get_world().get_character(&id).position;
+        assert_ne!(moved, start);
+
+        assert_eq!(game_state.undo(), Ok(()));
+        assert_eq!(game_state.get_world().get_character(&id).position, start);
+        assert!(game_state.get_decisions().is_empty());
+        assert_eq!(game_state.undo(), Err(UndoError::NothingToUndo));
+        assert_eq!(game_state.redo(), Ok(()));
+        assert_eq!(game_state.get_world().get_character(&id).position, moved);
+        assert_eq!(game_state.get_decisions().len(), 1);
+        assert_eq!(game_state.redo(), Err(UndoError::NothingToRedo));
+
+        // once the next turn starts, this one cannot be taken back.
+        let ends_turn = |facts: Facts| facts.iter().any(|f| matches!(f, Fact::TurnEnd(_)));
+        while !ends_turn(game_state.step()) {}
+        assert_ne!(game_state.can_undo(), Err(UndoError::NothingToUndo));
+        game_state.step();
+        assert_eq!(game_state.undo(), Err(UndoError::NothingToUndo));
+    }
+
+    #[test]
+    fn rolls_revealed() {
+        dice::seed(2);
+        let (mut game_state, _) = duel(1);
+        let facts = game_state.step();
+        assert!(facts.iter().any(|f| matches!(f, Fact::Attack { .. })));
+        assert_eq!(game_state.undo(), Err(UndoError::RollsRevealed));
+
+        let mut house_game = game_state.clone().house_rules(HouseRules {
+            undo_after_rolls: true,
+        });
+        assert_eq!(house_game.undo(), Ok(()));
+        assert_eq!(house_game.get_decisions().len(), 0);
+        // the dice are rewound, the same strike gets the same results.
+        assert_eq!(house_game.step(), facts);
+        let hps = |g: &GameState| {
+            g.get_world()
+                .get_characters()
+                .iter()
+                .map(|c| c.hp)
+                .collect::<Vec<i64>>()
+        };
+        assert_eq!(hps(&house_game), hps(&game_state));
+        assert_eq!(house_game.get_decisions(), game_state.get_decisions());
+    }
+}
@@ -0,0 +1,435 @@
+use std::fmt;
+
+use crate::{
+    character::{abilities::Ability, Character},
+    item::{consumable::ConsumableItem, AnyItem, Bulk, ItemId, Loadout},
+    status::{Duration, StatusEffect, StatusType},
+    world::World,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InventoryError {
+    UnknownItem(ItemId),
+    NotCarried(ItemId),
+    NotHeld(ItemId),
+    NoFreeHand(ItemId),
+    TwoHanded(ItemId),
+    TooHeavy { item: ItemId, maximum: i64 },
+}
+
+impl fmt::Display for InventoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InventoryError::UnknownItem(id) => write!(f, "no item \"{}\" in the world", id),
+            InventoryError::NotCarried(id) => write!(f, "\"{}\" is not in the inventory", id),
+            InventoryError::NotHeld(id) => write!(f, "\"{}\" is not held", id),
+            InventoryError::NoFreeHand(id) => write!(f, "no free hand to draw \"{}\"", id),
+            InventoryError::TwoHanded(id) => write!(f, "\"{}\" must be held in both hands", id),
+            InventoryError::TooHeavy { item, maximum } => write!(
+                f,
+                "cannot carry \"{}\", it would be over the maximum of {} Bulk",
+                item, maximum
+            ),
+        }
+    }
+}
+
+// everything carried: the equipped items and the inventory.
+fn get_carried(character: &Character) -> Vec<&ItemId> {
+    let loadout = &character.loadout;
+    vec![
+        &loadout.left_hand,
+        &loadout.right_hand,
+        &loadout.head,
+        &loadout.armor,
+    ]
+    .into_iter()
+    .flatten()
+    .chain(character.inventory.iter())
+    .fold(vec![], |mut carried, id| {
+        // a two-handed weapon is in both hands, it only counts once.
+        if !carried.contains(&id) {
+            carried.push(id);
+        }
+        carried
+    })
+}
+
+// p272 the Bulk of everything the character carries.
+pub fn get_bulk(character: &Character, world: &World) -> Bulk {
+    get_carried(character)
+        .into_iter()
+        .filter_map(|id| world.items.get(id))
+        .fold(Bulk::NEGLIGIBLE, |total, item| total + item.get_info().bulk)
+}
+
+// p272 encumbered above 5 + Str modifier, and nothing more above 10 + Str modifier.
+pub fn get_bulk_limits(character: &Character) -> (i64, i64) {
+    let strength = character.ability_score.get_modifier(Ability::Strength);
+    (5 + strength, 10 + strength)
+}
+
+fn update_encumbrance(character_id: &str, world: &mut World) {
+    let character = world.get_character(character_id);
+    let (encumbered, _) = get_bulk_limits(character);
+    let bulk = get_bulk(character, world);
+    let character = world.get_mut_character(character_id);
+    if bulk > Bulk::new(encumbered) {
+        character.add_status(StatusEffect {
+            status_type: StatusType::Encumbered,
+            duration: Duration::UntilRemoved,
+        });
+    } else {
+        character.remove_status(StatusType::Encumbered);
+    }
+}
+
+// puts an item of the world in the inventory, unless it is too heavy to carry.
+pub fn add_item(
+    character_id: &str,
+    item_id: &str,
+    world: &mut World,
+) -> Result<(), InventoryError> {
+    let item = world
+        .items
+        .get(item_id)
+        .ok_or_else(|| InventoryError::UnknownItem(String::from(item_id)))?;
+    let character = world.get_character(character_id);
+    let (_, maximum) = get_bulk_limits(character);
+    if get_bulk(character, world) + item.get_info().bulk > Bulk::new(maximum) {
+        return Err(InventoryError::TooHeavy {
+            item: String::from(item_id),
+            maximum,
+        });
+    }
+    world
+        .get_mut_character(character_id)
+        .inventory
+        .push(String::from(item_id));
+    update_encumbrance(character_id, world);
+    Ok(())
+}
+
+// takes an item away from the character, wherever it is. The item stays in the world.
+pub fn remove_item(
+    character_id: &str,
+    item_id: &str,
+    world: &mut World,
+) -> Result<(), InventoryError> {
+    let character = world.get_mut_character(character_id);
+    let loadout = &mut character.loadout;
+    // a two-handed weapon is in both hands.
+    let mut carried = false;
+    for slot in [
+        &mut loadout.left_hand,
+        &mut loadout.right_hand,
+        &mut loadout.head,
+        &mut loadout.armor,
+    ] {
+        if slot.as_deref() == Some(item_id) {
+            *slot = None;
+            carried = true;
+        }
+    }
+    if character.inventory.iter().any(|id| id == item_id) {
+        character.inventory.retain(|id| id != item_id);
+        carried = true;
+    }
+    if !carried {
+        return Err(InventoryError::NotCarried(String::from(item_id)));
+    }
+    update_encumbrance(character_id, world);
+    Ok(())
+}
+
+// p279 two-handed weapons need both hands, every other item one.
+pub fn get_hands(item: &AnyItem) -> usize {
+    match item {
+        AnyItem::WeaponItem(weapon) if weapon.is_two_hands => 2,
+        _ => 1,
+    }
+}
+
+pub fn get_free_hands(loadout: &Loadout) -> usize {
+    [&loadout.right_hand, &loadout.left_hand]
+        .iter()
+        .filter(|hand| hand.is_none())
+        .count()
+}
+
+/**
+The items in the hands must fit: a two-handed weapon takes both of them.
+Loadouts built by hand can be checked with it.
+*/
+pub fn check_hands(loadout: &Loadout, world: &World) -> Result<(), InventoryError> {
+    for id in [&loadout.right_hand, &loadout.left_hand]
+        .iter()
+        .copied()
+        .flatten()
+    {
+        let item = world
+            .items
+            .get(id)
+            .ok_or_else(|| InventoryError::UnknownItem(id.clone()))?;
+        if get_hands(item) == 2 && !loadout.is_held_in_both_hands(id) {
+            return Err(InventoryError::TwoHanded(id.clone()));
+        }
+    }
+    Ok(())
+}
+
+// puts an item in the free hands, the right one first.
+fn hold(loadout: &mut Loadout, item_id: &str, hands: usize) -> Result<(), InventoryError> {
+    if get_free_hands(loadout) < hands {
+        return Err(InventoryError::NoFreeHand(String::from(item_id)));
+    }
+    for _ in 0..hands {
+        let hand = match loadout.right_hand {
+            None => &mut loadout.right_hand,
+            Some(_) => &mut loadout.left_hand,
+        };
+        *hand = Some(String::from(item_id));
+    }
+    Ok(())
+}
+
+// equips an item of the world straight into the hands, like the starting weapons.
+pub fn wield(character_id: &str, item_id: &str, world: &mut World) -> Result<(), InventoryError> {
+    let hands = get_hands(
+        world
+            .items
+            .get(item_id)
+            .ok_or_else(|| InventoryError::UnknownItem(String::from(item_id)))?,
+    );
+    hold(
+        &mut world.get_mut_character(character_id).loadout,
+        item_id,
+        hands,
+    )?;
+    update_encumbrance(character_id, world);
+    Ok(())
+}
+
+/**
+p272 Interact to draw an item from the inventory into a free hand, the right one first.
+Two-handed weapons need both hands. The Bulk does not change.
+*/
+pub fn draw(character_id: &str, item_id: &str, world: &mut World) -> Result<(), InventoryError> {
+    let hands = world.items.get(item_id).map_or(1, get_hands);
+    let character = world.get_mut_character(character_id);
+    if !character.inventory.iter().any(|id| id == item_id) {
+        return Err(InventoryError::NotCarried(String::from(item_id)));
+    }
+    hold(&mut character.loadout, item_id, hands)?;
+    character.inventory.retain(|id| id != item_id);
+    Ok(())
+}
+
+// empties the hands holding the item, returns false when it was not held.
+fn let_go(loadout: &mut Loadout, item_id: &str) -> bool {
+    let mut held = false;
+    for hand in [&mut loadout.right_hand, &mut loadout.left_hand] {
+        if hand.as_deref() == Some(item_id) {
+            *hand = None;
+            held = true;
+        }
+    }
+    held
+}
+
+// p272 Interact to stow a held item back in the inventory.
+pub fn stow(character_id: &str, item_id: &str, world: &mut World) -> Result<(), InventoryError> {
+    let character = world.get_mut_character(character_id);
+    if !let_go(&mut character.loadout, item_id) {
+        return Err(InventoryError::NotHeld(String::from(item_id)));
+    }
+    character.inventory.push(String::from(item_id));
+    Ok(())
+}
+
+/**
+p272 Interact to change the grip: a weapon with the two-hand trait held in one hand
+is now held in both. The other hand has to be free.
+*/
+pub fn grip_in_both_hands(
+    character_id: &str,
+    item_id: &str,
+    world: &mut World,
+) -> Result<(), InventoryError> {
+    let loadout = &mut world.get_mut_character(character_id).loadout;
+    match (&loadout.right_hand, &loadout.left_hand) {
+        (Some(id), None) | (None, Some(id)) if id == item_id => {
+            loadout.right_hand = Some(String::from(item_id));
+            loadout.left_hand = Some(String::from(item_id));
+            Ok(())
+        }
+        (Some(id), _) | (_, Some(id)) if id == item_id => {
+            Err(InventoryError::NoFreeHand(String::from(item_id)))
+        }
+        _ => Err(InventoryError::NotHeld(String::from(item_id))),
+    }
+}
+
+/**
+p470 Release: a one-handed weapon held in both hands stays in the right one, anything else
+falls to the ground. The item stays in the world, the character does not carry it anymore.
+*/
+pub fn release(character_id: &str, item_id: &str, world: &mut World) -> Result<(), InventoryError> {
+    let hands = world.items.get(item_id).map_or(1, get_hands);
+    let loadout = &mut world.get_mut_character(character_id).loadout;
+    if hands == 1 && loadout.is_held_in_both_hands(item_id) {
+        loadout.left_hand = None;
+        return Ok(());
+    }
+    if !let_go(loadout, item_id) {
+        return Err(InventoryError::NotHeld(String::from(item_id)));
+    }
+    update_encumbrance(character_id, world);
+    Ok(())
+}
+
+/**
+Spends a charge of a consumable, the last one destroys it.
+Returns the charges left.
+*/
+pub fn use_charge(character_id: &str, item_id: &str, world: &mut World) -> i64 {
+    let charges = match world.items.get_mut(item_id) {
+        Some(AnyItem::ConsumableItem(consumable)) => {
+            consumable.charges -= 1;
+            consumable.charges
+        }
+        _ => return 0,
+    };
+    if charges <= 0 {
+        // the consumable is carried, it was checked before activating it.
+        let _ = remove_item(character_id, item_id, world);
+        world.items.remove(item_id);
+    }
+    charges
+}
+
+// the consumables in the hands of the character, ready to be activated.
+pub fn get_held_consumables<'a>(
+    character: &Character,
+    world: &'a World,
+) -> Vec<(ItemId, &'a ConsumableItem)> {
+    vec![&character.loadout.right_hand, &character.loadout.left_hand]
+        .into_iter()
+        .flatten()
+        .filter_map(|id| match world.items.get(id) {
+            Some(AnyItem::ConsumableItem(consumable)) => Some((id.clone(), consumable)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::get_speed;
+
+    #[test]
+    fn bulk() {
+        let mut world = World::new();
+        let character = Character::new(String::from("Valeros"), String::from("heroes"), 20);
+        let id = character.id.clone();
+        world.characters.insert(id.clone(), character);
+        // Str 10: encumbered above 5 Bulk, 10 at most
+        assert_eq!(get_bulk_limits(world.get_character(&id)), (5, 10));
+        for _ in 0..2 {
+            let plate = world.spawn_item("full-plate").unwrap();
+            add_item(&id, &plate, &mut world).unwrap();
+        }
+        let character = world.get_character(&id);
+        assert_eq!(get_bulk(character, &world), Bulk::new(8));
+        assert!(character.has_status(StatusType::Encumbered));
+        assert_eq!(get_speed(character, &world), 25 - 10);
+
+        let plate = world.spawn_item("full-plate").unwrap();
+        assert_eq!(
+            add_item(&id, &plate, &mut world),
+            Err(InventoryError::TooHeavy {
+                item: plate.clone(),
+                maximum: 10
+            })
+        );
+        let first = world.get_character(&id).inventory[0].clone();
+        remove_item(&id, &first, &mut world).unwrap();
+        assert!(!world.get_character(&id).has_status(StatusType::Encumbered));
+    }
+
+    #[test]
+    fn hands() {
+        let mut world = World::new();
+        let mut character = Character::new(String::from("Valeros"), String::from("heroes"), 20);
+        character.loadout.left_hand = world.spawn_item("steel-shield").ok();
+        let id = character.id.clone();
+        world.characters.insert(id.clone(), character);
+        let sword = world.spawn_item("longsword").unwrap();
+        let potion = world.spawn_item("minor-healing-potion").unwrap();
+        add_item(&id, &sword, &mut world).unwrap();
+        add_item(&id, &potion, &mut world).unwrap();
+
+        draw(&id, &sword, &mut world).unwrap();
+        assert_eq!(
+            world.get_character(&id).loadout.right_hand,
+            Some(sword.clone())
+        );
+        assert_eq!(
+            draw(&id, &potion, &mut world).unwrap_err().to_string(),
+            format!("no free hand to draw \"{}\"", potion)
+        );
+        stow(&id, &sword, &mut world).unwrap();
+        draw(&id, &potion, &mut world).unwrap();
+        let character = world.get_character(&id);
+        assert_eq!(get_held_consumables(character, &world).len(), 1);
+        assert_eq!(use_charge(&id, &potion, &mut world), 0);
+        let character = world.get_character(&id);
+        assert_eq!(character.loadout.right_hand, None);
+        assert_eq!(character.inventory, vec![sword]);
+        assert!(!world.items.contains_key(&potion));
+    }
+
+    #[test]
+    fn two_hands() {
+        let mut world = World::new();
+        let character = Character::new(String::from("Valeros"), String::from("heroes"), 20);
+        let id = character.id.clone();
+        world.characters.insert(id.clone(), character);
+        let greatsword = world.spawn_item("greatsword").unwrap();
+        let shield = world.spawn_item("steel-shield").unwrap();
+        wield(&id, &greatsword, &mut world).unwrap();
+        let loadout = &world.get_character(&id).loadout;
+        assert!(loadout.is_held_in_both_hands(&greatsword));
+        assert_eq!(check_hands(loadout, &world), Ok(()));
+        assert_eq!(
+            wield(&id, &shield, &mut world),
+            Err(InventoryError::NoFreeHand(shield.clone()))
+        );
+
+        // a greatsword and a shield do not fit
+        let mut loadout = world.get_character(&id).loadout.clone();
+        loadout.left_hand = Some(shield.clone());
+        assert_eq!(
+            check_hands(&loadout, &world),
+            Err(InventoryError::TwoHanded(greatsword.clone()))
+        );
+
+        release(&id, &greatsword, &mut world).unwrap();
+        assert_eq!(get_free_hands(&world.get_character(&id).loadout), 2);
+        assert_eq!(get_bulk(world.get_character(&id), &world), Bulk::NEGLIGIBLE);
+
+        // the two-hand trait
+        let sword = world.spawn_item("bastard-sword").unwrap();
+        wield(&id, &sword, &mut world).unwrap();
+        grip_in_both_hands(&id, &sword, &mut world).unwrap();
+        assert!(world
+            .get_character(&id)
+            .loadout
+            .is_held_in_both_hands(&sword));
+        release(&id, &sword, &mut world).unwrap();
+        let loadout = &world.get_character(&id).loadout;
+        assert_eq!(loadout.right_hand, Some(sword.clone()));
+        assert_eq!(loadout.left_hand, None);
+    }
+}
@@ -1,11 +1,18 @@
+use serde::{Deserialize, Serialize};
 pub mod armor;
+pub mod catalog;
+pub mod consumable;
+pub mod rune;
+pub mod shield;
 pub mod weapon;
 
+use std::{fmt, ops::Add};
+
 use weapon::WeaponItem;
 
 use crate::rules::Rule;
 
-use self::armor::ArmorItem;
+use self::{armor::ArmorItem, consumable::ConsumableItem, shield::ShieldItem};
 
 pub type ItemId = String;
 
@@ -13,34 +20,108 @@ pub trait GameItem {
     fn get_info(&self) -> &ItemInfo;
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum AnyItem {
     WeaponItem(WeaponItem),
     ArmorItem(ArmorItem),
+    ShieldItem(ShieldItem),
+    ConsumableItem(ConsumableItem),
     #[allow(dead_code)]
     HeadItem(HeadItem),
 }
 
-#[derive(Clone, Debug)]
+impl AnyItem {
+    pub fn get_info(&self) -> &ItemInfo {
+        match self {
+            AnyItem::WeaponItem(i) => i.get_info(),
+            AnyItem::ArmorItem(i) => i.get_info(),
+            AnyItem::ShieldItem(i) => i.get_info(),
+            AnyItem::ConsumableItem(i) => i.get_info(),
+            AnyItem::HeadItem(i) => i.get_info(),
+        }
+    }
+
+    pub(crate) fn get_mut_info(&mut self) -> &mut ItemInfo {
+        match self {
+            AnyItem::WeaponItem(i) => &mut i.info,
+            AnyItem::ArmorItem(i) => &mut i.info,
+            AnyItem::ShieldItem(i) => &mut i.info,
+            AnyItem::ConsumableItem(i) => &mut i.info,
+            AnyItem::HeadItem(i) => &mut i.info,
+        }
+    }
+}
+
+/**
+p271 Bulk, counted in tenths so that 10 light items make 1 Bulk.
+Negligible items are worth 0.
+*/
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Bulk(pub i64);
+
+impl Bulk {
+    pub const NEGLIGIBLE: Bulk = Bulk(0);
+    pub const LIGHT: Bulk = Bulk(1);
+
+    pub fn new(bulk: i64) -> Self {
+        Bulk(bulk * 10)
+    }
+
+    // whole Bulk, light items are dropped p272
+    pub fn get_value(&self) -> i64 {
+        self.0 / 10
+    }
+}
+
+impl Add for Bulk {
+    type Output = Bulk;
+    fn add(self, other: Bulk) -> Bulk {
+        Bulk(self.0 + other.0)
+    }
+}
+
+impl fmt::Display for Bulk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.0 / 10, self.0 % 10) {
+            (0, 0) => write!(f, "-"),
+            (0, 1) => write!(f, "L"),
+            (0, l) => write!(f, "{}L", l),
+            (b, 0) => write!(f, "{}", b),
+            (b, l) => write!(f, "{}; {}L", b, l),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ItemInfo {
     pub id: String,
     pub name: String,
-    pub bulk: i64,
+    pub bulk: Bulk,
     pub rules: Vec<Rule>,
+    // the catalog fields, 0 and empty for items built in code.
+    pub level: i64,
+    // in copper pieces p271
+    pub price: i64,
+    pub traits: Vec<String>,
 }
 
 impl ItemInfo {
-    pub fn new(name: &str, bulk: i64, rules: Vec<Rule>) -> Self {
+    pub fn new(name: &str, bulk: Bulk, rules: Vec<Rule>) -> Self {
         let name = String::from(name);
         ItemInfo {
-            id: format!("{}-{}", name, snowflake::ProcessUniqueId::new()),
+            // set by the world when the item is added to it.
+            id: String::new(),
             name,
             bulk,
             rules,
+            level: 0,
+            price: 0,
+            traits: vec![],
         }
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct HeadItem {
     pub info: ItemInfo,
 }
@@ -51,7 +132,7 @@ impl GameItem for HeadItem {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Loadout {
     pub left_hand: Option<ItemId>,
     pub right_hand: Option<ItemId>,
@@ -59,6 +140,13 @@ pub struct Loadout {
     pub armor: Option<ItemId>,
 }
 
+impl Loadout {
+    // p279 a two-handed weapon, or a weapon gripped with both hands, is in both slots.
+    pub fn is_held_in_both_hands(&self, id: &str) -> bool {
+        self.right_hand.as_deref() == Some(id) && self.left_hand.as_deref() == Some(id)
+    }
+}
+
 impl Default for Loadout {
     fn default() -> Loadout {
         Loadout {
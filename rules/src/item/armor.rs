@@ -1,8 +1,25 @@
-use super::{GameItem, HeadItem, ItemInfo};
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug)]
+use crate::{character::Character, timeline::get_modifier};
+
+use super::{
+    rune::{RuneError, RuneTarget, Runes},
+    Bulk, GameItem, HeadItem, ItemInfo,
+};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ArmorCategory {
+    Unarmored,
+    Light,
+    Medium,
+    Heavy,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ArmorItem {
     pub info: ItemInfo,
+    pub runes: Runes,
+    pub category: ArmorCategory,
     pub ac_bonus: i64,
     pub dex_cap: i64,
     pub check_penalty: i64,
@@ -16,31 +33,50 @@ impl GameItem for ArmorItem {
     }
 }
 
-pub fn leather() -> ArmorItem {
-    ArmorItem {
-        info: ItemInfo::new("Leather", 1, vec![]),
-        ac_bonus: 1,
-        dex_cap: 4,
-        check_penalty: -1,
-        speed_penalty: 0,
-        min_strength: 10,
+impl ArmorItem {
+    // p274 meeting the strength requirement removes the check penalty.
+    pub fn get_check_penalty(&self, character: &Character) -> i64 {
+        if character.ability_score.strength >= self.min_strength {
+            0
+        } else {
+            self.check_penalty
+        }
+    }
+
+    // p274 meeting the strength requirement reduces the speed penalty by 5 feet.
+    pub fn get_speed_penalty(&self, character: &Character) -> i64 {
+        if character.ability_score.strength >= self.min_strength {
+            (self.speed_penalty + 5).min(0)
+        } else {
+            self.speed_penalty
+        }
+    }
+
+    pub fn get_dex_bonus(&self, character: &Character) -> i64 {
+        get_modifier(character.ability_score.dexterity).min(self.dex_cap)
+    }
+
+    // p580 armor potency runes add an item bonus to AC.
+    pub fn get_potency(&self) -> i64 {
+        self.runes.potency as i64
+    }
+
+    // p581 resilient runes add an item bonus to saving throws.
+    pub fn get_resilient(&self) -> i64 {
+        self.runes.resilient as i64
     }
-}
 
-pub fn scale_mail() -> ArmorItem {
-    ArmorItem {
-        info: ItemInfo::new("Scale Mail", 2, vec![]),
-        ac_bonus: 3,
-        dex_cap: 3,
-        check_penalty: -2,
-        speed_penalty: 0,
-        min_strength: 12,
+    pub fn with_runes(mut self, runes: Runes) -> Result<Self, RuneError> {
+        runes.validate(RuneTarget::Armor)?;
+        self.info.name = runes.get_name(&self.info.name);
+        self.runes = runes;
+        Ok(self)
     }
 }
 
 #[allow(dead_code)]
 pub fn helmet() -> HeadItem {
     HeadItem {
-        info: ItemInfo::new("Helmet", 1, vec![]),
+        info: ItemInfo::new("Helmet", Bulk::new(1), vec![]),
     }
 }
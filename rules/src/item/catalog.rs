@@ -0,0 +1,576 @@
+use std::{collections::BTreeMap, fmt, fs, path::Path};
+
+use serde::Deserialize;
+
+use crate::{rules::Rule, spell::MAX_RANK, status::StatusType};
+
+use super::{
+    armor::{ArmorCategory, ArmorItem},
+    consumable::{ConsumableEffect, ConsumableItem},
+    rune::Runes,
+    shield::ShieldItem,
+    weapon::{shield_bash, CombatProperties, DamageType, WeaponCategory, WeaponGroup, WeaponItem},
+    AnyItem, Bulk, ItemInfo,
+};
+
+// the Core Rulebook items, shipped with the crate.
+const CORE_ITEMS: &str = include_str!("../../data/items.ron");
+
+// p282 weapon traits without an implementation yet, accepted as plain keywords.
+const KEYWORD_TRAITS: [&str; 26] = [
+    "agile",
+    "alchemical",
+    "backstabber",
+    "bomb",
+    "bulwark",
+    "consumable",
+    "disarm",
+    "fire",
+    "flexible",
+    "forceful",
+    "free-hand",
+    "healing",
+    "magical",
+    "necromancy",
+    "noisy",
+    "nonlethal",
+    "parry",
+    "potion",
+    "scroll",
+    "shove",
+    "splash",
+    "sweep",
+    "talisman",
+    "thrown",
+    "trip",
+    "versatile",
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CatalogError {
+    Io(String),
+    Parse(String),
+    InvalidEntry { key: String, message: String },
+    UnknownItem(String),
+    WrongKind { key: String, expected: &'static str },
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CatalogError::Io(e) => write!(f, "cannot read the item catalog: {}", e),
+            CatalogError::Parse(e) => write!(f, "malformed item catalog: {}", e),
+            CatalogError::InvalidEntry { key, message } => {
+                write!(f, "invalid catalog entry \"{}\": {}", key, message)
+            }
+            CatalogError::UnknownItem(key) => write!(f, "no item \"{}\" in the catalog", key),
+            CatalogError::WrongKind { key, expected } => {
+                write!(f, "catalog entry \"{}\" is not {}", key, expected)
+            }
+        }
+    }
+}
+
+// An entry as written in the catalog file, before validation.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+enum RawItem {
+    Weapon {
+        name: String,
+        price: String,
+        #[serde(default)]
+        level: i64,
+        bulk: String,
+        hands: i64,
+        category: WeaponCategory,
+        group: WeaponGroup,
+        damage: String,
+        damage_type: DamageType,
+        #[serde(default)]
+        range: i64,
+        #[serde(default)]
+        traits: Vec<String>,
+    },
+    Armor {
+        name: String,
+        price: String,
+        #[serde(default)]
+        level: i64,
+        bulk: String,
+        category: ArmorCategory,
+        ac_bonus: i64,
+        dex_cap: i64,
+        check_penalty: i64,
+        speed_penalty: i64,
+        min_strength: i64,
+        #[serde(default)]
+        traits: Vec<String>,
+    },
+    Shield {
+        name: String,
+        price: String,
+        #[serde(default)]
+        level: i64,
+        bulk: String,
+        ac_bonus: i64,
+        hardness: i64,
+        hp: i64,
+        broken_threshold: i64,
+        #[serde(default)]
+        traits: Vec<String>,
+    },
+    Consumable {
+        name: String,
+        price: String,
+        #[serde(default)]
+        level: i64,
+        bulk: String,
+        effect: RawEffect,
+        #[serde(default = "one")]
+        charges: i64,
+        #[serde(default)]
+        traits: Vec<String>,
+    },
+}
+
+fn one() -> i64 {
+    1
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+enum RawEffect {
+    Heal(String),
+    Bomb {
+        damage: String,
+        damage_type: DamageType,
+        splash: i64,
+    },
+    Scroll {
+        spell: String,
+        rank: i64,
+    },
+    Talisman(StatusType),
+}
+
+/**
+Item templates by catalog key, like "longsword".
+Every spawn clones the template with a fresh item id.
+*/
+#[derive(Clone, Debug, Default)]
+pub struct Catalog {
+    items: BTreeMap<String, AnyItem>,
+}
+
+impl Catalog {
+    pub fn core() -> Self {
+        Catalog::parse(CORE_ITEMS).expect("the core item catalog should be valid")
+    }
+
+    pub fn load(path: &Path) -> Result<Self, CatalogError> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| CatalogError::Io(format!("{}: {}", path.display(), e)))?;
+        Catalog::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Self, CatalogError> {
+        let raw: BTreeMap<String, RawItem> =
+            ron::from_str(content).map_err(|e| CatalogError::Parse(e.to_string()))?;
+        let mut items = BTreeMap::new();
+        for (key, entry) in raw {
+            let item = validate(entry).map_err(|message| CatalogError::InvalidEntry {
+                key: key.clone(),
+                message,
+            })?;
+            items.insert(key, item);
+        }
+        Ok(Catalog { items })
+    }
+
+    // later catalogs override the entries with the same key.
+    pub fn extend(&mut self, other: Catalog) {
+        self.items.extend(other.items);
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.items.keys()
+    }
+
+    pub fn spawn(&self, key: &str) -> Result<AnyItem, CatalogError> {
+        let mut item = self
+            .items
+            .get(key)
+            .cloned()
+            .ok_or_else(|| CatalogError::UnknownItem(String::from(key)))?;
+        // every copy gets its own id once added to the world.
+        item.get_mut_info().id = String::new();
+        Ok(item)
+    }
+
+    pub fn get_weapon(&self, key: &str) -> Result<WeaponItem, CatalogError> {
+        match self.spawn(key)? {
+            AnyItem::WeaponItem(w) => Ok(w),
+            _ => Err(wrong_kind(key, "a weapon")),
+        }
+    }
+
+    pub fn get_armor(&self, key: &str) -> Result<ArmorItem, CatalogError> {
+        match self.spawn(key)? {
+            AnyItem::ArmorItem(a) => Ok(a),
+            _ => Err(wrong_kind(key, "an armor")),
+        }
+    }
+
+    pub fn get_shield(&self, key: &str) -> Result<ShieldItem, CatalogError> {
+        match self.spawn(key)? {
+            AnyItem::ShieldItem(s) => Ok(s),
+            _ => Err(wrong_kind(key, "a shield")),
+        }
+    }
+}
+
+fn wrong_kind(key: &str, expected: &'static str) -> CatalogError {
+    CatalogError::WrongKind {
+        key: String::from(key),
+        expected,
+    }
+}
+
+fn validate(entry: RawItem) -> Result<AnyItem, String> {
+    match entry {
+        RawItem::Weapon {
+            name,
+            price,
+            level,
+            bulk,
+            hands,
+            category,
+            group,
+            damage,
+            damage_type,
+            range,
+            traits,
+        } => {
+            let (nb_dice, dice_faces, bonus) = parse_dice(&damage)?;
+            if bonus != 0 {
+                return Err(format!("weapon damage \"{}\" cannot have a bonus", damage));
+            }
+            if hands != 1 && hands != 2 {
+                return Err(format!("hands must be 1 or 2, got {}", hands));
+            }
+            if range < 0 {
+                return Err(format!("range must be positive, got {}", range));
+            }
+            Ok(AnyItem::WeaponItem(WeaponItem {
+                info: parse_info(&name, &price, level, &bulk, traits)?,
+                runes: Runes::default(),
+                damage: CombatProperties {
+                    damage_type,
+                    dice_faces,
+                    nb_dice,
+                },
+                category,
+                group,
+                is_two_hands: hands == 2,
+                is_ranged: range > 0,
+                range,
+            }))
+        }
+        RawItem::Armor {
+            name,
+            price,
+            level,
+            bulk,
+            category,
+            ac_bonus,
+            dex_cap,
+            check_penalty,
+            speed_penalty,
+            min_strength,
+            traits,
+        } => {
+            if check_penalty > 0 || speed_penalty > 0 {
+                return Err(String::from("armor penalties must be 0 or negative"));
+            }
+            Ok(AnyItem::ArmorItem(ArmorItem {
+                info: parse_info(&name, &price, level, &bulk, traits)?,
+                runes: Runes::default(),
+                category,
+                ac_bonus,
+                dex_cap,
+                check_penalty,
+                speed_penalty,
+                min_strength,
+            }))
+        }
+        RawItem::Shield {
+            name,
+            price,
+            level,
+            bulk,
+            ac_bonus,
+            hardness,
+            hp,
+            broken_threshold,
+            traits,
+        } => {
+            if broken_threshold > hp {
+                return Err(format!(
+                    "broken threshold {} is above the shield HP {}",
+                    broken_threshold, hp
+                ));
+            }
+            Ok(AnyItem::ShieldItem(ShieldItem {
+                info: parse_info(&name, &price, level, &bulk, traits)?,
+                ac_bonus,
+                hardness,
+                hp,
+                max_hp: hp,
+                broken_threshold,
+                bash: shield_bash(),
+            }))
+        }
+        RawItem::Consumable {
+            name,
+            price,
+            level,
+            bulk,
+            effect,
+            charges,
+            traits,
+        } => {
+            if charges < 1 {
+                return Err(format!("charges must be positive, got {}", charges));
+            }
+            let effect = match effect {
+                RawEffect::Heal(heal) => {
+                    let (nb_dice, dice_faces, bonus) = parse_dice(&heal)?;
+                    ConsumableEffect::Heal {
+                        nb_dice,
+                        dice_faces,
+                        bonus,
+                    }
+                }
+                RawEffect::Bomb {
+                    damage,
+                    damage_type,
+                    splash,
+                } => {
+                    let (nb_dice, dice_faces, bonus) = parse_dice(&damage)?;
+                    if bonus != 0 {
+                        return Err(format!("bomb damage \"{}\" cannot have a bonus", damage));
+                    }
+                    ConsumableEffect::Bomb {
+                        nb_dice,
+                        dice_faces,
+                        damage_type,
+                        splash,
+                    }
+                }
+                // the spell itself is only checked against the spellbook when cast.
+                RawEffect::Scroll { spell, rank } => {
+                    if !(1..=MAX_RANK).contains(&rank) {
+                        return Err(format!(
+                            "scroll rank must be between 1 and {}, got {}",
+                            MAX_RANK, rank
+                        ));
+                    }
+                    ConsumableEffect::Scroll { spell, rank }
+                }
+                RawEffect::Talisman(status) => ConsumableEffect::Talisman(status),
+            };
+            Ok(AnyItem::ConsumableItem(ConsumableItem {
+                info: parse_info(&name, &price, level, &bulk, traits)?,
+                effect,
+                charges,
+            }))
+        }
+    }
+}
+
+fn parse_info(
+    name: &str,
+    price: &str,
+    level: i64,
+    bulk: &str,
+    traits: Vec<String>,
+) -> Result<ItemInfo, String> {
+    if !(0..=25).contains(&level) {
+        return Err(format!("level must be between 0 and 25, got {}", level));
+    }
+    let mut rules = vec![];
+    for t in traits.iter() {
+        if let Some(rule) = parse_trait(t)? {
+            rules.push(rule);
+        }
+    }
+    let mut info = ItemInfo::new(name, parse_bulk(bulk)?, rules);
+    info.price = parse_price(price)?;
+    info.level = level;
+    info.traits = traits;
+    Ok(info)
+}
+
+// "1d8", "2d8+5", "1d4-1"
+pub fn parse_dice(expr: &str) -> Result<(i64, i64, i64), String> {
+    let invalid = || format!("\"{}\" is not in dice notation like 1d8 or 2d8+5", expr);
+    let (dice, bonus) = match expr.find(['+', '-']) {
+        // the sign is kept with the modifier.
+        Some(sign) => {
+            let bonus = expr[sign..].trim().parse::<i64>();
+            (&expr[..sign], bonus.map_err(|_| invalid())?)
+        }
+        None => (expr, 0),
+    };
+    let (nb_dice, faces) = dice.trim().split_once('d').ok_or_else(invalid)?;
+    let nb_dice = nb_dice.parse::<i64>().map_err(|_| invalid())?;
+    let faces = faces.parse::<i64>().map_err(|_| invalid())?;
+    if nb_dice < 1 || ![4, 6, 8, 10, 12].contains(&faces) {
+        return Err(invalid());
+    }
+    Ok((nb_dice, faces, bonus))
+}
+
+// p271 "-" for negligible, "L" for light, or a number.
+fn parse_bulk(bulk: &str) -> Result<Bulk, String> {
+    match bulk {
+        "-" => Ok(Bulk::NEGLIGIBLE),
+        "L" => Ok(Bulk::LIGHT),
+        n => match n.parse::<i64>() {
+            Ok(n) if n >= 0 => Ok(Bulk::new(n)),
+            _ => Err(format!("bulk must be -, L or a number, got \"{}\"", bulk)),
+        },
+    }
+}
+
+// p271 1 gp = 10 sp = 100 cp
+fn parse_price(price: &str) -> Result<i64, String> {
+    let invalid = || format!("price must look like \"5 sp\", got \"{}\"", price);
+    let (amount, coin) = price.split_once(' ').ok_or_else(invalid)?;
+    let amount = amount.parse::<i64>().map_err(|_| invalid())?;
+    let value = match coin {
+        "cp" => 1,
+        "sp" => 10,
+        "gp" => 100,
+        "pp" => 1000,
+        _ => return Err(invalid()),
+    };
+    if amount < 0 {
+        return Err(invalid());
+    }
+    Ok(amount * value)
+}
+
+// Traits with a rule implementation map to it, the others must be known keywords.
+pub(crate) fn parse_trait(t: &str) -> Result<Option<Rule>, String> {
+    let mut words = t.split(' ');
+    let name = words.next().unwrap_or_default();
+    let arg = words.next();
+    match (name, arg) {
+        ("finesse", None) => Ok(Some(Rule::Finesse)),
+        ("propulsive", None) => Ok(Some(Rule::Propulsive)),
+        ("reach", None) => Ok(Some(Rule::Reach)),
+        ("deadly", Some(die)) => {
+            let (_, faces, _) = parse_dice(&format!("1{}", die))?;
+            Ok(Some(Rule::Deadly(faces as usize)))
+        }
+        ("two-hand", Some(die)) => {
+            let (_, faces, _) = parse_dice(&format!("1{}", die))?;
+            Ok(Some(Rule::TwoHand(faces as usize)))
+        }
+        // volley only matters for ranged penalties we do not compute yet.
+        ("volley", Some(_)) => Ok(None),
+        (name, _) if KEYWORD_TRAITS.contains(&name) => Ok(None),
+        _ => Err(format!("unknown trait \"{}\"", t)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::World;
+
+    #[test]
+    fn core() {
+        let catalog = Catalog::core();
+        let longsword = catalog.get_weapon("longsword").unwrap();
+        assert_eq!(longsword.info.name, "Longsword");
+        assert_eq!(longsword.info.price, 100);
+        assert_eq!(longsword.info.bulk, Bulk::new(1));
+        assert_eq!(longsword.damage.nb_dice, 1);
+        assert_eq!(longsword.damage.dice_faces, 8);
+        assert_eq!(longsword.damage.damage_type, DamageType::Slashing);
+        assert_eq!(longsword.group, WeaponGroup::Sword);
+        assert!(!longsword.is_two_hands);
+
+        let rapier = catalog.get_weapon("rapier").unwrap();
+        assert!(rapier.info.rules.contains(&Rule::Finesse));
+        assert!(rapier.info.rules.contains(&Rule::Deadly(8)));
+        let staff = catalog.get_weapon("staff").unwrap();
+        assert!(staff.info.rules.contains(&Rule::TwoHand(8)));
+
+        // every copy gets its own id in the world.
+        let mut world = World::new();
+        assert_ne!(
+            world.spawn_item("longsword").unwrap(),
+            world.spawn_item("longsword").unwrap()
+        );
+        assert_eq!(
+            catalog.get_shield("buckler").unwrap().info.bulk,
+            Bulk::LIGHT
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let catalog = Catalog::core();
+        assert_eq!(
+            catalog.get_armor("longsword").unwrap_err().to_string(),
+            "catalog entry \"longsword\" is not an armor"
+        );
+        assert_eq!(
+            catalog.spawn("vorpal-sword").unwrap_err(),
+            CatalogError::UnknownItem(String::from("vorpal-sword"))
+        );
+
+        let entry = |traits: &str, damage: &str| {
+            format!(
+                r#"{{ "test": Weapon(name: "Test", price: "1 gp", bulk: "1", hands: 1, category: Martial,
+                    group: Sword, damage: "{}", damage_type: Slashing, traits: [{}]) }}"#,
+                damage, traits
+            )
+        };
+        assert!(Catalog::parse(&entry("\"finesse\"", "1d6")).is_ok());
+        assert_eq!(
+            Catalog::parse(&entry("\"vorpal\"", "1d6"))
+                .unwrap_err()
+                .to_string(),
+            "invalid catalog entry \"test\": unknown trait \"vorpal\""
+        );
+        assert_eq!(
+            Catalog::parse(&entry("", "1d7")).unwrap_err().to_string(),
+            "invalid catalog entry \"test\": \"1d7\" is not in dice notation like 1d8 or 2d8+5"
+        );
+        let bomb = r#"{ "test": Consumable(name: "Test", price: "3 gp", bulk: "L",
+            effect: Bomb(damage: "1d8+2", damage_type: Fire, splash: 1)) }"#;
+        assert_eq!(
+            Catalog::parse(bomb).unwrap_err().to_string(),
+            "invalid catalog entry \"test\": bomb damage \"1d8+2\" cannot have a bonus"
+        );
+        assert!(matches!(
+            Catalog::parse(r#"{ "test": Wand(name: "Test") }"#),
+            Err(CatalogError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn dice() {
+        assert_eq!(parse_dice("1d8"), Ok((1, 8, 0)));
+        assert_eq!(parse_dice("2d8+5"), Ok((2, 8, 5)));
+        assert_eq!(parse_dice("1d4-1"), Ok((1, 4, -1)));
+        assert!(parse_dice("1d4-").is_err());
+        assert!(parse_dice("1d4+-1").is_err());
+        assert!(parse_dice("d8").is_err());
+        assert!(parse_dice("1d8+").is_err());
+    }
+}
@@ -0,0 +1,76 @@
+use crate::status::StatusType;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    weapon::{CombatProperties, DamageType, WeaponCategory, WeaponGroup, WeaponItem},
+    GameItem, ItemInfo,
+};
+
+// What happens when a consumable is activated.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConsumableEffect {
+    // p256 healing potions restore nb_dice d faces + bonus HP
+    Heal {
+        nb_dice: i64,
+        dice_faces: i64,
+        bonus: i64,
+    },
+    // p544 alchemical bombs are thrown, splash damage hits the target and the creatures next to it.
+    Bomb {
+        nb_dice: i64,
+        dice_faces: i64,
+        damage_type: DamageType,
+        splash: i64,
+    },
+    // p564 a scroll holds a spell, cast at the rank of the scroll.
+    Scroll {
+        spell: String,
+        rank: i64,
+    },
+    // p565 a talisman affixed to an item grants a status until the next turn.
+    Talisman(StatusType),
+}
+
+/**
+p544 Consumables are used up when activated, after their last charge.
+*/
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ConsumableItem {
+    pub info: ItemInfo,
+    pub effect: ConsumableEffect,
+    pub charges: i64,
+}
+
+impl ConsumableItem {
+    // p544 bombs are martial thrown weapons with a range increment of 20 feet.
+    pub fn get_bomb(&self) -> Option<WeaponItem> {
+        match self.effect {
+            ConsumableEffect::Bomb {
+                nb_dice,
+                dice_faces,
+                damage_type,
+                ..
+            } => Some(WeaponItem {
+                info: self.info.clone(),
+                runes: Default::default(),
+                damage: CombatProperties {
+                    damage_type,
+                    dice_faces,
+                    nb_dice,
+                },
+                category: WeaponCategory::Martial,
+                group: WeaponGroup::Bomb,
+                is_two_hands: false,
+                is_ranged: true,
+                range: 20,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl GameItem for ConsumableItem {
+    fn get_info(&self) -> &ItemInfo {
+        &self.info
+    }
+}
@@ -0,0 +1,229 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::rules::Rule;
+
+// p581 property runes. Each one takes a slot granted by the potency rune.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PropertyRune {
+    Flaming,
+    Frost,
+    Shock,
+    Holy,
+    // p581 its Strikes ignore the resistance to all damage of incorporeal creatures.
+    GhostTouch,
+}
+
+impl PropertyRune {
+    pub fn get_rule(&self) -> Rule {
+        match self {
+            PropertyRune::Flaming => Rule::Flaming,
+            PropertyRune::Frost => Rule::Frost,
+            PropertyRune::Shock => Rule::Shock,
+            PropertyRune::Holy => Rule::Holy,
+            PropertyRune::GhostTouch => Rule::GhostTouch,
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        match self {
+            PropertyRune::Flaming => "Flaming",
+            PropertyRune::Frost => "Frost",
+            PropertyRune::Shock => "Shock",
+            PropertyRune::Holy => "Holy",
+            PropertyRune::GhostTouch => "Ghost Touch",
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RuneTarget {
+    Weapon,
+    Armor,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RuneError {
+    InvalidTier { rune: &'static str, tier: usize },
+    WrongItem { rune: String, target: RuneTarget },
+    NotEnoughSlots { slots: usize, property: usize },
+    Duplicate(PropertyRune),
+}
+
+impl fmt::Display for RuneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuneError::InvalidTier { rune, tier } => {
+                write!(
+                    f,
+                    "{} rune tier must be between 1 and 3, got {}",
+                    rune, tier
+                )
+            }
+            RuneError::WrongItem { rune, target } => {
+                write!(f, "{} rune cannot be etched on {:?}", rune, target)
+            }
+            RuneError::NotEnoughSlots { slots, property } => write!(
+                f,
+                "{} property runes but the potency rune only grants {} slots",
+                property, slots
+            ),
+            RuneError::Duplicate(rune) => write!(f, "{} rune is etched twice", rune.get_name()),
+        }
+    }
+}
+
+/**
+p580 Runes etched on a weapon or an armor. 0 means no rune.
+- potency (+1 to +3) is an item bonus to attack rolls or AC, and grants as many property rune slots.
+- striking (weapons) adds 1 to 3 weapon damage dice: striking, greater striking, major striking.
+- resilient (armor) is an item bonus of 1 to 3 to saving throws.
+*/
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Runes {
+    pub potency: usize,
+    pub striking: usize,
+    pub resilient: usize,
+    pub property: Vec<PropertyRune>,
+}
+
+impl Runes {
+    pub fn validate(&self, target: RuneTarget) -> Result<(), RuneError> {
+        for (rune, tier) in [
+            ("potency", self.potency),
+            ("striking", self.striking),
+            ("resilient", self.resilient),
+        ] {
+            if tier > 3 {
+                return Err(RuneError::InvalidTier { rune, tier });
+            }
+        }
+        match target {
+            RuneTarget::Weapon if self.resilient > 0 => {
+                return Err(RuneError::WrongItem {
+                    rune: String::from("Resilient"),
+                    target,
+                })
+            }
+            RuneTarget::Armor if self.striking > 0 => {
+                return Err(RuneError::WrongItem {
+                    rune: String::from("Striking"),
+                    target,
+                })
+            }
+            // all the property runes we know of are weapon runes.
+            RuneTarget::Armor if !self.property.is_empty() => {
+                return Err(RuneError::WrongItem {
+                    rune: String::from(self.property[0].get_name()),
+                    target,
+                })
+            }
+            _ => {}
+        }
+        if self.property.len() > self.potency {
+            return Err(RuneError::NotEnoughSlots {
+                slots: self.potency,
+                property: self.property.len(),
+            });
+        }
+        for (i, rune) in self.property.iter().enumerate() {
+            if self.property[..i].contains(rune) {
+                return Err(RuneError::Duplicate(*rune));
+            }
+        }
+        Ok(())
+    }
+
+    // the rules added to the damage pipeline.
+    pub fn get_rules(&self) -> Vec<Rule> {
+        let mut rules = vec![];
+        if self.striking > 0 {
+            rules.push(Rule::Striking(self.striking));
+        }
+        rules.extend(self.property.iter().map(|r| r.get_rule()));
+        rules
+    }
+
+    // "+1 Striking Flaming Greatsword"
+    pub fn get_name(&self, base: &str) -> String {
+        let tier = |t: usize| match t {
+            2 => "Greater ",
+            3 => "Major ",
+            _ => "",
+        };
+        let mut parts = vec![];
+        if self.potency > 0 {
+            parts.push(format!("+{}", self.potency));
+        }
+        if self.striking > 0 {
+            parts.push(format!("{}Striking", tier(self.striking)));
+        }
+        if self.resilient > 0 {
+            parts.push(format!("{}Resilient", tier(self.resilient)));
+        }
+        parts.extend(self.property.iter().map(|r| String::from(r.get_name())));
+        parts.push(String::from(base));
+        parts.join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate() {
+        let runes = Runes {
+            potency: 1,
+            striking: 1,
+            property: vec![PropertyRune::Flaming],
+            ..Default::default()
+        };
+        assert_eq!(runes.validate(RuneTarget::Weapon), Ok(()));
+        assert!(runes.validate(RuneTarget::Armor).is_err());
+
+        let runes = Runes {
+            potency: 1,
+            property: vec![PropertyRune::Flaming, PropertyRune::Frost],
+            ..Default::default()
+        };
+        assert_eq!(
+            runes.validate(RuneTarget::Weapon),
+            Err(RuneError::NotEnoughSlots {
+                slots: 1,
+                property: 2
+            })
+        );
+
+        let runes = Runes {
+            potency: 2,
+            property: vec![PropertyRune::Shock, PropertyRune::Shock],
+            ..Default::default()
+        };
+        assert_eq!(
+            runes.validate(RuneTarget::Weapon),
+            Err(RuneError::Duplicate(PropertyRune::Shock))
+        );
+
+        let runes = Runes {
+            potency: 4,
+            ..Default::default()
+        };
+        assert!(runes.validate(RuneTarget::Armor).is_err());
+    }
+
+    #[test]
+    fn name() {
+        let runes = Runes {
+            potency: 2,
+            striking: 2,
+            property: vec![PropertyRune::Holy],
+            ..Default::default()
+        };
+        assert_eq!(
+            runes.get_name("Greatsword"),
+            "+2 Greater Striking Holy Greatsword"
+        );
+        assert_eq!(Runes::default().get_name("Longbow"), "Longbow");
+    }
+}
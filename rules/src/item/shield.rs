@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::max;
+
+use super::{weapon::WeaponItem, GameItem, ItemInfo};
+
+/**
+p277 Shields.
+The AC bonus is a circumstance bonus that only applies while the shield is raised.
+Once its HP drops to its broken threshold, the shield cannot be raised anymore.
+*/
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ShieldItem {
+    pub info: ItemInfo,
+    pub ac_bonus: i64,
+    pub hardness: i64,
+    pub hp: i64,
+    pub max_hp: i64,
+    pub broken_threshold: i64,
+    // a shield bash is a Strike with the shield, like an unarmed attack.
+    pub bash: WeaponItem,
+}
+
+impl GameItem for ShieldItem {
+    fn get_info(&self) -> &ItemInfo {
+        &self.info
+    }
+}
+
+impl ShieldItem {
+    pub fn is_broken(&self) -> bool {
+        self.hp <= self.broken_threshold
+    }
+
+    /**
+    p266 Shield Block: the damage is reduced by the hardness,
+    then the shield and the bearer each take the remaining damage.
+    Returns the damage left for the bearer.
+    */
+    pub fn block(&mut self, damage: i64) -> i64 {
+        let remaining = max(damage - self.hardness, 0);
+        self.hp = max(self.hp - remaining, 0);
+        remaining
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::item::catalog::Catalog;
+
+    #[test]
+    fn block() {
+        let mut shield = Catalog::core().get_shield("steel-shield").unwrap();
+        assert_eq!(shield.block(3), 0);
+        assert_eq!(shield.hp, 20);
+        assert_eq!(shield.block(12), 7);
+        assert_eq!(shield.hp, 13);
+        assert!(!shield.is_broken());
+        assert_eq!(shield.block(8), 3);
+        assert!(shield.is_broken());
+    }
+}
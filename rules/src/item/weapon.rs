@@ -1,14 +1,49 @@
 use crate::rules::Rule;
-use rand::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use super::{GameItem, ItemInfo};
-#[derive(Clone, Debug)]
+use super::{
+    rune::{RuneError, RuneTarget, Runes},
+    Bulk, GameItem, ItemInfo,
+};
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct WeaponItem {
     pub info: ItemInfo,
+    pub runes: Runes,
     pub damage: CombatProperties,
+    pub category: WeaponCategory,
+    pub group: WeaponGroup,
     pub is_two_hands: bool,
     pub is_ranged: bool,
-    range: i64,
+    // range increment in feet, 0 for melee weapons.
+    pub range: i64,
+}
+
+impl WeaponItem {
+    pub fn with_runes(mut self, runes: Runes) -> Result<Self, RuneError> {
+        runes.validate(RuneTarget::Weapon)?;
+        self.info.name = runes.get_name(&self.info.name);
+        self.runes = runes;
+        Ok(self)
+    }
+
+    // p283 reach trait, in feet. Ranged weapons do not threaten any square.
+    pub fn get_reach(&self) -> i64 {
+        if self.is_ranged {
+            0
+        } else if self.info.rules.contains(&Rule::Reach) {
+            10
+        } else {
+            5
+        }
+    }
+
+    // p283 two-hand trait, the damage die when wielded in both hands.
+    pub fn get_two_hand_die(&self) -> Option<i64> {
+        self.info.rules.iter().find_map(|r| match r {
+            Rule::TwoHand(faces) => Some(*faces as i64),
+            _ => None,
+        })
+    }
 }
 
 impl GameItem for WeaponItem {
@@ -17,53 +52,63 @@ impl GameItem for WeaponItem {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CombatProperties {
     pub damage_type: DamageType,
     pub dice_faces: i64,
     pub nb_dice: i64,
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DamageType {
     Bludgeoning,
     Piercing,
     Slashing,
+    // energy and alignment damage come from property runes for now.
+    Fire,
+    Cold,
+    Electricity,
+    Good,
+    // p452 Magic Missile
+    Force,
 }
 
-// p280 greatsword
-pub fn greatswordplus2() -> WeaponItem {
-    WeaponItem {
-        info: ItemInfo::new("Greatsword +2", 2, vec![Rule::Striking(2)]),
-        is_two_hands: true,
-        is_ranged: false,
-        range: 0,
-        damage: CombatProperties {
-            nb_dice: 1,
-            damage_type: DamageType::Slashing,
-            dice_faces: 12,
-        },
-    }
+// p279 weapon categories, each with its own proficiency.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WeaponCategory {
+    Unarmed,
+    Simple,
+    Martial,
+    Advanced,
 }
 
-pub fn greatswordplus1() -> WeaponItem {
-    WeaponItem {
-        info: ItemInfo::new("Greatsword +2", 2, vec![Rule::Striking(1)]),
-        is_two_hands: true,
-        is_ranged: false,
-        range: 0,
-        damage: CombatProperties {
-            nb_dice: 1,
-            damage_type: DamageType::Slashing,
-            dice_faces: 12,
-        },
-    }
+// p280 weapon groups, used by critical specialization effects.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WeaponGroup {
+    Axe,
+    Bomb,
+    Bow,
+    Brawling,
+    Club,
+    Dart,
+    Flail,
+    Hammer,
+    Knife,
+    Pick,
+    Polearm,
+    Shield,
+    Sling,
+    Spear,
+    Sword,
 }
+
 // p280 fist
-#[allow(dead_code)]
 pub fn fist() -> WeaponItem {
     WeaponItem {
-        info: ItemInfo::new("Fist", 0, vec![]),
+        info: ItemInfo::new("Fist", Bulk::NEGLIGIBLE, vec![]),
+        runes: Runes::default(),
+        category: WeaponCategory::Unarmed,
+        group: WeaponGroup::Brawling,
         is_two_hands: false,
         is_ranged: false,
         range: 0,
@@ -75,11 +120,13 @@ pub fn fist() -> WeaponItem {
     }
 }
 
-pub fn unarmed() -> WeaponItem {
-    let names = vec!["Fist", "Head", "Knee", "Foot"];
-    let pick = thread_rng().gen_range(0..names.len());
+// p277 shield bash, the shield is used like an unarmed attack.
+pub fn shield_bash() -> WeaponItem {
     WeaponItem {
-        info: ItemInfo::new(names[pick], 0, vec![]),
+        info: ItemInfo::new("Shield Bash", Bulk::NEGLIGIBLE, vec![]),
+        runes: Runes::default(),
+        category: WeaponCategory::Martial,
+        group: WeaponGroup::Shield,
         is_two_hands: false,
         is_ranged: false,
         range: 0,
@@ -91,30 +138,10 @@ pub fn unarmed() -> WeaponItem {
     }
 }
 
-pub fn longbow() -> WeaponItem {
-    WeaponItem {
-        info: ItemInfo::new("Longbow", 2, vec![]),
-        is_two_hands: true,
-        is_ranged: true,
-        range: 100,
-        damage: CombatProperties {
-            nb_dice: 1,
-            damage_type: DamageType::Piercing,
-            dice_faces: 8,
-        },
-    }
-}
-
-pub fn sling() -> WeaponItem {
-    WeaponItem {
-        info: ItemInfo::new("Sling", 2, vec![Rule::Propulsive]),
-        is_two_hands: false,
-        is_ranged: true,
-        range: 100,
-        damage: CombatProperties {
-            nb_dice: 1,
-            dice_faces: 6,
-            damage_type: DamageType::Bludgeoning,
-        },
-    }
+// p280 a fist, the same name in every world so that games can be replayed.
+pub fn unarmed() -> WeaponItem {
+    let mut weapon = fist();
+    // a single item shared by everyone with empty hands.
+    weapon.info.id = String::from("unarmed");
+    weapon
 }
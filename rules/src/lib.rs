@@ -1,17 +1,43 @@
 pub mod activity;
+pub mod ai;
+pub mod area;
+pub mod batch;
+pub mod bestiary;
 pub mod character;
+pub mod check;
+pub mod cover;
+pub mod damage;
+pub mod defense;
+pub mod encounter;
 pub mod fact;
+pub mod flanking;
+pub mod healing;
+pub mod history;
+pub mod inventory;
 pub mod item;
+pub mod map;
+pub mod position;
+pub mod reaction;
+pub mod replay;
 pub mod rules;
+pub mod save;
+pub mod spell;
 pub mod status;
 pub mod timeline;
-pub mod ui;
 pub mod utils;
 pub mod world;
 
-use activity::select_best_action;
-use fact::Facts;
-use timeline::{Activation, Timeline};
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use activity::{can_choose, perform, take_activity, Activity};
+use ai::plan_turn;
+use character::Character;
+use fact::{Fact, Facts};
+use history::{History, HouseRules};
+use replay::Decision;
+use timeline::{Activation, CharacterId, Timeline};
 use world::{init, World};
 
 pub fn get_initiative<'b>(world: &'b World) -> Vec<Activation> {
@@ -26,29 +52,206 @@ pub fn get_initiative<'b>(world: &'b World) -> Vec<Activation> {
         .collect()
 }
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct GameState {
     world: World,
     timeline: Timeline,
     activations: Vec<Activation>,
     is_encounter_done: bool,
+    // every activity chosen so far, to replay the encounter.
+    decisions: Vec<Decision>,
+    // the choices of the players, taken in order instead of the ones of the AI.
+    planned: VecDeque<Decision>,
+    turn: Option<Turn>,
+    house_rules: HouseRules,
+    // a loaded game cannot take back what was played before it was saved.
+    #[serde(skip)]
+    history: History,
+}
+
+// the character playing its turn, with the actions it has left.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Turn {
+    character_id: CharacterId,
+    action_left: i64,
 }
 
 impl GameState {
     pub fn new() -> Self {
         let mut world = World::new();
         init(&mut world);
+        GameState::from_world(world)
+    }
+
+    // an encounter set up beforehand, for instance from an encounter file.
+    pub fn from_world(world: World) -> Self {
         GameState {
             activations: get_initiative(&world),
             world,
             timeline: Timeline::new(),
             is_encounter_done: false,
+            decisions: vec![],
+            planned: VecDeque::new(),
+            turn: None,
+            house_rules: HouseRules::default(),
+            history: History::default(),
         }
     }
 
+    pub fn house_rules(mut self, house_rules: HouseRules) -> Self {
+        self.house_rules = house_rules;
+        self
+    }
+
+    // the characters named by the facts, to render them.
+    pub fn get_world(&self) -> &World {
+        &self.world
+    }
+
+    pub fn get_round(&self) -> i64 {
+        self.timeline.turn_counter
+    }
+
+    pub fn get_decisions(&self) -> &[Decision] {
+        &self.decisions
+    }
+
+    // the names of the activities of the character, a decision picks one of them by its index.
+    pub fn get_activities(&self, character_id: &str) -> Vec<String> {
+        self.world
+            .get_character(character_id)
+            .get_activities(&self.world)
+            .iter()
+            .map(|a| String::from(a.get_name()))
+            .collect()
+    }
+
+    /**
+    Plans the next activity of a character, in place of the AI.
+    A decision that cannot be played when its turn comes, like an activity costing too many actions,
+    is left to the AI.
+    */
+    pub fn decide(&mut self, decision: Decision) {
+        self.planned.push_back(decision);
+    }
+
+    /**
+    The decision planned by a player comes first, the AI plans the rest of the turn otherwise.
+    Planning rolls no dice of the game, a replay rolls the same dice as the game it was recorded from.
+    */
+    fn choose(
+        &mut self,
+        activities: &[Box<dyn Activity>],
+        character: &Character,
+        action_left: i64,
+    ) -> Option<usize> {
+        let is_planned = self
+            .planned
+            .front()
+            .is_some_and(|d| d.character_id == character.id);
+        if is_planned {
+            match self.planned.pop_front().and_then(|d| d.activity) {
+                None => return None,
+                Some(index)
+                    if activities.get(index).is_some_and(|a| {
+                        can_choose(a.as_ref(), character, action_left, &self.world)
+                    }) =>
+                {
+                    return Some(index)
+                }
+                Some(_) => {}
+            }
+        }
+        plan_turn(character, action_left, &mut self.world).first
+    }
+
+    /**
+    Ticks until one party is left standing, or until the rounds limit is reached.
+    The first round starts without a fact, every following one with a RoundStart.
+    */
+    pub fn run(&mut self, rounds_limit: i64) -> Facts {
+        let mut log = Facts::new();
+        loop {
+            for fact in self.tick() {
+                match fact {
+                    Fact::RoundStart(round) if round > rounds_limit => return log,
+                    Fact::EncounterOver => {
+                        log.push(fact);
+                        return log;
+                    }
+                    fact => log.push(fact),
+                }
+            }
+        }
+    }
+
+    // plays a whole turn at once, its activities cannot be taken back.
     pub fn tick(&mut self) -> Facts {
+        let mut facts = self.play(false);
+        while self.turn.is_some() {
+            facts.extend(self.play(false));
+        }
+        facts
+    }
+
+    /**
+    Plays one activity of the character whose turn it is, so that a player can take it back.
+    Between two turns, starts the next turn or round instead.
+    */
+    pub fn step(&mut self) -> Facts {
+        self.play(true)
+    }
+
+    fn play(&mut self, undoable: bool) -> Facts {
+        let mut turn = match &self.turn {
+            Some(turn) => turn.clone(),
+            None => return self.next_turn(),
+        };
+        let mut facts = Facts::new();
+        let active_character = self.world.get_character(&turn.character_id).clone();
+        if active_character.hp > 0 {
+            let before = undoable.then(|| self.snapshot());
+            // had to clone because activity needs at the same time:
+            // an immutable ref to the character to know how much damage the attacker can do,
+            // a mutable ref to the world to resolve the action.
+
+            // @todo do not clone when I understand RefCell for performance reason.
+            let activities = active_character.get_activities(&self.world);
+            let choice = self.choose(&activities, &active_character, turn.action_left);
+            self.decisions.push(Decision {
+                character_id: turn.character_id.clone(),
+                activity: choice,
+            });
+            let mut best_action = take_activity(activities, choice);
+            turn.action_left -= best_action.get_cost();
+            // collect effects of an activity as list of characters in the world
+            perform(
+                &mut best_action,
+                &active_character,
+                &mut self.world,
+                &mut facts,
+            );
+            if let Some(before) = before {
+                self.history
+                    .push(before, facts.iter().any(|f| f.reveals_roll()));
+            }
+        }
+        // an attack of opportunity may have knocked the character out.
+        if turn.action_left > 0 && self.world.get_character(&turn.character_id).hp > 0 {
+            self.turn = Some(turn);
+        } else {
+            self.turn = None;
+            facts.push(Fact::TurnEnd(turn.character_id));
+        }
+        facts
+    }
+
+    // what was done before the new turn can no longer be taken back.
+    fn next_turn(&mut self) -> Facts {
         let mut facts = Facts::new();
+        self.history = History::default();
         if self.is_encounter_done {
-            facts.info("OVER");
+            facts.push(Fact::EncounterOver);
             return facts;
         }
 
@@ -70,33 +273,27 @@ impl GameState {
         match tick {
             timeline::Tick::Over => {
                 self.is_encounter_done = true;
-                facts.info("OVER");
-                return facts;
+                facts.push(Fact::EncounterOver);
             }
 
             timeline::Tick::NewRound => {
-                self.world.tick_down();
-                facts.info(&format!("Start of Round {}", self.timeline.turn_counter));
-                return facts;
+                self.world.tick_down(&mut facts);
+                facts.push(Fact::RoundStart(self.timeline.turn_counter));
             }
             timeline::Tick::CharacterAction(c) => {
-                let mut action_left = 3;
-                while action_left > 0 {
-                    let active_character = self.world.get_character(&c).clone();
-                    // had to clone because activity needs at the same time:
-                    // an immutable ref to the character to know how much damage the attacker can do,
-                    // a mutable ref to the world to resolve the action.
-
-                    // @todo do not clone when I understand RefCell for performance reason.
-                    let mut best_action =
-                        select_best_action(&active_character, action_left, &self.world);
-                    action_left = action_left - best_action.get_cost();
-                    // collect effects of an activity as list of characters in the world
-                    best_action.resolve(&active_character, &mut self.world, &mut facts);
+                facts.push(Fact::TurnStart(c.clone()));
+                for status in self.world.get_mut_character(&c).start_turn() {
+                    facts.push(Fact::StatusRemoved {
+                        character_id: c.clone(),
+                        status,
+                    });
                 }
-                facts.info("OVER");
-                return facts;
+                self.turn = Some(Turn {
+                    character_id: c,
+                    action_left: 3,
+                });
             }
         }
+        facts
     }
 }
@@ -0,0 +1,138 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::position::Position;
+
+// sampling step along a line, in squares.
+const STEP: f64 = 0.05;
+// how far from the center of a square its corners are checked, slightly inside the square.
+const CORNER: f64 = 0.4;
+
+/**
+The terrain of the encounter grid, squares are open unless listed here.
+p457 walls block line of effect and line of sight, and creatures cannot enter them.
+p458 thick smoke blocks sight but not effect: creatures inside are concealed, the ones
+behind it are hidden.
+*/
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Map {
+    pub walls: BTreeSet<Position>,
+    pub smoke: BTreeSet<Position>,
+}
+
+/**
+The squares a line crosses between the centers of two squares, without them.
+A line along the edge of a square, or through its corner, crosses it.
+*/
+fn get_crossed_squares(from: &Position, to: (f64, f64)) -> BTreeSet<Position> {
+    let start = (from.x as f64, from.y as f64);
+    let (dx, dy) = (to.0 - start.0, to.1 - start.1);
+    let steps = ((dx.abs().max(dy.abs())) / STEP).ceil() as i64;
+    let mut squares = BTreeSet::new();
+    for i in 0..=steps {
+        let t = i as f64 / steps.max(1) as f64;
+        let (x, y) = (start.0 + t * dx, start.1 + t * dy);
+        // a point on the edge of two squares is in both of them.
+        for sx in [(x - 0.5 + 1e-9).ceil(), (x + 0.5 - 1e-9).floor()] {
+            for sy in [(y - 0.5 + 1e-9).ceil(), (y + 0.5 - 1e-9).floor()] {
+                squares.insert(Position::new(sx as i64, sy as i64));
+            }
+        }
+    }
+    let end = Position::new(to.0.round() as i64, to.1.round() as i64);
+    squares.remove(from);
+    squares.remove(&end);
+    squares
+}
+
+impl Map {
+    pub fn new() -> Self {
+        Map::default()
+    }
+
+    pub fn add_wall(mut self, from: Position, to: Position) -> Self {
+        self.walls.extend(get_rectangle(from, to));
+        self
+    }
+
+    pub fn add_smoke(mut self, from: Position, to: Position) -> Self {
+        self.smoke.extend(get_rectangle(from, to));
+        self
+    }
+
+    pub fn is_wall(&self, position: &Position) -> bool {
+        self.walls.contains(position)
+    }
+
+    // the squares crossed by the line between the centers of both squares.
+    pub fn get_center_line(&self, from: &Position, to: &Position) -> BTreeSet<Position> {
+        get_crossed_squares(from, (to.x as f64, to.y as f64))
+    }
+
+    fn is_blocked(&self, from: &Position, to: (f64, f64)) -> bool {
+        get_crossed_squares(from, to)
+            .iter()
+            .any(|s| self.walls.contains(s))
+    }
+
+    /**
+    p457 line of effect: an unblocked line from the center of the first square to the second one.
+    It is only blocked when the lines to the center and to the corners of the target all are.
+    */
+    pub fn has_line_of_effect(&self, from: &Position, to: &Position) -> bool {
+        let (x, y) = (to.x as f64, to.y as f64);
+        let points = [
+            (x, y),
+            (x - CORNER, y - CORNER),
+            (x + CORNER, y - CORNER),
+            (x - CORNER, y + CORNER),
+            (x + CORNER, y + CORNER),
+        ];
+        points.iter().any(|p| !self.is_blocked(from, *p))
+    }
+
+    // p457 line of sight: the line of effect, if no smoke is in the way.
+    pub fn has_line_of_sight(&self, from: &Position, to: &Position) -> bool {
+        self.has_line_of_effect(from, to)
+            && !self
+                .get_center_line(from, to)
+                .iter()
+                .any(|s| self.smoke.contains(s))
+    }
+}
+
+fn get_rectangle(from: Position, to: Position) -> Vec<Position> {
+    let mut squares = vec![];
+    for x in from.x.min(to.x)..=from.x.max(to.x) {
+        for y in from.y.min(to.y)..=from.y.max(to.y) {
+            squares.push(Position::new(x, y));
+        }
+    }
+    squares
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines() {
+        let origin = Position::new(0, 0);
+        let map = Map::new().add_wall(Position::new(2, 0), Position::new(2, 0));
+        assert_eq!(
+            map.get_center_line(&origin, &Position::new(2, 0)),
+            BTreeSet::from([Position::new(1, 0)])
+        );
+        // the wall hides the target completely
+        assert!(!map.has_line_of_effect(&origin, &Position::new(4, 0)));
+        assert!(map.has_line_of_effect(&origin, &Position::new(2, 3)));
+        // the center is behind the wall, but not one of the corners
+        assert!(map.is_blocked(&origin, (4.0, 1.0)));
+        assert!(map.has_line_of_effect(&origin, &Position::new(4, 1)));
+
+        let map = Map::new().add_smoke(Position::new(1, 0), Position::new(1, 0));
+        assert!(map.has_line_of_effect(&origin, &Position::new(3, 0)));
+        assert!(!map.has_line_of_sight(&origin, &Position::new(3, 0)));
+        assert!(map.has_line_of_sight(&origin, &Position::new(0, 3)));
+    }
+}
@@ -0,0 +1,84 @@
+use std::cmp::{max, min};
+
+use serde::{Deserialize, Serialize};
+
+/**
+A square on the encounter grid. One square is 5 feet wide. p421
+
+Distances follow the book: every second diagonal counts as 10 feet.
+*/
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub struct Position {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Position {
+    pub fn new(x: i64, y: i64) -> Self {
+        Position { x, y }
+    }
+
+    // p422 measuring distance: 5ft, 10ft, 5ft, 10ft... for diagonals
+    pub fn distance(&self, other: &Position) -> i64 {
+        let dx = (self.x - other.x).abs();
+        let dy = (self.y - other.y).abs();
+        let diagonals = min(dx, dy);
+        let straights = max(dx, dy) - diagonals;
+        (straights + diagonals + diagonals / 2) * 5
+    }
+
+    // p455 a 10-foot reach lets you reach 2 squares away diagonally, even if it is 15 feet.
+    pub fn is_within_reach(&self, other: &Position, reach: i64) -> bool {
+        if reach == 10 {
+            let squares = max((self.x - other.x).abs(), (self.y - other.y).abs());
+            return squares <= 2;
+        }
+        self.distance(other) <= reach
+    }
+
+    pub fn neighbours(&self) -> Vec<Position> {
+        let mut out = vec![];
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                if dx != 0 || dy != 0 {
+                    out.push(Position::new(self.x + dx, self.y + dy));
+                }
+            }
+        }
+        out
+    }
+
+    /// Next square on the way to `target`, moving diagonally when possible.
+    pub fn step_towards(&self, target: &Position) -> Position {
+        Position {
+            x: self.x + (target.x - self.x).signum(),
+            y: self.y + (target.y - self.y).signum(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance() {
+        let origin = Position::new(0, 0);
+        assert_eq!(origin.distance(&Position::new(0, 3)), 15);
+        assert_eq!(origin.distance(&Position::new(1, 1)), 5);
+        assert_eq!(origin.distance(&Position::new(2, 2)), 15);
+        assert_eq!(origin.distance(&Position::new(3, 3)), 20);
+        assert_eq!(origin.distance(&Position::new(4, 1)), 20);
+    }
+
+    #[test]
+    fn reach() {
+        let origin = Position::new(0, 0);
+        assert!(origin.is_within_reach(&Position::new(1, 1), 5));
+        assert!(!origin.is_within_reach(&Position::new(2, 0), 5));
+        assert!(origin.is_within_reach(&Position::new(2, 2), 10));
+        assert!(!origin.is_within_reach(&Position::new(3, 0), 10));
+    }
+}
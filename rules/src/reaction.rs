@@ -0,0 +1,261 @@
+use crate::{
+    character::{creature::CreatureAbility, Character},
+    fact::{self, Fact},
+    timeline::CharacterId,
+    world::World,
+};
+
+mod attack_of_opportunity;
+mod shield_block;
+
+use std::fmt;
+
+/**
+Trigger points raised by actions, that other creatures may answer with their reaction. p470
+A reaction can modify the trigger, for instance to reduce the damage about to be taken.
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub enum Trigger {
+    // a creature uses an action with the manipulate or move trait.
+    ManipulateOrMove {
+        actor: CharacterId,
+    },
+    // a creature would take damage.
+    Damage {
+        source: CharacterId,
+        target: CharacterId,
+        amount: i64,
+    },
+}
+
+// Value AI: same scale as activities.
+pub trait Reaction: fmt::Debug {
+    fn can_react(&self, reactor: &Character, trigger: &Trigger, world: &World) -> bool;
+    fn ai_playing_value(&self, reactor: &Character, trigger: &Trigger, world: &World) -> i64;
+    fn resolve(
+        &mut self,
+        reactor: &Character,
+        trigger: &mut Trigger,
+        world: &mut World,
+        facts: &mut fact::Facts,
+    );
+    fn get_name(&self) -> &str;
+}
+
+/**
+Right now every character knows every reaction, the same way they know every activity.
+Creatures only know the ones listed in their stat block.
+*/
+impl Character {
+    fn get_reactions(&self) -> Vec<Box<dyn Reaction>> {
+        let knows = |ability| {
+            self.creature
+                .as_ref()
+                .is_none_or(|c| c.has_ability(ability))
+        };
+        let mut reactions: Vec<Box<dyn Reaction>> = vec![];
+        if knows(CreatureAbility::AttackOfOpportunity) {
+            reactions.push(Box::new(attack_of_opportunity::Action::new()));
+        }
+        if knows(CreatureAbility::ShieldBlock) {
+            reactions.push(Box::new(shield_block::Action::new()));
+        }
+        reactions
+    }
+}
+
+/**
+Decides which of the eligible reactions a creature uses, if any.
+The AI implementation is the default, a player controlled implementation can ask the UI instead.
+*/
+pub trait ReactionPolicy: Send + Sync {
+    fn choose(
+        &self,
+        reactor: &Character,
+        eligible: Vec<Box<dyn Reaction>>,
+        trigger: &Trigger,
+        world: &World,
+    ) -> Option<Box<dyn Reaction>>;
+}
+
+pub struct AiReactionPolicy;
+
+impl ReactionPolicy for AiReactionPolicy {
+    fn choose(
+        &self,
+        reactor: &Character,
+        eligible: Vec<Box<dyn Reaction>>,
+        trigger: &Trigger,
+        world: &World,
+    ) -> Option<Box<dyn Reaction>> {
+        eligible
+            .into_iter()
+            .map(|r| (r.ai_playing_value(reactor, trigger, world), r))
+            .filter(|(value, _)| *value > 0)
+            .max_by(|a, b| a.0.cmp(&b.0))
+            .map(|(_, r)| r)
+    }
+}
+
+/**
+Offers the trigger to every conscious creature that still has its reaction this round.
+Creatures are visited in a stable order so that the outcome does not depend on the HashMap.
+*/
+pub fn offer_reactions(trigger: &mut Trigger, world: &mut World, facts: &mut fact::Facts) {
+    let mut reactor_ids: Vec<CharacterId> = world
+        .get_characters()
+        .iter()
+        .filter(|c| c.hp > 0 && c.reaction_available)
+        .map(|c| c.id.clone())
+        .collect();
+    reactor_ids.sort();
+
+    for id in reactor_ids {
+        let reactor = world.get_character(&id).clone();
+        // a previous reaction may have knocked this one out.
+        if reactor.hp <= 0 || !reactor.reaction_available {
+            continue;
+        }
+        let eligible: Vec<Box<dyn Reaction>> = reactor
+            .get_reactions()
+            .into_iter()
+            .filter(|r| r.can_react(&reactor, trigger, world))
+            .collect();
+        if eligible.is_empty() {
+            continue;
+        }
+        let chosen = world
+            .reaction_policy
+            .choose(&reactor, eligible, trigger, world);
+        if let Some(mut reaction) = chosen {
+            world.get_mut_character(&id).reaction_available = false;
+            let trigger_id = match trigger {
+                Trigger::ManipulateOrMove { actor } => actor.clone(),
+                Trigger::Damage { source, .. } => source.clone(),
+            };
+            facts.push(Fact::Reaction {
+                character_id: id.clone(),
+                name: String::from(reaction.get_name()),
+                trigger_id,
+            });
+            reaction.resolve(&reactor, trigger, world, facts);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        activity::{perform, Activity},
+        character::Character,
+        damage::{apply_damage, Damage},
+        inventory::{release, wield},
+        position::Position,
+        status::{Duration, StatusEffect, StatusType},
+        utils::get_shield,
+    };
+
+    fn add_unit(world: &mut World, name: &str, party: &str, position: Position) -> CharacterId {
+        let mut character = Character::new(String::from(name), String::from(party), 50);
+        character.position = position;
+        character.loadout.armor = world.spawn_item("leather").ok();
+        let id = character.id.clone();
+        world.characters.insert(id.clone(), character);
+        let greatsword = world.spawn_item("greatsword").unwrap();
+        wield(&id, &greatsword, world).unwrap();
+        id
+    }
+
+    fn setup() -> (World, CharacterId, CharacterId) {
+        let mut world = World::new();
+        let guard = add_unit(&mut world, "Guard", "knights", Position::new(0, 0));
+        let thief = add_unit(&mut world, "Thief", "thieves", Position::new(1, 0));
+        (world, guard, thief)
+    }
+
+    #[derive(Debug)]
+    struct Fidget;
+    impl Activity for Fidget {
+        fn ai_utility(&self, _: &Character, _: &World) -> Option<crate::ai::Utility> {
+            None
+        }
+        fn resolve(&mut self, _: &Character, _: &mut World, _: &mut fact::Facts) {}
+        fn get_name(&self) -> &str {
+            "Fidget"
+        }
+        fn get_traits(&self) -> Vec<crate::activity::ActionTrait> {
+            vec![crate::activity::ActionTrait::Manipulate]
+        }
+    }
+
+    #[test]
+    fn attack_of_opportunity() {
+        let (mut world, guard, thief) = setup();
+        let mut facts = fact::Facts::new();
+        let mut fidget: Box<dyn Activity> = Box::new(Fidget);
+        let actor = world.get_character(&thief).clone();
+
+        perform(&mut fidget, &actor, &mut world, &mut facts);
+        assert!(world.get_character(&thief).hp < 50);
+        assert!(!world.get_character(&guard).reaction_available);
+
+        // only one reaction per round
+        let hp = world.get_character(&thief).hp;
+        perform(&mut fidget, &actor, &mut world, &mut facts);
+        assert_eq!(world.get_character(&thief).hp, hp);
+
+        world.get_mut_character(&guard).start_turn();
+        assert!(world.get_character(&guard).reaction_available);
+    }
+
+    #[test]
+    fn attack_of_opportunity_out_of_reach() {
+        let (mut world, guard, thief) = setup();
+        world.get_mut_character(&thief).position = Position::new(3, 0);
+        let mut facts = fact::Facts::new();
+        let mut fidget: Box<dyn Activity> = Box::new(Fidget);
+        let actor = world.get_character(&thief).clone();
+        perform(&mut fidget, &actor, &mut world, &mut facts);
+        assert_eq!(world.get_character(&thief).hp, 50);
+        assert!(world.get_character(&guard).reaction_available);
+    }
+
+    #[test]
+    fn shield_block() {
+        let (mut world, guard, thief) = setup();
+        // the greatsword is dropped to hold the shield
+        let greatsword = world
+            .get_character(&guard)
+            .loadout
+            .right_hand
+            .clone()
+            .unwrap();
+        release(&guard, &greatsword, &mut world).unwrap();
+        let shield = world.spawn_item("steel-shield").unwrap();
+        wield(&guard, &shield, &mut world).unwrap();
+        let mut facts = fact::Facts::new();
+        assert_eq!(
+            apply_damage(&thief, &guard, Damage::new(8), &mut world, &mut facts),
+            8
+        );
+
+        world.get_mut_character(&guard).add_status(StatusEffect {
+            duration: Duration::StartOfNextTurn,
+            status_type: StatusType::ShieldRaised,
+        });
+        assert_eq!(
+            apply_damage(&thief, &guard, Damage::new(8), &mut world, &mut facts),
+            3
+        );
+        assert_eq!(world.get_character(&guard).hp, 50 - 8 - 3);
+        let guard_character = world.get_character(&guard).clone();
+        assert_eq!(get_shield(&guard_character, &world).unwrap().hp, 17);
+
+        // the raised shield goes away at the start of the next turn
+        world.get_mut_character(&guard).start_turn();
+        assert!(!world
+            .get_character(&guard)
+            .has_status(StatusType::ShieldRaised));
+    }
+}
@@ -0,0 +1,54 @@
+use crate::{
+    activity::attack::strike, character::Character, fact, utils::get_melee_weapon, world::World,
+};
+
+use super::{Reaction, Trigger};
+
+// p142 Attack of Opportunity: a melee Strike against a creature within reach
+// that uses a manipulate or move action.
+#[derive(Clone, Debug)]
+pub struct Action;
+
+impl Action {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Reaction for Action {
+    fn can_react(&self, reactor: &Character, trigger: &Trigger, world: &World) -> bool {
+        let actor_id = match trigger {
+            Trigger::ManipulateOrMove { actor } => actor,
+            _ => return false,
+        };
+        let actor = world.get_character(actor_id);
+        if actor.party == reactor.party || actor.hp <= 0 {
+            return false;
+        }
+        get_melee_weapon(reactor, world).is_some_and(|(weapon, _)| {
+            reactor
+                .position
+                .is_within_reach(&actor.position, weapon.get_reach())
+        })
+    }
+
+    fn ai_playing_value(&self, _reactor: &Character, _trigger: &Trigger, _world: &World) -> i64 {
+        15
+    }
+
+    fn resolve(
+        &mut self,
+        reactor: &Character,
+        trigger: &mut Trigger,
+        world: &mut World,
+        facts: &mut fact::Facts,
+    ) {
+        if let Trigger::ManipulateOrMove { actor } = trigger {
+            strike(reactor, actor, world, facts);
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        "Attack of Opportunity"
+    }
+}
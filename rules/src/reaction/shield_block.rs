@@ -0,0 +1,69 @@
+use crate::{
+    character::Character,
+    fact::{self, Fact},
+    status::{end_status, StatusType},
+    utils::{get_mut_shield, get_shield},
+    world::World,
+};
+
+use super::{Reaction, Trigger};
+
+// p266 Shield Block: when you would take damage while your shield is raised,
+// the shield absorbs damage up to its hardness and shares the rest with you.
+#[derive(Clone, Debug)]
+pub struct Action;
+
+impl Action {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Reaction for Action {
+    fn can_react(&self, reactor: &Character, trigger: &Trigger, world: &World) -> bool {
+        match trigger {
+            Trigger::Damage { target, amount, .. } => {
+                *target == reactor.id
+                    && *amount > 0
+                    && reactor.has_status(StatusType::ShieldRaised)
+                    && get_shield(reactor, world).is_some_and(|s| !s.is_broken())
+            }
+            _ => false,
+        }
+    }
+
+    fn ai_playing_value(&self, _reactor: &Character, _trigger: &Trigger, _world: &World) -> i64 {
+        10
+    }
+
+    fn resolve(
+        &mut self,
+        reactor: &Character,
+        trigger: &mut Trigger,
+        world: &mut World,
+        facts: &mut fact::Facts,
+    ) {
+        if let Trigger::Damage { amount, .. } = trigger {
+            let shield = match get_mut_shield(reactor, world) {
+                Some(shield) => shield,
+                None => return,
+            };
+            let remaining = shield.block(*amount);
+            let broken = shield.is_broken();
+            facts.push(Fact::ShieldBlock {
+                character_id: reactor.id.clone(),
+                shield: shield.info.name.clone(),
+                damage: remaining,
+                broken,
+            });
+            if broken {
+                end_status(&reactor.id, StatusType::ShieldRaised, world, facts);
+            }
+            *amount = remaining;
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        "Shield Block"
+    }
+}
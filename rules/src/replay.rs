@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    fact::Facts,
+    save::{from_ron, to_ron, SaveError},
+    timeline::CharacterId,
+    world::World,
+    GameState,
+};
+
+/**
+An activity chosen by a character, by its index among the activities it had at that time.
+None is a Pass.
+*/
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Decision {
+    pub character_id: CharacterId,
+    pub activity: Option<usize>,
+}
+
+/**
+An encounter as it was played: the world before initiative, the seed of the dice and the decisions.
+Played again, it yields the same facts.
+A game is recorded by starting it from the replay, and recording it once the players are done.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Replay {
+    pub seed: u64,
+    pub world: World,
+    pub decisions: Vec<Decision>,
+}
+
+impl Replay {
+    pub fn new(world: World, seed: u64) -> Self {
+        Replay {
+            seed,
+            world,
+            decisions: vec![],
+        }
+    }
+
+    // seeds the dice of the current thread and rolls initiative, the recorded decisions are planned.
+    pub fn start(&self) -> GameState {
+        dice::seed(self.seed);
+        let mut game_state = GameState::from_world(self.world.clone());
+        for decision in &self.decisions {
+            game_state.decide(decision.clone());
+        }
+        game_state
+    }
+
+    // keeps the decisions taken so far in a game started from this replay.
+    pub fn record(&mut self, game_state: &GameState) {
+        self.decisions = game_state.get_decisions().to_vec();
+    }
+
+    pub fn play(&self, rounds_limit: i64) -> Facts {
+        self.start().run(rounds_limit)
+    }
+
+    pub fn save(&self) -> Result<String, SaveError> {
+        to_ron(self)
+    }
+
+    pub fn load(content: &str) -> Result<Replay, SaveError> {
+        from_ron(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fact::Fact, position::Position};
+
+    #[test]
+    fn replay() {
+        let (world, _, kobold) = World::orc_duel("kobold-warrior", "kobolds", Position::new(0, 12));
+        let mut replay = Replay::new(world, 11);
+        let mut game_state = replay.start();
+        // the kobold player holds its ground for the first round.
+        game_state.decide(Decision {
+            character_id: kobold.clone(),
+            activity: None,
+        });
+        let facts = game_state.run(5);
+        replay.record(&game_state);
+        assert_eq!(replay.decisions.len(), game_state.get_decisions().len());
+        assert!(replay.decisions.contains(&Decision {
+            character_id: kobold.clone(),
+            activity: None,
+        }));
+
+        assert_eq!(replay.play(5), facts);
+        let loaded = Replay::load(&replay.save().unwrap()).unwrap();
+        assert_eq!(loaded.play(5), facts);
+        // the AI alone plays another game.
+        assert_ne!(Replay::new(loaded.world, 11).play(5), facts);
+        assert!(facts.iter().any(|f| matches!(f, Fact::Activity { .. })));
+    }
+}
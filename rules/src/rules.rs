@@ -1,26 +1,42 @@
 use dice::Roll;
+use serde::{Deserialize, Serialize};
 
 use self::{
-    deadly::DeadlyRule, finesse::FinessRule, passthrough::Passthrough, propulsive::PropulsiveRule,
-    str_mod_damage::StrengthModDamageRule, striking::StrikingRule,
+    deadly::DeadlyRule,
+    finesse::FinessRule,
+    passthrough::Passthrough,
+    property_rune::{EnergyRuneRule, HolyRule},
+    propulsive::PropulsiveRule,
+    str_mod_damage::StrengthModDamageRule,
+    striking::StrikingRule,
 };
 use crate::character::Character;
+use crate::item::weapon::{DamageType, WeaponItem};
 use crate::world::World;
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 mod deadly;
 mod finesse;
 mod passthrough;
+mod property_rune;
 mod propulsive;
 mod str_mod_damage;
 mod striking;
 
-#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Serialize, Deserialize)]
 pub enum Rule {
     Propulsive,
     Finesse,
     Striking(usize),
     Deadly(usize),
+    Reach,
+    // p283 the damage die when wielded in two hands.
+    TwoHand(usize),
+    Flaming,
+    Frost,
+    Shock,
+    Holy,
+    GhostTouch,
     StrengthModDamage,
     Dwarf,
     Elf,
@@ -31,6 +47,8 @@ pub enum Rule {
     ColdIron,
     Adamantine,
     Silver,
+    Evil,
+    Incorporeal,
 }
 
 // pub enum PrevActionResult {
@@ -39,20 +57,36 @@ pub enum Rule {
 //     AttackHit,
 // }
 
-pub trait RuleImplementation {
-    fn dmg_pre_crit(&self, r: Roll, _: &Character, _: &World) -> Roll {
+// What rules know about the Strike being resolved.
+pub struct CombatContext<'a> {
+    pub source: &'a Character,
+    pub target: &'a Character,
+    // the weapon of the Strike, not always the first one wielded.
+    pub weapon: &'a WeaponItem,
+    pub world: &'a World,
+    pub is_critical: bool,
+}
+
+// Rules hold no state, so that the rulebook can be shared between simulations.
+pub trait RuleImplementation: Send + Sync {
+    fn dmg_pre_crit(&self, r: Roll, _: &CombatContext) -> Roll {
         r
     }
-    fn dmg_post_crit(&self, r: Roll, _: &Character, _: &World) -> Roll {
+    fn dmg_post_crit(&self, r: Roll, _: &CombatContext) -> Roll {
         r
     }
-    fn dmg_reduction(&self, r: Roll, _: &Character, _: &World) -> Roll {
+    fn dmg_reduction(&self, r: Roll, _: &CombatContext) -> Roll {
         r
     }
+    // damage of its own type, resisted apart from the weapon damage, like the fire of a flaming rune.
+    fn extra_damage(&self, _: &CombatContext) -> Option<(DamageType, Roll)> {
+        None
+    }
 }
 
+#[derive(Clone)]
 pub struct RuleBook {
-    pub rules: HashMap<Rule, Box<dyn RuleImplementation>>,
+    pub rules: HashMap<Rule, Arc<dyn RuleImplementation>>,
 }
 
 impl RuleBook {
@@ -62,7 +96,7 @@ impl RuleBook {
         }
     }
     pub fn load_rule(&mut self, r: Rule, ri: Box<dyn RuleImplementation>) {
-        self.rules.insert(r, ri);
+        self.rules.insert(r, Arc::from(ri));
     }
 
     pub fn load_rules(&mut self) {
@@ -75,9 +109,43 @@ impl RuleBook {
         self.load_rule(Rule::Striking(1), Box::new(StrikingRule { level: 1 }));
         self.load_rule(Rule::Striking(2), Box::new(StrikingRule { level: 2 }));
         self.load_rule(Rule::Striking(3), Box::new(StrikingRule { level: 3 }));
-        self.load_rule(Rule::Deadly(1), Box::new(DeadlyRule { die: 1 }));
-        self.load_rule(Rule::Deadly(2), Box::new(DeadlyRule { die: 2 }));
-        self.load_rule(Rule::Deadly(3), Box::new(DeadlyRule { die: 3 }));
+        for die in [6, 8, 10, 12] {
+            self.load_rule(Rule::Deadly(die), Box::new(DeadlyRule { die }));
+        }
+        self.load_rule(Rule::Reach, Box::new(Passthrough {}));
+        // the damage die is picked when rolling the weapon damage.
+        for die in [8, 10, 12] {
+            self.load_rule(Rule::TwoHand(die), Box::new(Passthrough {}));
+        }
+
+        // property runes p581
+        self.load_rule(
+            Rule::Flaming,
+            Box::new(EnergyRuneRule {
+                tag: "flaming",
+                faces: 6,
+                damage_type: DamageType::Fire,
+            }),
+        );
+        self.load_rule(
+            Rule::Frost,
+            Box::new(EnergyRuneRule {
+                tag: "frost",
+                faces: 6,
+                damage_type: DamageType::Cold,
+            }),
+        );
+        self.load_rule(
+            Rule::Shock,
+            Box::new(EnergyRuneRule {
+                tag: "shock",
+                faces: 6,
+                damage_type: DamageType::Electricity,
+            }),
+        );
+        self.load_rule(Rule::Holy, Box::new(HolyRule {}));
+        // the damage pipeline checks for ghost touch when it applies resistances.
+        self.load_rule(Rule::GhostTouch, Box::new(Passthrough {}));
 
         // races
         self.load_rule(Rule::Dwarf, Box::new(Passthrough {}));
@@ -91,21 +159,23 @@ impl RuleBook {
         self.load_rule(Rule::Adamantine, Box::new(Passthrough {}));
         self.load_rule(Rule::ColdIron, Box::new(Passthrough {}));
         self.load_rule(Rule::Silver, Box::new(Passthrough {}));
+
+        // creature traits
+        self.load_rule(Rule::Evil, Box::new(Passthrough {}));
+        self.load_rule(Rule::Incorporeal, Box::new(Passthrough {}));
     }
 
-    // @todo group in a CombatContext all the fields, add is_critical, prev_action_result, action_nb
+    // @todo add prev_action_result, action_nb to the CombatContext
     pub fn dmg_pre_crit(
         &self,
         active_rules: &Vec<Rule>,
         mut roll: Roll,
-        character: &Character,
-        world: &World,
+        ctx: &CombatContext,
     ) -> Roll {
         for rule in active_rules {
-            let maybe_rule = self.rules.get(&rule);
-            match maybe_rule {
-                None => eprintln!("missing rule from the rulebook"),
-                Some(rule_impl) => roll = rule_impl.dmg_pre_crit(roll, character, world),
+            // rules missing from the rulebook leave the roll as it is
+            if let Some(rule_impl) = self.rules.get(rule) {
+                roll = rule_impl.dmg_pre_crit(roll, ctx);
             }
         }
         roll
@@ -115,33 +185,51 @@ impl RuleBook {
         &self,
         active_rules: &Vec<Rule>,
         mut roll: Roll,
-        character: &Character,
-        world: &World,
+        ctx: &CombatContext,
     ) -> Roll {
         for rule in active_rules {
-            let maybe_rule = self.rules.get(&rule);
-            match maybe_rule {
-                None => eprintln!("missing rule from the rulebook"),
-                Some(rule_impl) => roll = rule_impl.dmg_post_crit(roll, character, world),
+            // rules missing from the rulebook leave the roll as it is
+            if let Some(rule_impl) = self.rules.get(rule) {
+                roll = rule_impl.dmg_post_crit(roll, ctx);
             }
         }
         roll
     }
+
+    pub fn extra_damage(
+        &self,
+        active_rules: &[Rule],
+        ctx: &CombatContext,
+    ) -> Vec<(DamageType, Roll)> {
+        active_rules
+            .iter()
+            .filter_map(|rule| self.rules.get(rule))
+            .filter_map(|rule_impl| rule_impl.extra_damage(ctx))
+            .collect()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::character::Character;
+    use crate::{character::Character, item::weapon::unarmed};
 
     #[test]
     fn no_rules() {
         let c = Character::default(); // char should be registered, but hey
         let w = World::new();
+        let fist = unarmed();
 
         let r = RuleBook::new();
+        let ctx = CombatContext {
+            source: &c,
+            target: &c,
+            weapon: &fist,
+            world: &w,
+            is_critical: false,
+        };
         assert_eq!(
-            r.dmg_pre_crit(&vec![], Roll::new("", 1, 6, 2), &c, &w),
+            r.dmg_pre_crit(&vec![], Roll::new("", 1, 6, 2), &ctx),
             Roll::new("", 1, 6, 2)
         );
     }
@@ -150,6 +238,7 @@ mod tests {
     fn finesse() {
         let mut c = Character::default(); // char should be registered, but hey
         let w = World::new();
+        let fist = unarmed();
         let mut r = RuleBook::new();
 
         r.load_rule(Rule::Finesse, Box::new(FinessRule {}));
@@ -157,16 +246,68 @@ mod tests {
         c.ability_score.strength = 12;
         c.ability_score.dexterity = 18;
         let roll = Roll::from("1d6") + Roll::flat("str", 1);
+        let ctx = CombatContext {
+            source: &c,
+            target: &c,
+            weapon: &fist,
+            world: &w,
+            is_critical: false,
+        };
         assert_eq!(
-            r.dmg_pre_crit(&active_rules, roll, &c, &w),
+            r.dmg_pre_crit(&active_rules, roll, &ctx),
             (Roll::from("1d6") + Roll::flat("finesse", 4))
         );
 
         c.ability_score.dexterity = 8;
         let roll = Roll::from("1d6+1") + Roll::flat("str", 1);
+        let ctx = CombatContext {
+            source: &c,
+            target: &c,
+            weapon: &fist,
+            world: &w,
+            is_critical: false,
+        };
         assert_eq!(
-            r.dmg_pre_crit(&active_rules, roll, &c, &w),
+            r.dmg_pre_crit(&active_rules, roll, &ctx),
             Roll::from("1d6+1") + Roll::flat("str", 1)
         )
     }
+
+    #[test]
+    fn holy() {
+        let c = Character::default();
+        let mut fiend = Character::default();
+        let w = World::new();
+        let fist = unarmed();
+        let mut r = RuleBook::new();
+
+        r.load_rule(Rule::Holy, Box::new(HolyRule {}));
+        let active_rules = vec![Rule::Holy];
+        let ctx = CombatContext {
+            source: &c,
+            target: &fiend,
+            weapon: &fist,
+            world: &w,
+            is_critical: false,
+        };
+        assert_eq!(r.extra_damage(&active_rules, &ctx), vec![]);
+        // the weapon damage is left as it is, the good damage is resisted on its own.
+        assert_eq!(
+            r.dmg_pre_crit(&active_rules, Roll::from("1d12"), &ctx),
+            Roll::from("1d12")
+        );
+
+        fiend.traits.push(Rule::Evil);
+        let ctx = CombatContext {
+            source: &c,
+            target: &fiend,
+            weapon: &fist,
+            world: &w,
+            is_critical: false,
+        };
+        assert_eq!(
+            r.extra_damage(&active_rules, &ctx),
+            vec![(DamageType::Good, Roll::d("holy", 1, 6))]
+        );
+    }
 }
@@ -1,14 +1,17 @@
 use dice::Roll;
 
-use crate::{character::Character, rules::RuleImplementation, world::World};
+use crate::rules::{CombatContext, RuleImplementation};
 
 pub struct DeadlyRule {
     pub die: usize,
 }
 
 impl RuleImplementation for DeadlyRule {
-    fn dmg_post_crit(&self, r: Roll, _: &Character, _: &World) -> Roll {
-        // @todo should only occur when it is a crit and boost roll based on striking level.
+    fn dmg_post_crit(&self, r: Roll, ctx: &CombatContext) -> Roll {
+        if !ctx.is_critical {
+            return r;
+        }
+        // @todo boost roll based on striking level.
         // let nb_to_roll = match weapon.damage.striking_level {
         //     0 => 1,
         //     1 => 1,
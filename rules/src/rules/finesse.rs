@@ -1,12 +1,14 @@
 use dice::Roll;
 
 use crate::{
-    character::Character, rules::RuleImplementation, timeline::get_modifier, world::World,
+    rules::{CombatContext, RuleImplementation},
+    timeline::get_modifier,
 };
 
 pub struct FinessRule {}
 impl RuleImplementation for FinessRule {
-    fn dmg_pre_crit(&self, r: Roll, c: &Character, _: &World) -> Roll {
+    fn dmg_pre_crit(&self, r: Roll, ctx: &CombatContext) -> Roll {
+        let c = ctx.source;
         let str_mod = get_modifier(c.ability_score.strength);
         let dex_mod = get_modifier(c.ability_score.dexterity);
         if str_mod < dex_mod {
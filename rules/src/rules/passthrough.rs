@@ -1,10 +1,10 @@
 use dice::Roll;
 
-use crate::{character::Character, rules::RuleImplementation, world::World};
+use crate::rules::{CombatContext, RuleImplementation};
 
 pub struct Passthrough {}
 impl RuleImplementation for Passthrough {
-    fn dmg_pre_crit(&self, r: Roll, _: &Character, _: &World) -> Roll {
+    fn dmg_pre_crit(&self, r: Roll, _: &CombatContext) -> Roll {
         r
     }
 }
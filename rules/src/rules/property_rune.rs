@@ -0,0 +1,32 @@
+use dice::Roll;
+
+use crate::{
+    item::weapon::DamageType,
+    rules::{CombatContext, Rule, RuleImplementation},
+};
+
+// p581 flaming, frost and shock: an extra die of energy damage on every hit.
+pub struct EnergyRuneRule {
+    pub tag: &'static str,
+    pub faces: i64,
+    pub damage_type: DamageType,
+}
+
+impl RuleImplementation for EnergyRuneRule {
+    fn extra_damage(&self, _: &CombatContext) -> Option<(DamageType, Roll)> {
+        Some((self.damage_type, Roll::d(self.tag, 1, self.faces)))
+    }
+}
+
+// p582 holy: 1d6 good damage against evil creatures.
+pub struct HolyRule {}
+
+impl RuleImplementation for HolyRule {
+    fn extra_damage(&self, ctx: &CombatContext) -> Option<(DamageType, Roll)> {
+        if ctx.target.traits.contains(&Rule::Evil) {
+            Some((DamageType::Good, Roll::d("holy", 1, 6)))
+        } else {
+            None
+        }
+    }
+}
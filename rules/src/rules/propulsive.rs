@@ -1,12 +1,14 @@
 use dice::Roll;
 
 use crate::{
-    character::Character, rules::RuleImplementation, timeline::get_modifier, world::World,
+    rules::{CombatContext, RuleImplementation},
+    timeline::get_modifier,
 };
 
 pub struct PropulsiveRule {}
 impl RuleImplementation for PropulsiveRule {
-    fn dmg_pre_crit(&self, r: Roll, c: &Character, _: &World) -> Roll {
+    fn dmg_pre_crit(&self, r: Roll, ctx: &CombatContext) -> Roll {
+        let c = ctx.source;
         let str_mod = get_modifier(c.ability_score.strength);
         if str_mod >= 0 {
             //i64 divide rounds down,as per rule p444 Chapter 9
@@ -1,18 +1,17 @@
 use dice::Roll;
 
 use crate::{
-    character::Character, rules::RuleImplementation, timeline::get_modifier,
-    utils::get_active_weapon, world::World,
+    rules::{CombatContext, RuleImplementation},
+    timeline::get_modifier,
 };
 
 pub struct StrengthModDamageRule {}
 impl RuleImplementation for StrengthModDamageRule {
-    fn dmg_pre_crit(&self, r: Roll, c: &Character, w: &World) -> Roll {
-        let weapon = get_active_weapon(c, w);
-        if weapon.is_ranged {
+    fn dmg_pre_crit(&self, r: Roll, ctx: &CombatContext) -> Roll {
+        if ctx.weapon.is_ranged {
             return r;
         }
-        let str_mod = get_modifier(c.ability_score.strength);
+        let str_mod = get_modifier(ctx.source.ability_score.strength);
         r + Roll::flat("str", str_mod)
     }
 }
@@ -1,13 +1,13 @@
 use dice::Roll;
 
-use crate::{character::Character, rules::RuleImplementation, world::World};
+use crate::rules::{CombatContext, RuleImplementation};
 
 pub struct StrikingRule {
     pub level: usize,
 }
 
 impl RuleImplementation for StrikingRule {
-    fn dmg_pre_crit(&self, r: Roll, _: &Character, _: &World) -> Roll {
+    fn dmg_pre_crit(&self, r: Roll, _: &CombatContext) -> Roll {
         let extra_die = if r.get_bonus("weapon").nb_dice > 0 {
             r.get_bonus("weapon").face
         } else {
@@ -0,0 +1,120 @@
+use std::{fmt, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::GameState;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SaveError {
+    Io(String),
+    Serialize(String),
+    Parse(String),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(e) => write!(f, "cannot access the save: {}", e),
+            SaveError::Serialize(e) => write!(f, "cannot save: {}", e),
+            SaveError::Parse(e) => write!(f, "malformed save: {}", e),
+        }
+    }
+}
+
+// the dice are saved with the game, so that a loaded game gets the rolls it would have had.
+#[derive(Serialize, Deserialize)]
+struct Save<G> {
+    dice: Option<u64>,
+    game_state: G,
+}
+
+pub(crate) fn to_ron<T: Serialize>(value: &T) -> Result<String, SaveError> {
+    ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+        .map_err(|e| SaveError::Serialize(e.to_string()))
+}
+
+pub(crate) fn from_ron<T: for<'de> Deserialize<'de>>(content: &str) -> Result<T, SaveError> {
+    ron::from_str(content).map_err(|e| SaveError::Parse(e.to_string()))
+}
+
+/**
+Saving works at any time, in the middle of an encounter too.
+The state of the dice is the one of the current thread.
+*/
+impl GameState {
+    pub fn save(&self) -> Result<String, SaveError> {
+        to_ron(&Save {
+            dice: dice::get_state(),
+            game_state: self,
+        })
+    }
+
+    // the dice of the current thread are restored too.
+    pub fn load(content: &str) -> Result<GameState, SaveError> {
+        let save: Save<GameState> = from_ron(content)?;
+        dice::set_state(save.dice);
+        Ok(save.game_state)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), SaveError> {
+        fs::write(path, self.save()?)
+            .map_err(|e| SaveError::Io(format!("{}: {}", path.display(), e)))
+    }
+
+    pub fn load_from(path: &Path) -> Result<GameState, SaveError> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| SaveError::Io(format!("{}: {}", path.display(), e)))?;
+        GameState::load(&content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fact::Facts, position::Position, world::World};
+
+    fn ambush() -> GameState {
+        let (mut world, _, _) = World::orc_duel("kobold-warrior", "kobolds", Position::new(0, 4));
+        world
+            .spawn_creature("kobold-warrior", "kobolds", Position::new(1, 4))
+            .unwrap();
+        GameState::from_world(world)
+    }
+
+    fn play(game_state: &mut GameState, ticks: usize) -> Facts {
+        let mut facts = Facts::new();
+        for _ in 0..ticks {
+            facts.extend(game_state.tick());
+        }
+        facts
+    }
+
+    #[test]
+    fn save_and_load() {
+        dice::seed(3);
+        let mut game_state = ambush();
+        play(&mut game_state, 4);
+        let save = game_state.save().unwrap();
+        let round = game_state.get_round();
+        let rest = game_state.run(20);
+        assert!(!rest.is_empty());
+
+        let mut loaded = GameState::load(&save).unwrap();
+        assert_eq!(loaded.get_round(), round);
+        assert_eq!(loaded.run(20), rest);
+        // the ids are numbered by the world, in the order of the spawns.
+        assert_eq!(
+            loaded.get_world().characters.keys().collect::<Vec<_>>(),
+            vec!["Kobold Warrior-2", "Kobold Warrior-3", "Orc Brute-1"]
+        );
+
+        assert!(matches!(
+            GameState::load("(dice: None)"),
+            Err(SaveError::Parse(_))
+        ));
+        assert!(matches!(
+            GameState::load_from(Path::new("/nowhere/save.ron")),
+            Err(SaveError::Io(_))
+        ));
+    }
+}
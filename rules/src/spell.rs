@@ -0,0 +1,585 @@
+use std::{collections::BTreeMap, fmt, fs, path::Path};
+
+use dice::Roll;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    area::{find_creatures, Affects, Area},
+    character::Character,
+    check::DegreeOfSuccess,
+    defense::Save,
+    item::{catalog::parse_dice, weapon::DamageType},
+    status::StatusType,
+    world::World,
+};
+
+// the Core Rulebook spells, shipped with the crate.
+const CORE_SPELLS: &str = include_str!("../data/spells.ron");
+
+// p299 the highest spell rank.
+pub const MAX_RANK: i64 = 10;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SpellError {
+    Io(String),
+    Parse(String),
+    InvalidEntry { key: String, message: String },
+    UnknownSpell(String),
+    Unavailable { key: String, message: String },
+}
+
+impl fmt::Display for SpellError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpellError::Io(e) => write!(f, "cannot read the spell list: {}", e),
+            SpellError::Parse(e) => write!(f, "malformed spell list: {}", e),
+            SpellError::InvalidEntry { key, message } => {
+                write!(f, "invalid spell \"{}\": {}", key, message)
+            }
+            SpellError::UnknownSpell(key) => write!(f, "no spell \"{}\" in the spell list", key),
+            SpellError::Unavailable { key, message } => {
+                write!(f, "cannot add \"{}\": {}", key, message)
+            }
+        }
+    }
+}
+
+// p297 the four magical traditions.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Tradition {
+    Arcane,
+    Divine,
+    Occult,
+    Primal,
+}
+
+// p302 casting a spell takes 1 to 3 actions, some spells let the caster choose how many.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+pub enum SpellActions {
+    Fixed(i64),
+    // without variants, the damage is dealt once per action spent, like the missiles of
+    // Magic Missile.
+    Variable(i64, i64),
+}
+
+impl SpellActions {
+    pub fn get_choices(&self) -> Vec<i64> {
+        match self {
+            SpellActions::Fixed(n) => vec![*n],
+            SpellActions::Variable(min, max) => (*min..=*max).collect(),
+        }
+    }
+}
+
+// p456 areas, in feet, see `Area` for their squares.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+pub enum SpellArea {
+    // centered on a point within range
+    Burst(i64),
+    // from the caster
+    Cone(i64),
+    Line(i64),
+    // around the caster
+    Emanation(i64),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpellTargets {
+    // up to that many enemies within range
+    Enemies(usize),
+    // every other creature in the area, allies included
+    Creatures,
+    // the caster and its allies in the area
+    Allies,
+    // one ally within range, the caster included: the most wounded one.
+    Ally,
+}
+
+// p305 how the targets defend against the spell.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+pub enum SpellDefense {
+    #[default]
+    None,
+    // a spell attack roll against AC, the degree is the one of the caster.
+    Attack,
+    // a save against the spell DC, the degree is the one of the target.
+    Save(Save),
+    // p449 half damage on a success, double on a critical failure.
+    BasicSave(Save),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+pub enum DamageScale {
+    Half,
+    Full,
+    Double,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+pub enum SpellEffect {
+    Damage(DamageScale),
+    // a status lasting that many rounds
+    Status(StatusType, i64),
+    // restores the healing of the spell
+    Heal,
+    // p461 temporary HP, plus the spellcasting ability modifier
+    TemporaryHp(i64),
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpellDamage {
+    // dice notation, like "1d4+1"
+    pub dice: String,
+    pub damage_type: DamageType,
+    // adds the spellcasting ability modifier, like Electric Arc.
+    #[serde(default)]
+    pub ability: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpellHealing {
+    // dice notation, like "1d8"
+    pub dice: String,
+    // a flat amount per rank of the spell, like the two-action Heal.
+    #[serde(default)]
+    pub per_rank: i64,
+}
+
+// p343 what changes with the number of actions spent, like the range of Heal.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpellVariant {
+    #[serde(default)]
+    pub range: Option<i64>,
+    #[serde(default)]
+    pub area: Option<SpellArea>,
+    #[serde(default)]
+    pub targets: Option<SpellTargets>,
+    #[serde(default)]
+    pub healing: Option<SpellHealing>,
+}
+
+// p299 heightened (+every): the extra dice, or extra missiles, for every `every` ranks.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Heightened {
+    pub every: i64,
+    #[serde(default)]
+    pub dice: i64,
+    #[serde(default)]
+    pub repeats: i64,
+}
+
+/**
+An entry of the spell list, rank 0 for cantrips.
+Without explicit outcomes, the damage follows the defense: double on a critical spell attack,
+the basic save table for basic saves, full damage when there is no defense.
+*/
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpellData {
+    pub name: String,
+    pub rank: i64,
+    pub traditions: Vec<Tradition>,
+    pub actions: SpellActions,
+    // in feet, 0 for the caster itself
+    #[serde(default)]
+    pub range: i64,
+    #[serde(default)]
+    pub area: Option<SpellArea>,
+    pub targets: SpellTargets,
+    #[serde(default)]
+    pub defense: SpellDefense,
+    #[serde(default)]
+    pub damage: Option<SpellDamage>,
+    #[serde(default)]
+    pub healing: Option<SpellHealing>,
+    #[serde(default)]
+    pub heightened: Option<Heightened>,
+    // by number of actions, for spells with variable actions.
+    #[serde(default)]
+    pub variants: BTreeMap<i64, SpellVariant>,
+    #[serde(default)]
+    pub outcomes: BTreeMap<DegreeOfSuccess, Vec<SpellEffect>>,
+    // p304 in rounds, an emanation with a duration lasts around its caster, see `LastingEmanation`.
+    #[serde(default)]
+    pub duration: i64,
+    // p305 feet added to the emanation each time the spell is sustained.
+    #[serde(default)]
+    pub sustain_growth: i64,
+}
+
+impl SpellData {
+    pub fn validate(&self) -> Result<(), String> {
+        for actions in self.variants.keys() {
+            if !self.actions.get_choices().contains(actions) {
+                return Err(format!("no variant with {} actions", actions));
+            }
+            self.with_actions(*actions).validate_variant()?;
+        }
+        self.validate_variant()
+    }
+
+    fn validate_variant(&self) -> Result<(), String> {
+        if !(0..=MAX_RANK).contains(&self.rank) {
+            return Err(format!(
+                "rank must be between 0 and {}, got {}",
+                MAX_RANK, self.rank
+            ));
+        }
+        if self.traditions.is_empty() {
+            return Err(String::from("a spell needs at least one tradition"));
+        }
+        let valid_actions = match self.actions {
+            SpellActions::Fixed(n) => (1..=3).contains(&n),
+            SpellActions::Variable(min, max) => 1 <= min && min < max && max <= 3,
+        };
+        if !valid_actions {
+            return Err(format!(
+                "a spell takes 1 to 3 actions, got {:?}",
+                self.actions
+            ));
+        }
+        match (self.targets, self.area) {
+            (SpellTargets::Enemies(0), _) => {
+                return Err(String::from("a spell needs at least one target"))
+            }
+            (SpellTargets::Creatures, None) | (SpellTargets::Allies, None) => {
+                return Err(format!("{:?} targets need an area", self.targets))
+            }
+            _ => {}
+        }
+        if let Some(damage) = &self.damage {
+            parse_dice(&damage.dice)?;
+        }
+        if let Some(healing) = &self.healing {
+            parse_dice(&healing.dice)?;
+        }
+        for effect in self.outcomes.values().flatten() {
+            match effect {
+                SpellEffect::Damage(_) if self.damage.is_none() => {
+                    return Err(String::from("damage outcomes need damage"))
+                }
+                SpellEffect::Heal if self.healing.is_none() => {
+                    return Err(String::from("heal outcomes need healing"))
+                }
+                _ => {}
+            }
+        }
+        if let Some(heightened) = &self.heightened {
+            if heightened.every < 1 {
+                return Err(format!(
+                    "heightened every must be positive, got {}",
+                    heightened.every
+                ));
+            }
+        }
+        if self.damage.is_none() && self.outcomes.is_empty() {
+            return Err(String::from("a spell needs damage or outcomes"));
+        }
+        let emanation = matches!(self.area, Some(SpellArea::Emanation(_)));
+        if (self.duration > 0 || self.sustain_growth > 0) && !emanation {
+            return Err(String::from("only emanations can last or be sustained"));
+        }
+        if self.duration > 0 && self.damage.is_some() {
+            return Err(String::from("lasting emanations only grant statuses"));
+        }
+        Ok(())
+    }
+
+    pub fn is_cantrip(&self) -> bool {
+        self.rank == 0
+    }
+
+    // the spell cast with that many actions, with the changes of its variant.
+    pub fn with_actions(&self, actions: i64) -> SpellData {
+        let mut spell = self.clone();
+        if let Some(variant) = self.variants.get(&actions) {
+            spell.range = variant.range.unwrap_or(spell.range);
+            spell.area = variant.area.or(spell.area);
+            spell.targets = variant.targets.unwrap_or(spell.targets);
+            spell.healing = variant.healing.clone().or(spell.healing);
+        }
+        spell
+    }
+
+    // the effects of each degree of success, see the struct documentation.
+    pub fn get_outcomes(&self) -> BTreeMap<DegreeOfSuccess, Vec<SpellEffect>> {
+        use DegreeOfSuccess::*;
+        if !self.outcomes.is_empty() {
+            return self.outcomes.clone();
+        }
+        let outcomes = match self.defense {
+            SpellDefense::Attack => vec![
+                (CriticalSuccess, DamageScale::Double),
+                (Success, DamageScale::Full),
+            ],
+            SpellDefense::BasicSave(_) => vec![
+                (Success, DamageScale::Half),
+                (Failure, DamageScale::Full),
+                (CriticalFailure, DamageScale::Double),
+            ],
+            SpellDefense::None | SpellDefense::Save(_) => vec![(Success, DamageScale::Full)],
+        };
+        outcomes
+            .into_iter()
+            .map(|(degree, scale)| (degree, vec![SpellEffect::Damage(scale)]))
+            .collect()
+    }
+
+    // p300 cantrips are heightened from their first rank.
+    fn get_heightening_steps(&self, rank: i64) -> i64 {
+        match &self.heightened {
+            Some(h) => (rank - self.rank.max(1)).max(0) / h.every,
+            None => 0,
+        }
+    }
+
+    /**
+    The dice of the spell cast at `rank` with `actions` actions.
+    A variable spell without variants rolls them once per action, heightening adds dice or repeats.
+    */
+    fn get_roll(&self, dice: &str, rank: i64, actions: i64) -> Roll {
+        let (nb_dice, faces, bonus) = parse_dice(dice).expect("spell should be validated");
+        let steps = self.get_heightening_steps(rank);
+        let (extra_dice, extra_repeats) = match &self.heightened {
+            Some(h) => (h.dice * steps, h.repeats * steps),
+            None => (0, 0),
+        };
+        let per_action = match self.actions {
+            SpellActions::Variable(_, _) if self.variants.is_empty() => actions,
+            _ => 1,
+        };
+        let times = per_action * (1 + extra_repeats);
+        let mut roll = Roll::d(&self.name, (nb_dice + extra_dice) * times, faces);
+        if bonus != 0 {
+            roll = roll + Roll::flat("bonus", bonus * times);
+        }
+        roll
+    }
+
+    // the damage of the spell, before its outcome.
+    pub fn get_damage_roll(&self, rank: i64, actions: i64, ability_modifier: i64) -> Option<Roll> {
+        let damage = self.damage.as_ref()?;
+        let mut roll = self.get_roll(&damage.dice, rank, actions);
+        if damage.ability {
+            roll = roll + Roll::flat("ability", ability_modifier);
+        }
+        Some(roll)
+    }
+
+    // the HP restored by the spell, see `with_actions` for the healing of each variant.
+    pub fn get_healing_roll(&self, rank: i64, actions: i64) -> Option<Roll> {
+        let healing = self.healing.as_ref()?;
+        let mut roll = self.get_roll(&healing.dice, rank, actions);
+        if healing.per_rank != 0 {
+            roll = roll + Roll::flat("bonus", healing.per_rank * rank);
+        }
+        Some(roll)
+    }
+}
+
+/**
+p304 an emanation lasting several rounds, like Bless. It follows its caster, and the creatures
+inside it have the statuses of the spell outcomes for as long as they stay inside.
+*/
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LastingEmanation {
+    pub spell: String,
+    pub name: String,
+    pub radius: i64,
+    pub rounds: i64,
+    pub targets: SpellTargets,
+    pub statuses: Vec<StatusType>,
+    pub sustain_growth: i64,
+    // p305 a spell can be sustained once per turn, starting the turn after it is cast.
+    pub sustained: bool,
+}
+
+impl LastingEmanation {
+    pub fn new(key: &str, spell: &SpellData, radius: i64) -> Self {
+        LastingEmanation {
+            spell: String::from(key),
+            name: spell.name.clone(),
+            radius,
+            rounds: spell.duration,
+            targets: spell.targets,
+            statuses: spell
+                .get_outcomes()
+                .values()
+                .flatten()
+                .filter_map(|e| match e {
+                    SpellEffect::Status(status, _) => Some(*status),
+                    _ => None,
+                })
+                .collect(),
+            sustain_growth: spell.sustain_growth,
+            sustained: true,
+        }
+    }
+
+    pub fn get_area(&self, caster: &Character) -> Area {
+        Area::Emanation {
+            origin: caster.position,
+            radius: self.radius,
+        }
+    }
+
+    // the conscious creatures currently inside the emanation.
+    pub fn find_creatures(&self, caster: &Character, world: &World) -> Vec<String> {
+        let affects = match self.targets {
+            SpellTargets::Allies | SpellTargets::Ally => Affects::Allies,
+            SpellTargets::Enemies(_) => Affects::Enemies,
+            SpellTargets::Creatures => Affects::Everyone,
+        };
+        let mut ids = find_creatures(&self.get_area(caster), &caster.party, affects, world);
+        ids.retain(|id| world.get_character(id).hp > 0);
+        ids
+    }
+}
+
+// the statuses a creature gets from the lasting emanations it stands in.
+pub fn get_emanation_statuses(character: &Character, world: &World) -> Vec<StatusType> {
+    let mut casters: Vec<&Character> = world
+        .get_characters()
+        .into_iter()
+        .filter(|c| c.hp > 0 && !c.emanations.is_empty())
+        .collect();
+    casters.sort_by_key(|c| c.id.clone());
+    casters
+        .into_iter()
+        .flat_map(|caster| {
+            caster
+                .emanations
+                .iter()
+                .filter(|e| e.find_creatures(caster, world).contains(&character.id))
+                .flat_map(|e| e.statuses.clone())
+                .collect::<Vec<StatusType>>()
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Spellbook {
+    spells: BTreeMap<String, SpellData>,
+}
+
+impl Spellbook {
+    pub fn core() -> Self {
+        Spellbook::parse(CORE_SPELLS).expect("the core spell list should be valid")
+    }
+
+    pub fn load(path: &Path) -> Result<Self, SpellError> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| SpellError::Io(format!("{}: {}", path.display(), e)))?;
+        Spellbook::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Self, SpellError> {
+        let spells: BTreeMap<String, SpellData> =
+            ron::from_str(content).map_err(|e| SpellError::Parse(e.to_string()))?;
+        for (key, spell) in spells.iter() {
+            spell
+                .validate()
+                .map_err(|message| SpellError::InvalidEntry {
+                    key: key.clone(),
+                    message,
+                })?;
+        }
+        Ok(Spellbook { spells })
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.spells.keys()
+    }
+
+    pub fn get(&self, key: &str) -> Result<&SpellData, SpellError> {
+        self.spells
+            .get(key)
+            .ok_or_else(|| SpellError::UnknownSpell(String::from(key)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heightening() {
+        let spellbook = Spellbook::core();
+        let missile = spellbook.get("magic-missile").unwrap();
+        assert_eq!(missile.actions.get_choices(), vec![1, 2, 3]);
+        // 1d4+1 per action
+        assert_eq!(
+            missile.get_damage_roll(1, 3, 0).unwrap().roll(),
+            3 * (2 + 1)
+        );
+        // one more missile per action at rank 3
+        assert_eq!(
+            missile.get_damage_roll(3, 2, 0).unwrap().roll(),
+            4 * (2 + 1)
+        );
+
+        let ray = spellbook.get("ray-of-frost").unwrap();
+        assert!(ray.is_cantrip());
+        assert_eq!(ray.get_damage_roll(1, 2, 4).unwrap().roll(), 2 * 2);
+        assert_eq!(ray.get_damage_roll(3, 2, 4).unwrap().roll(), 6 * 2);
+        assert_eq!(
+            ray.get_outcomes()[&DegreeOfSuccess::CriticalSuccess],
+            vec![SpellEffect::Damage(DamageScale::Double)]
+        );
+        let arc = spellbook.get("electric-arc").unwrap();
+        assert_eq!(arc.get_damage_roll(1, 2, 4).unwrap().roll(), 2 + 4);
+
+        // p343 the variants of Heal are not repeated per action
+        let heal = spellbook.get("heal").unwrap();
+        assert_eq!(
+            heal.with_actions(1).get_healing_roll(2, 1).unwrap().roll(),
+            2 * 6
+        );
+        let ranged = heal.with_actions(2);
+        assert_eq!(ranged.range, 30);
+        assert_eq!(ranged.get_healing_roll(3, 2).unwrap().roll(), 3 * 6 + 24);
+        assert_eq!(heal.with_actions(3).area, Some(SpellArea::Emanation(30)));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Spellbook::core().get("wish").unwrap_err().to_string(),
+            "no spell \"wish\" in the spell list"
+        );
+        let spell = r#"{ "zap": (name: "Zap", rank: 1, traditions: [Arcane],
+            actions: Variable(2, 2), targets: Enemies(1),
+            damage: Some((dice: "1d6", damage_type: Electricity))) }"#;
+        assert_eq!(
+            Spellbook::parse(spell).unwrap_err().to_string(),
+            "invalid spell \"zap\": a spell takes 1 to 3 actions, got Variable(2, 2)"
+        );
+        assert_eq!(
+            Spellbook::parse(
+                &spell
+                    .replace("Variable(2, 2)", "Fixed(2)")
+                    .replace("Enemies(1)", "Allies")
+            )
+            .unwrap_err()
+            .to_string(),
+            "invalid spell \"zap\": Allies targets need an area"
+        );
+        assert_eq!(
+            Spellbook::parse(&spell.replace(
+                "Variable(2, 2)",
+                "Variable(1, 2), variants: { 2: (targets: Some(Creatures)) }"
+            ))
+            .unwrap_err()
+            .to_string(),
+            "invalid spell \"zap\": Creatures targets need an area"
+        );
+        assert!(matches!(
+            Spellbook::parse(&spell.replace("rank: 1", "level: 1")),
+            Err(SpellError::Parse(_))
+        ));
+    }
+}
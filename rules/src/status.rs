@@ -1,33 +1,73 @@
 use std::cmp::max;
 
-use crate::character::Character;
+use dice::Roll;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug)]
+use crate::{
+    character::{abilities::Ability, Character},
+    fact::{Fact, Facts},
+    world::World,
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct StatusEffect {
     pub status_type: StatusType,
     pub duration: Duration,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum StatusType {
     // Poison,
     Bless,
+    // p472 Raise Shield
+    ShieldRaised,
+    // p620 -2 circumstance penalty to AC
+    FlatFooted,
+    // p621 -1 status penalty to checks and DCs, frightened 2 lasts one more round.
+    Frightened,
+    // p622 flat-footed and -2 circumstance penalty to attack rolls, until the creature Stands.
+    Prone,
+    // p471 Take Cover, until the creature moves or attacks.
+    TakingCover,
+    // p617 DC 5 flat check to target the creature.
+    Concealed,
+    // p617 DC 11 flat check to target the creature.
+    Hidden,
+    // p249 Treat Wounds cannot be used again on the creature for an hour.
+    TreatWoundsImmunity,
+    // p258 Battle Medicine cannot be used again on the creature for a day.
+    BattleMedicineImmunity,
+    // p272 clumsy 1 and a -10-foot penalty to Speed, while carrying too much Bulk.
+    Encumbered,
+    // p565 +1 item bonus to attack rolls, from the talisman.
+    PotencyCrystal,
     // Unconscious,
     // Dead,
 }
 
 // order is important, as we use this to do the partial order
-#[derive(Copy, Clone, PartialEq, PartialOrd, Eq, Ord, Debug)]
+#[derive(Copy, Clone, PartialEq, PartialOrd, Eq, Ord, Debug, Serialize, Deserialize)]
 pub enum Duration {
     // EndOfAction,
     // EndOfActivation,
     // EndOfRound,
     // StartOfNextActivation,
+    // removed at the start of the creature's next turn.
+    StartOfNextTurn,
     Round(i64),
+    // until an activity removes it, like Stand for prone.
+    UntilRemoved,
 }
 
 impl Character {
-    pub fn tick_down(&mut self) {
+    // returns the statuses that ran out.
+    pub fn tick_down(&mut self) -> Vec<StatusType> {
+        let expired = self
+            .status
+            .iter()
+            .filter(|s| s.duration == Duration::Round(1))
+            .map(|s| s.status_type)
+            .collect();
         let new_status = self
             .status
             .iter()
@@ -37,10 +77,39 @@ impl Character {
                     duration: Duration::Round(x - 1),
                     status_type: s.status_type,
                 }),
+                Duration::StartOfNextTurn | Duration::UntilRemoved => Some(s.clone()),
             })
             .collect();
         self.status = new_status;
+        self.emanations.iter_mut().for_each(|e| e.rounds -= 1);
+        self.emanations.retain(|e| e.rounds > 0);
+        expired
+    }
+    // p621 frightened is a status penalty to every check and DC.
+    pub fn get_status_penalty(&self) -> Roll {
+        if self.has_status(StatusType::Frightened) {
+            Roll::flat("frightened", -1)
+        } else {
+            Roll::default()
+        }
     }
+
+    /**
+    p621 clumsy 1 from being encumbered applies to the checks and DCs based on Dexterity,
+    frightened to all of them. Status penalties do not stack.
+    */
+    pub fn get_ability_status_penalty(&self, ability: Ability) -> Roll {
+        if ability == Ability::Dexterity && self.has_status(StatusType::Encumbered) {
+            Roll::flat("clumsy", -1)
+        } else {
+            self.get_status_penalty()
+        }
+    }
+
+    pub fn remove_status(&mut self, status_type: StatusType) {
+        self.status.retain(|s| s.status_type != status_type);
+    }
+
     pub fn add_status(&mut self, status: StatusEffect) {
         let mut was_found = false;
 
@@ -63,3 +132,41 @@ impl Character {
         }
     }
 }
+/**
+Adds a status to a character and reports it, unless the character already had it.
+Every status given by an activity goes through here.
+*/
+pub fn apply_status(
+    character_id: &str,
+    status: StatusEffect,
+    world: &mut World,
+    facts: &mut Facts,
+) {
+    let character = world.get_mut_character(character_id);
+    let status_type = status.status_type;
+    let is_new = !character.has_status(status_type);
+    character.add_status(status);
+    if is_new {
+        facts.push(Fact::StatusApplied {
+            character_id: String::from(character_id),
+            status: status_type,
+        });
+    }
+}
+
+// removes a status and reports it, when the character had it.
+pub fn end_status(
+    character_id: &str,
+    status_type: StatusType,
+    world: &mut World,
+    facts: &mut Facts,
+) {
+    let character = world.get_mut_character(character_id);
+    if character.has_status(status_type) {
+        character.remove_status(status_type);
+        facts.push(Fact::StatusRemoved {
+            character_id: String::from(character_id),
+            status: status_type,
+        });
+    }
+}
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 /*
 Timeline keeps track of rounds and which units already activated.
@@ -30,12 +31,13 @@ pub type CharacterId = String;
 
 pub type PartyId = String;
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Timeline {
     pub turn_counter: i64,
     activated: Vec<String>,
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
 pub struct Activation {
     pub character_id: CharacterId,
     pub party: PartyId,
@@ -209,6 +211,9 @@ pub fn get_modifier(score: i64) -> i64 {
 impl character::Character {
     pub fn roll_perception_check(&self) -> i64 {
         // Perception check result = d20 roll + Wisdom modifier + proficiency bonus + other bonuses + penalties
+        if let Some(creature) = &self.creature {
+            return Roll::d("", 1, 20).roll() + creature.perception;
+        }
         Roll::d("", 1, 20).roll() + get_modifier(self.ability_score.wisdom)
     }
 
@@ -1,42 +1,120 @@
 use crate::{
-    character::Character,
-    item::{
-        armor::ArmorItem,
-        weapon::{WeaponItem},
-        AnyItem,
-    },
+    character::{creature::CreatureStrike, Character},
+    item::{armor::ArmorItem, shield::ShieldItem, weapon::WeaponItem, AnyItem, ItemId},
+    status::StatusType,
     world::World,
 };
 
-pub fn get_active_weapon<'a>(character: &Character, world: &'a World) -> &'a WeaponItem {
-    let mut id = match (&character.loadout.left_hand, &character.loadout.right_hand) {
-        (_, Some(ref w)) => w, // @todo check the rules on lefty/righty rules.
-        (Some(ref w), None) => w,
-        (None, None) => "",
-    };
-    if id == "" {
-        id = "unarmed";
-    } 
-        if let AnyItem::WeaponItem(item) = world.items.get(id).expect("cannot find weapon") {
-            &item
-        } else {
-            panic!("this is not a weapon")
-        }
-    
-}
-
-pub fn get_armor<'a>(character: &Character, world: &'a World) -> &'a ArmorItem {
-    let id = match &character.loadout.armor {
-        Some(ref w) => w,
-        None => "",
-    };
-    if id == "" {
-        panic!("Didnt implement naked defense yet");
-    } else {
-        if let AnyItem::ArmorItem(item) = world.items.get(id).expect("cannot find weapon") {
-            &item
-        } else {
-            panic!("this is not a weapon")
+// the first wielded weapon, the right hand first.
+pub fn get_active_weapon<'a>(character: &'a Character, world: &'a World) -> &'a WeaponItem {
+    // creatures use the first Strike of their stat block.
+    if let Some(strike) = character.creature.as_ref().and_then(|c| c.strikes.first()) {
+        return &strike.weapon;
+    }
+    get_wielded_weapons(character, world)[0].1
+}
+
+/**
+The weapon of melee Strikes like Power Attack or Attack of Opportunity: the first melee weapon
+wielded, or the first melee Strike of a stat block, with the Strike.
+*/
+pub fn get_melee_weapon<'a>(
+    character: &'a Character,
+    world: &'a World,
+) -> Option<(&'a WeaponItem, Option<&'a CreatureStrike>)> {
+    match &character.creature {
+        Some(creature) => creature
+            .strikes
+            .iter()
+            .find(|s| !s.weapon.is_ranged)
+            .map(|s| (&s.weapon, Some(s))),
+        None => get_wielded_weapons(character, world)
+            .into_iter()
+            .find(|(_, weapon)| !weapon.is_ranged)
+            .map(|(_, weapon)| (weapon, None)),
+    }
+}
+
+// weapons and shields can be used to Strike.
+fn is_weapon(id: &str, world: &World) -> bool {
+    matches!(
+        world.items.get(id),
+        Some(AnyItem::WeaponItem(_)) | Some(AnyItem::ShieldItem(_))
+    )
+}
+
+// a weapon, or the shield bash of a shield.
+pub fn get_weapon<'a>(id: &str, world: &'a World) -> &'a WeaponItem {
+    match world.items.get(id).expect("cannot find weapon") {
+        AnyItem::WeaponItem(item) => item,
+        AnyItem::ShieldItem(shield) => &shield.bash,
+        _ => panic!("this is not a weapon"),
+    }
+}
+
+/**
+What the character can Strike with: the right hand, the left hand, or the fists.
+Items that are not weapons, like a drawn potion, leave the hand empty for Strikes, and a two-handed
+weapon held in one hand cannot be wielded. A weapon held in both hands is only listed once.
+*/
+pub fn get_wielded_weapons<'a>(
+    character: &Character,
+    world: &'a World,
+) -> Vec<(ItemId, &'a WeaponItem)> {
+    let loadout = &character.loadout;
+    let mut weapons: Vec<(ItemId, &WeaponItem)> = vec![];
+    for id in [&loadout.right_hand, &loadout.left_hand]
+        .iter()
+        .filter_map(|hand| hand.as_ref())
+        .filter(|id| is_weapon(id, world))
+    {
+        let weapon = get_weapon(id, world);
+        let wielded = !weapon.is_two_hands || loadout.is_held_in_both_hands(id);
+        if wielded && !weapons.iter().any(|(w, _)| w == id) {
+            weapons.push((id.clone(), weapon));
         }
     }
+    if weapons.is_empty() {
+        return vec![(String::from("unarmed"), get_weapon("unarmed", world))];
+    }
+    weapons
+}
+
+// a shield can be held in either hand.
+pub fn get_shield<'a>(character: &Character, world: &'a World) -> Option<&'a ShieldItem> {
+    [&character.loadout.left_hand, &character.loadout.right_hand]
+        .iter()
+        .filter_map(|hand| hand.as_ref())
+        .find_map(|id| match world.items.get(id) {
+            Some(AnyItem::ShieldItem(shield)) => Some(shield),
+            _ => None,
+        })
+}
+
+pub fn get_mut_shield<'a>(
+    character: &Character,
+    world: &'a mut World,
+) -> Option<&'a mut ShieldItem> {
+    let id = get_shield(character, world)?.info.id.clone();
+    match world.items.get_mut(&id) {
+        Some(AnyItem::ShieldItem(shield)) => Some(shield),
+        _ => None,
+    }
+}
+
+pub fn get_armor<'a>(character: &Character, world: &'a World) -> Option<&'a ArmorItem> {
+    let id = character.loadout.armor.as_ref()?;
+    match world.items.get(id).expect("cannot find armor") {
+        AnyItem::ArmorItem(item) => Some(item),
+        _ => panic!("this is not an armor"),
+    }
+}
+
+// in feet, after the armor speed penalty and p272 the encumbered one.
+pub fn get_speed(character: &Character, world: &World) -> i64 {
+    let mut penalty = get_armor(character, world).map_or(0, |a| a.get_speed_penalty(character));
+    if character.has_status(StatusType::Encumbered) {
+        penalty -= 10;
+    }
+    (character.speed + penalty).max(5)
 }
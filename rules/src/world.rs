@@ -1,54 +1,160 @@
-use crate::item::weapon::unarmed;
 use crate::{
-    character::Character,
+    bestiary::{Bestiary, BestiaryError},
+    character::{proficiency::Proficiencies, Character},
+    fact::{Fact, Facts},
+    inventory::wield,
     item::{
-        armor::{leather, scale_mail, ArmorItem},
-        weapon::{greatswordplus1, greatswordplus2, longbow, sling, WeaponItem},
+        armor::ArmorItem,
+        catalog::{Catalog, CatalogError},
+        rune::Runes,
+        shield::ShieldItem,
+        weapon::{unarmed, WeaponItem},
         AnyItem, ItemId,
     },
+    map::Map,
+    position::Position,
+    reaction::{AiReactionPolicy, ReactionPolicy},
     rules::RuleBook,
+    spell::Spellbook,
 };
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, sync::Arc};
 
 use crate::timeline::CharacterId;
 
-type WeaponSpawner = &'static dyn Fn() -> WeaponItem;
-type ArmorSpawner = &'static dyn Fn() -> ArmorItem;
-
 /**
  * Cannot delete characters during an encounter...
+ * Worlds are cloned to simulate the same encounter many times, on several threads.
+ * The characters and items are kept sorted by id, so that the same world always plays the same way.
+ * Only the state of the encounter is saved, the rules, the core data and the reaction policy
+ * are loaded again.
  */
+#[derive(Clone, Serialize, Deserialize)]
 pub struct World {
-    pub characters: HashMap<CharacterId, Character>,
-    pub items: HashMap<ItemId, AnyItem>,
+    pub characters: BTreeMap<CharacterId, Character>,
+    pub items: BTreeMap<ItemId, AnyItem>,
+    #[serde(skip, default = "load_rules")]
     pub rules: RuleBook,
+    pub map: Map,
+    #[serde(skip, default = "Catalog::core")]
+    pub catalog: Catalog,
+    #[serde(skip, default = "Bestiary::core")]
+    pub bestiary: Bestiary,
+    #[serde(skip, default = "Spellbook::core")]
+    pub spellbook: Spellbook,
+    #[serde(skip, default = "ai_reaction_policy")]
+    pub reaction_policy: Arc<dyn ReactionPolicy>,
+    // the ids are numbered in the order things enter the world.
+    next_id: u64,
+}
+
+/**
+What the activities change in a world, to take them back.
+The rules and the core data stay the same during an encounter, they are left out.
+*/
+#[derive(Clone)]
+pub struct WorldSnapshot {
+    characters: BTreeMap<CharacterId, Character>,
+    items: BTreeMap<ItemId, AnyItem>,
+    map: Map,
+    next_id: u64,
+}
+
+fn load_rules() -> RuleBook {
+    let mut rules = RuleBook::new();
+    rules.load_rules();
+    rules
+}
+
+fn ai_reaction_policy() -> Arc<dyn ReactionPolicy> {
+    Arc::new(AiReactionPolicy {})
 }
 
 impl World {
     pub fn new() -> Self {
-        let items: HashMap<ItemId, AnyItem> = HashMap::new();
-        let characters = HashMap::new();
-        let mut rules = RuleBook::new();
-        rules.load_rules();
-        World {
-            characters,
-            items,
-            rules,
+        let mut world = World {
+            characters: BTreeMap::new(),
+            items: BTreeMap::new(),
+            rules: load_rules(),
+            map: Map::new(),
+            catalog: Catalog::core(),
+            bestiary: Bestiary::core(),
+            spellbook: Spellbook::core(),
+            reaction_policy: ai_reaction_policy(),
+            next_id: 1,
+        };
+        world.spawn_weapon(unarmed());
+        world
+    }
+
+    pub fn snapshot(&self) -> WorldSnapshot {
+        WorldSnapshot {
+            characters: self.characters.clone(),
+            items: self.items.clone(),
+            map: self.map.clone(),
+            next_id: self.next_id,
+        }
+    }
+
+    pub fn restore(&mut self, snapshot: WorldSnapshot) {
+        self.characters = snapshot.characters;
+        self.items = snapshot.items;
+        self.map = snapshot.map;
+        self.next_id = snapshot.next_id;
+    }
+
+    fn new_id(&mut self, name: &str) -> String {
+        let id = format!("{}-{}", name, self.next_id);
+        self.next_id += 1;
+        id
+    }
+
+    // spawns a copy of the catalog item registered under `key`, like "longsword".
+    pub fn spawn_item(&mut self, key: &str) -> Result<ItemId, CatalogError> {
+        let item = self.catalog.spawn(key)?;
+        Ok(self.add_item(item))
+    }
+
+    // spawns the creature of the bestiary registered under `key`, like "kobold-warrior".
+    pub fn spawn_creature(
+        &mut self,
+        key: &str,
+        party: &str,
+        position: Position,
+    ) -> Result<CharacterId, BestiaryError> {
+        let mut character = self.bestiary.get(key)?.to_character(party);
+        character.position = position;
+        Ok(self.add_character(character))
+    }
+
+    // the character gets its id from the world.
+    pub fn add_character(&mut self, mut character: Character) -> CharacterId {
+        character.id = self.new_id(&character.name);
+        let id = character.id.clone();
+        self.characters.insert(id.clone(), character);
+        id
+    }
+
+    // items without an id, the ones built in code or spawned from the catalog, get one from the world.
+    pub fn add_item(&mut self, mut item: AnyItem) -> ItemId {
+        if item.get_info().id.is_empty() {
+            item.get_mut_info().id = self.new_id(&item.get_info().name);
         }
+        let id = item.get_info().id.clone();
+        self.items.insert(id.clone(), item);
+        id
+    }
+
+    pub fn spawn_armor(&mut self, item: ArmorItem) -> Option<ItemId> {
+        Some(self.add_item(AnyItem::ArmorItem(item)))
     }
 
-    pub fn spawn_armor(&mut self, f: ArmorSpawner) -> Option<ItemId> {
-        let item = f();
-        let id = item.info.id.clone();
-        self.items.insert(id.clone(), AnyItem::ArmorItem(item));
-        Some(id)
+    pub fn spawn_shield(&mut self, item: ShieldItem) -> Option<ItemId> {
+        Some(self.add_item(AnyItem::ShieldItem(item)))
     }
 
-    pub fn spawn_weapon(&mut self, f: WeaponSpawner) -> Option<ItemId> {
-        let item = f();
-        let id = item.info.id.clone();
-        self.items.insert(id.clone(), AnyItem::WeaponItem(item));
-        Some(id)
+    pub fn spawn_weapon(&mut self, item: WeaponItem) -> Option<ItemId> {
+        Some(self.add_item(AnyItem::WeaponItem(item)))
     }
 
     pub fn get_characters(&self) -> Vec<&Character> {
@@ -64,30 +170,111 @@ impl World {
             .get_mut(key)
             .expect("Oh no, could not find the right target")
     }
-    pub fn tick_down(&mut self) {
-        self.characters.iter_mut().for_each(|(_s, c)| c.tick_down());
+    // a wall, or a conscious creature.
+    pub fn is_occupied(&self, position: &Position) -> bool {
+        self.map.is_wall(position)
+            || self
+                .characters
+                .values()
+                .any(|c| c.hp > 0 && c.position == *position)
+    }
+    pub fn tick_down(&mut self, facts: &mut Facts) {
+        let mut ids: Vec<CharacterId> = self.characters.keys().cloned().collect();
+        ids.sort();
+        for id in ids {
+            for status in self.get_mut_character(&id).tick_down() {
+                facts.push(Fact::StatusRemoved {
+                    character_id: id.clone(),
+                    status,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+impl World {
+    // a bare character standing at `position`, for the tests.
+    pub fn add_character_at(
+        &mut self,
+        name: &str,
+        party: &str,
+        hp: i64,
+        position: Position,
+    ) -> CharacterId {
+        let mut character = Character::new(String::from(name), String::from(party), hp);
+        character.position = position;
+        self.add_character(character)
+    }
+
+    // an orc brute at (0, 0) against a creature of the bestiary, with both their ids.
+    pub fn orc_duel(
+        opponent: &str,
+        party: &str,
+        position: Position,
+    ) -> (World, CharacterId, CharacterId) {
+        let mut world = World::new();
+        let orc = world
+            .spawn_creature("orc-brute", "orcs", Position::new(0, 0))
+            .unwrap();
+        let other = world.spawn_creature(opponent, party, position).unwrap();
+        (world, orc, other)
     }
 }
 
 pub fn init(world: &mut World) {
-    world.spawn_weapon(&unarmed);
-    init_unit(world, "Kobold Slinger", "kobolds", 40, &sling, &leather);
-    init_unit(world, "Kobold Archer", "kobolds", 40, &longbow, &leather);
+    let sling = world.catalog.get_weapon("sling").unwrap();
+    let slinger = init_unit(
+        world,
+        "Kobold Slinger",
+        "kobolds",
+        40,
+        sling,
+        "leather",
+        Position::new(0, 0),
+    );
+    world.get_mut_character(&slinger).loadout.left_hand = world.spawn_item("wooden-shield").ok();
+    let longbow = world.catalog.get_weapon("longbow").unwrap();
+    init_unit(
+        world,
+        "Kobold Archer",
+        "kobolds",
+        40,
+        longbow,
+        "leather",
+        Position::new(1, 0),
+    );
+    let greatsword = world.catalog.get_weapon("greatsword").unwrap();
     init_unit(
         world,
         "Paladin",
         "knights",
         100,
-        &greatswordplus1,
-        &scale_mail,
+        greatsword
+            .with_runes(Runes {
+                potency: 1,
+                striking: 1,
+                ..Default::default()
+            })
+            .unwrap(),
+        "scale-mail",
+        Position::new(0, 6),
     );
+    let greatsword = world.catalog.get_weapon("greatsword").unwrap();
     init_unit(
         world,
         "Barbarian",
         "knights",
         100,
-        &greatswordplus2,
-        &scale_mail,
+        greatsword
+            .with_runes(Runes {
+                potency: 2,
+                striking: 2,
+                ..Default::default()
+            })
+            .unwrap(),
+        "scale-mail",
+        Position::new(1, 6),
     );
 }
 
@@ -96,17 +283,18 @@ fn init_unit(
     name: &str,
     party: &str,
     hp: i64,
-    weapon: WeaponSpawner,
-    armor: ArmorSpawner,
+    weapon: WeaponItem,
+    armor: &str,
+    position: Position,
 ) -> CharacterId {
     let mut character = Character::new(String::from(name), String::from(party), hp);
-    let id = world.spawn_armor(armor);
-    character.loadout.armor = id;
-
-    let id = world.spawn_weapon(weapon);
-    character.loadout.right_hand = id;
-    let char_id = character.id.clone();
-    let out = char_id.clone();
-    world.characters.insert(char_id, character);
-    out
+    character.position = position;
+    character.proficiencies = Proficiencies::trained();
+    character.loadout.armor = world.spawn_item(armor).ok();
+    let char_id = world.add_character(character);
+    let weapon_id = world
+        .spawn_weapon(weapon)
+        .expect("weapons are always spawned");
+    wield(&char_id, &weapon_id, world).expect("the hands of a new unit are empty");
+    char_id
 }
@@ -0,0 +1,214 @@
+use std::{
+    env,
+    path::PathBuf,
+    process,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+
+use rules::{
+    batch::Batch,
+    encounter::EncounterDefinition,
+    fact::{TextFormatter, Verbosity},
+    world::World,
+    GameState,
+};
+
+const USAGE: &str = "usage: sim <encounter.ron> [--seed <n>] [--rounds-limit <n>] \
+[--format text|json|csv] [--verbosity summary|normal|full] [--runs <n>] [--threads <n>]
+
+With --runs, the encounter is played n times, from seed to seed + n - 1, \
+and the summary of the runs is printed instead of the combat log.";
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Format {
+    Text,
+    Json,
+    // only for batches, one line per character and per run.
+    Csv,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Options {
+    encounter: PathBuf,
+    seed: Option<u64>,
+    rounds_limit: i64,
+    format: Format,
+    verbosity: Verbosity,
+    runs: Option<u64>,
+    threads: u64,
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("{} needs a value", flag))?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid value \"{}\" for {}", value, flag))
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut encounter = None;
+    let mut options = Options {
+        encounter: PathBuf::new(),
+        seed: None,
+        // encounters where nobody can reach the other side would never end.
+        rounds_limit: 100,
+        format: Format::Text,
+        verbosity: Verbosity::Normal,
+        runs: None,
+        threads: 4,
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => options.seed = Some(parse_value(&arg, args.next())?),
+            "--rounds-limit" => options.rounds_limit = parse_value(&arg, args.next())?,
+            "--format" => {
+                options.format = match args.next().as_deref() {
+                    Some("text") => Format::Text,
+                    Some("json") => Format::Json,
+                    Some("csv") => Format::Csv,
+                    _ => return Err(String::from("--format is text, json or csv")),
+                }
+            }
+            "--verbosity" => {
+                options.verbosity = match args.next().as_deref() {
+                    Some("summary") => Verbosity::Summary,
+                    Some("normal") => Verbosity::Normal,
+                    Some("full") => Verbosity::Full,
+                    _ => return Err(String::from("--verbosity is summary, normal or full")),
+                }
+            }
+            "--runs" => options.runs = Some(parse_value(&arg, args.next())?),
+            "--threads" => options.threads = parse_value(&arg, args.next())?,
+            flag if flag.starts_with("--") => return Err(format!("unknown option {}", flag)),
+            path if encounter.is_none() => encounter = Some(PathBuf::from(path)),
+            path => return Err(format!("unexpected argument {}", path)),
+        }
+    }
+    options.encounter = encounter.ok_or_else(|| String::from("missing the encounter file"))?;
+    if options.format == Format::Csv && options.runs.is_none() {
+        return Err(String::from("--format csv needs --runs"));
+    }
+    Ok(options)
+}
+
+fn main() {
+    let options = match parse_args(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+    let definition = match EncounterDefinition::load(&options.encounter) {
+        Ok(definition) => definition,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    };
+    // without a seed, the run can still be replayed with the one printed here.
+    let seed = options.seed.unwrap_or_else(|| {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        eprintln!("seed: {}", seed);
+        seed
+    });
+    dice::seed(seed);
+
+    let mut world = World::new();
+    if let Err(e) = definition.spawn(&mut world) {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+    let mut game_state = GameState::from_world(world);
+    if let Some(runs) = options.runs {
+        let report = Batch::new(game_state)
+            .runs(runs)
+            .seed(seed)
+            .threads(options.threads)
+            .rounds_limit(options.rounds_limit)
+            .run();
+        match options.format {
+            Format::Text => print!("{}", report),
+            Format::Csv => print!("{}", report.to_csv()),
+            Format::Json => print_json(&report),
+        }
+        return;
+    }
+    let log = game_state.run(options.rounds_limit);
+    match options.format {
+        Format::Text => {
+            let formatter = TextFormatter::new(game_state.get_world()).verbosity(options.verbosity);
+            for line in formatter.format_all(&log) {
+                println!("{}", line);
+            }
+        }
+        Format::Json => print_json(&log),
+        Format::Csv => unreachable!("csv is only for batches"),
+    }
+}
+
+fn print_json<T: Serialize>(value: &T) {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => println!("{}", json),
+        Err(e) => {
+            eprintln!("cannot serialize the output: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rules::fact::Fact;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        parse_args(args.iter().map(|a| String::from(*a)))
+    }
+
+    #[test]
+    fn args() {
+        let options = parse(&[
+            "ambush.ron",
+            "--seed",
+            "42",
+            "--format",
+            "json",
+            "--rounds-limit",
+            "3",
+        ])
+        .unwrap();
+        assert_eq!(options.encounter, PathBuf::from("ambush.ron"));
+        assert_eq!(options.seed, Some(42));
+        assert_eq!(options.rounds_limit, 3);
+        assert_eq!(options.format, Format::Json);
+        assert_eq!(options.verbosity, Verbosity::Normal);
+
+        assert!(parse(&[]).is_err());
+        assert!(parse(&["ambush.ron", "--seed", "many"]).is_err());
+        assert!(parse(&["ambush.ron", "--format", "xml"]).is_err());
+        assert!(parse(&["ambush.ron", "--fast"]).is_err());
+        assert!(parse(&["ambush.ron", "--format", "csv"]).is_err());
+        let batch = parse(&["ambush.ron", "--runs", "500", "--format", "csv"]).unwrap();
+        assert_eq!(batch.runs, Some(500));
+        assert_eq!(batch.threads, 4);
+    }
+
+    #[test]
+    fn rounds_limit() {
+        let definition = EncounterDefinition::load(&PathBuf::from(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/encounters/kobold-ambush.ron"
+        )))
+        .unwrap();
+        let mut world = World::new();
+        definition.spawn(&mut world).unwrap();
+        let log = GameState::from_world(world).run(1);
+        assert!(!log.is_empty());
+        assert!(log.iter().all(|f| !matches!(f, Fact::RoundStart(_))));
+    }
+}